@@ -0,0 +1,83 @@
+//! Format HOCON files in place, keeping their comments and the order of their keys, or
+//! with `--check` only list the files that are not formatted, for example in a pre-commit
+//! hook. Files with substitutions or includes are left as they are.
+//!
+//! `cargo run --example hoconfmt -- --check conf/*.conf`
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use hocon::raw;
+use hocon::{Error, HoconLoader, RenderOptions, Separator};
+
+const USAGE: &str = "usage: hoconfmt [--check] [--colon] [--indent N] FILE...";
+
+/// The file formatted, or `None` when it can not be formatted without resolving it
+fn format(path: &str, options: RenderOptions) -> Result<Option<String>, Error> {
+    // rendering resolves the document, that must stay as written
+    let raw = raw::parse_file(path)?;
+    if !raw.substitutions().is_empty() || !raw.includes().is_empty() {
+        return Ok(None);
+    }
+    HoconLoader::new()
+        .no_system()
+        .keep_comments()
+        .load_file(path)?
+        .document()?
+        .to_hocon_string(options)
+        .map(Some)
+}
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut options = RenderOptions::default();
+    let mut files = vec![];
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--colon" => options.separator = Separator::Colon,
+            "--indent" => match args.next().and_then(|indent| indent.parse().ok()) {
+                Some(indent) => options.indent = indent,
+                None => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut success = true;
+    for file in files {
+        let original = fs::read_to_string(&file).unwrap_or_default();
+        match format(&file, options) {
+            Ok(Some(formatted)) if formatted == original => {}
+            Ok(Some(_)) if check => {
+                println!("{file} is not formatted");
+                success = false;
+            }
+            Ok(Some(formatted)) => {
+                if let Err(err) = fs::write(&file, formatted) {
+                    eprintln!("{file}: {err}");
+                    success = false;
+                }
+            }
+            Ok(None) => eprintln!("{file}: skipped, it has substitutions or includes"),
+            Err(err) => {
+                eprintln!("{file}: {err}");
+                success = false;
+            }
+        }
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
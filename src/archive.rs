@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+use crate::Result;
+
+const BLOCK_SIZE: usize = 512;
+
+/// Archive and entry of a path like `bundle.tar!defaults.conf`
+pub(crate) fn split_archive_path(path: &Path) -> Option<(PathBuf, String)> {
+    let (archive, entry) = path.to_str()?.split_once(".tar!")?;
+    Some((PathBuf::from(format!("{archive}.tar")), String::from(entry)))
+}
+
+/// Content of `entry` in the tar archive at `archive`, `None` if there is no such file in it
+pub(crate) fn read_tar_entry(archive: &Path, entry: &str) -> Result<Option<Vec<u8>>> {
+    let Some(entry) = normalize(entry) else {
+        return Ok(None);
+    };
+    let data = std::fs::read(archive)?;
+    let invalid = || Error::Io {
+        message: format!("invalid tar archive '{}'", archive.display()),
+    };
+
+    let mut offset = 0;
+    let mut long_name = None;
+    while offset + BLOCK_SIZE <= data.len() {
+        let header = &data[offset..offset + BLOCK_SIZE];
+        // the archive ends with empty blocks
+        if header.iter().all(|byte| *byte == 0) {
+            break;
+        }
+        let size = parse_octal(&header[124..136]).ok_or_else(invalid)?;
+        let start = offset + BLOCK_SIZE;
+        let content = data.get(start..start + size).ok_or_else(invalid)?;
+        let name = long_name.take().unwrap_or_else(|| header_name(header));
+        match header[156] {
+            // GNU tar stores long names in an entry before the file
+            b'L' => long_name = Some(field(content)),
+            b'0' | 0 if normalize(&name).as_ref() == Some(&entry) => {
+                return Ok(Some(content.to_vec()));
+            }
+            _ => {}
+        }
+        offset = start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+    Ok(None)
+}
+
+/// Name of the entry of a header, with the prefix of the `ustar` format
+fn header_name(header: &[u8]) -> String {
+    let name = field(&header[0..100]);
+    match field(&header[345..500]) {
+        prefix if &header[257..262] == b"ustar" && !prefix.is_empty() => {
+            format!("{prefix}/{name}")
+        }
+        _ => name,
+    }
+}
+
+/// Text of a field, ending at the first NUL
+fn field(bytes: &[u8]) -> String {
+    let end = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn parse_octal(bytes: &[u8]) -> Option<usize> {
+    let digits = field(bytes);
+    match digits.trim() {
+        "" => Some(0),
+        digits => usize::from_str_radix(digits, 8).ok(),
+    }
+}
+
+/// Path of an entry without `.` and `..`, `None` if it goes out of the archive
+fn normalize(entry: &str) -> Option<String> {
+    let mut components = vec![];
+    for component in entry.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+    Some(components.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_split_archive_paths() {
+        assert_eq!(
+            split_archive_path(Path::new("conf/bundle.tar!sub/defaults.conf")),
+            Some((
+                PathBuf::from("conf/bundle.tar"),
+                String::from("sub/defaults.conf")
+            ))
+        );
+        assert_eq!(split_archive_path(Path::new("conf/defaults.conf")), None);
+    }
+
+    #[test]
+    fn can_normalize_entries() {
+        assert_eq!(normalize("./sub/../a.conf"), Some(String::from("a.conf")));
+        assert_eq!(normalize("sub//a.conf"), Some(String::from("sub/a.conf")));
+        assert_eq!(normalize("../a.conf"), None);
+    }
+}
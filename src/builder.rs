@@ -0,0 +1,309 @@
+use linked_hash_map::LinkedHashMap;
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+
+/// Build an [`Hocon`](enum.Hocon.html) document from an inline literal.
+///
+/// Objects are written with `key = value`, `key: value`, or `key { ... }`, separated by
+/// commas. Keys are HOCON path expressions: `a.b = 1` sets `b` inside `a`, and quoted keys can
+/// contain dots. As in an HOCON document, an object set on a key that already holds an object
+/// is merged into it. Any other value is an expression converted with `Hocon::from`.
+///
+/// # Usage
+///
+/// ```rust
+/// # use hocon::{hocon, Hocon};
+/// let port = 8080;
+/// let doc = hocon!({
+///     a = 1,
+///     b { c = [1, 2] },
+///     b.d: "text",
+///     "e.f" = null,
+///     http.port = port,
+/// });
+///
+/// assert_eq!(doc["a"], Hocon::Integer(1));
+/// assert_eq!(doc["b"]["c"][1], Hocon::Integer(2));
+/// assert_eq!(doc["b"]["d"], Hocon::String(String::from("text")));
+/// assert_eq!(doc["e.f"], Hocon::Null);
+/// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
+/// ```
+///
+/// # Panics
+///
+/// If a key indexes an array past its end, as [`Hocon::set`](enum.Hocon.html#method.set)
+/// would return an error
+#[macro_export]
+macro_rules! hocon {
+    (null) => {
+        $crate::Hocon::Null
+    };
+    ([]) => {
+        $crate::Hocon::Array(::std::vec::Vec::new())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Hocon::Array($crate::hocon_internal!(@array [] () $($tt)+))
+    };
+    ({}) => {
+        $crate::Hocon::Hash(::std::default::Default::default())
+    };
+    ({ $($tt:tt)+ }) => {{
+        let mut object = $crate::Hocon::Hash(::std::default::Default::default());
+        $crate::hocon_internal!(@object object () $($tt)+);
+        object
+    }};
+    ($other:expr) => {
+        $crate::Hocon::from($other)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! hocon_internal {
+    // Arrays: collect the tokens of the current element until a comma
+    (@array [$($elems:expr,)*] ()) => {
+        ::std::vec![$($elems,)*]
+    };
+    (@array [$($elems:expr,)*] ($($current:tt)+)) => {
+        ::std::vec![$($elems,)* $crate::hocon!($($current)+),]
+    };
+    (@array [$($elems:expr,)*] ($($current:tt)+) , $($rest:tt)*) => {
+        $crate::hocon_internal!(@array [$($elems,)* $crate::hocon!($($current)+),] () $($rest)*)
+    };
+    (@array [$($elems:expr,)*] ($($current:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::hocon_internal!(@array [$($elems,)*] ($($current)* $next) $($rest)*)
+    };
+
+    // Objects: collect the tokens of the key until a separator or an object
+    (@object $object:ident ()) => {};
+    (@object $object:ident ($($key:tt)+) = $($rest:tt)*) => {
+        $crate::hocon_internal!(@value $object ($($key)+) () $($rest)*)
+    };
+    (@object $object:ident ($($key:tt)+) : $($rest:tt)*) => {
+        $crate::hocon_internal!(@value $object ($($key)+) () $($rest)*)
+    };
+    (@object $object:ident ($($key:tt)+) { $($inner:tt)* } , $($rest:tt)*) => {
+        $crate::hocon_internal!(@insert $object ($($key)+) ({ $($inner)* }));
+        $crate::hocon_internal!(@object $object () $($rest)*)
+    };
+    (@object $object:ident ($($key:tt)+) { $($inner:tt)* }) => {
+        $crate::hocon_internal!(@insert $object ($($key)+) ({ $($inner)* }))
+    };
+    (@object $object:ident ($($key:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::hocon_internal!(@object $object ($($key)* $next) $($rest)*)
+    };
+
+    // Objects: collect the tokens of the value until a comma
+    (@value $object:ident ($($key:tt)+) ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::hocon_internal!(@insert $object ($($key)+) ($($value)+));
+        $crate::hocon_internal!(@object $object () $($rest)*)
+    };
+    (@value $object:ident ($($key:tt)+) ($($value:tt)+)) => {
+        $crate::hocon_internal!(@insert $object ($($key)+) ($($value)+))
+    };
+    (@value $object:ident ($($key:tt)+) ($($value:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::hocon_internal!(@value $object ($($key)+) ($($value)* $next) $($rest)*)
+    };
+
+    (@insert $object:ident ($($key:tt)+) ($($value:tt)+)) => {
+        $object
+            .merge_at(
+                ::std::concat!($(::std::stringify!($key)),+),
+                $crate::hocon!($($value)+),
+            )
+            .expect("invalid key in hocon! literal")
+    };
+}
+
+/// Helper to build an HOCON document from code, without writing and parsing an HOCON string.
+///
+/// Paths are HOCON path expressions, and values are set as with
+/// [`Hocon::set`](enum.Hocon.html#method.set). The document can be used directly, or loaded
+/// in an [`HoconLoader`](struct.HoconLoader.html) with
+/// [`load_hocon`](struct.HoconLoader.html#method.load_hocon) to be merged with other documents.
+///
+/// # Usage
+///
+/// ```rust
+/// # use hocon::{Hocon, HoconBuilder, HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// let defaults = HoconBuilder::new()
+///     .put("db.url", "postgres://localhost")
+///     .put("db.pool", 4)
+///     .put_array("hosts", vec!["a.example.com", "b.example.com"])
+///     .build()?;
+///
+/// assert_eq!(defaults["db"]["pool"], Hocon::Integer(4));
+///
+/// let doc = HoconLoader::new()
+///     .load_hocon(defaults)
+///     .load_str(r#"{ db.pool: 8 }"#)?
+///     .hocon()?;
+///
+/// assert_eq!(doc["db"]["url"].as_string(), Some(String::from("postgres://localhost")));
+/// assert_eq!(doc["db"]["pool"], Hocon::Integer(8));
+/// assert_eq!(doc["hosts"][1].as_string(), Some(String::from("b.example.com")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HoconBuilder {
+    hocon: Hocon,
+    error: Option<Error>,
+}
+
+impl Default for HoconBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HoconBuilder {
+    /// New `HoconBuilder` with an empty document
+    pub fn new() -> Self {
+        Self {
+            hocon: Hocon::Hash(LinkedHashMap::new()),
+            error: None,
+        }
+    }
+
+    /// Set `value` at `path`, replacing the value already there
+    pub fn put(self, path: &str, value: impl Into<Hocon>) -> Self {
+        self.apply(|hocon| hocon.set(path, value.into()))
+    }
+
+    /// Set an array made of `values` at `path`, replacing the value already there
+    pub fn put_array<T: Into<Hocon>>(
+        self,
+        path: &str,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.put(
+            path,
+            Hocon::Array(values.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    /// Merge `value` into the value at `path`, as with
+    /// [`Hocon::merge_at`](enum.Hocon.html#method.merge_at)
+    pub fn merge(self, path: &str, value: impl Into<Hocon>) -> Self {
+        self.apply(|hocon| hocon.merge_at(path, value.into()))
+    }
+
+    fn apply(mut self, change: impl FnOnce(&mut Hocon) -> Result<()>) -> Self {
+        if self.error.is_none()
+            && let Err(err) = change(&mut self.hocon)
+        {
+            self.error = Some(err);
+        }
+        self
+    }
+
+    /// Build the document
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if a numeric key of a path
+    ///   was past the end of an array. This is the first error encountered
+    pub fn build(self) -> Result<Hocon> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.hocon),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_build_document() {
+        let doc = HoconBuilder::new()
+            .put("a.b", 1)
+            .put("a.c", 2.5)
+            .put(r#""d.e""#, true)
+            .put("f", Option::<i64>::None)
+            .put_array("l", vec![1, 2])
+            .put("l.2", "three")
+            .merge(
+                "a",
+                HoconBuilder::new()
+                    .put("g", "h")
+                    .build()
+                    .expect("during test"),
+            )
+            .build()
+            .expect("during test");
+
+        assert_eq!(doc["a"]["b"], Hocon::Integer(1));
+        assert_eq!(doc["a"]["c"], Hocon::Real(2.5));
+        assert_eq!(doc["a"]["g"], Hocon::String(String::from("h")));
+        assert_eq!(doc["d.e"], Hocon::Boolean(true));
+        assert_eq!(doc["f"], Hocon::Null);
+        assert_eq!(
+            doc["l"],
+            Hocon::Array(vec![
+                Hocon::Integer(1),
+                Hocon::Integer(2),
+                Hocon::String(String::from("three"))
+            ])
+        );
+    }
+
+    #[test]
+    fn can_build_with_macro() {
+        let three = 3;
+        let doc = crate::hocon!({
+            a = 1,
+            a-b: -2.5,
+            c { d = [1, [2, three], { e: true }], f {} },
+            c.g = "h",
+            c { i = false },
+            "j.k" = null,
+            l = [],
+            m = Some(4),
+        });
+
+        assert_eq!(
+            doc,
+            HoconBuilder::new()
+                .put("a", 1)
+                .put("a-b", -2.5)
+                .put_array(
+                    "c.d",
+                    vec![
+                        Hocon::Integer(1),
+                        Hocon::from(vec![2, 3]),
+                        HoconBuilder::new()
+                            .put("e", true)
+                            .build()
+                            .expect("during test"),
+                    ]
+                )
+                .put("c.f", Hocon::Hash(LinkedHashMap::new()))
+                .put("c.g", "h")
+                .put("c.i", false)
+                .put(r#""j.k""#, Hocon::Null)
+                .put_array("l", Vec::<Hocon>::new())
+                .put("m", 4)
+                .build()
+                .expect("during test")
+        );
+        assert_eq!(crate::hocon!(null), Hocon::Null);
+        assert_eq!(crate::hocon!([1, "a"])[1], Hocon::String(String::from("a")));
+    }
+
+    #[test]
+    fn keeps_first_error() {
+        let built = HoconBuilder::new()
+            .put_array("l", Vec::<i64>::new())
+            .put("l.1", 1)
+            .put("a", 2)
+            .build();
+
+        assert_eq!(built, Err(Error::InvalidKey));
+    }
+}
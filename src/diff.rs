@@ -0,0 +1,240 @@
+use crate::Hocon;
+
+/// A difference between two [`Hocon`](enum.Hocon.html) documents, as returned by
+/// [`Hocon::diff`](enum.Hocon.html#method.diff). Paths are HOCON path expressions, with
+/// elements of arrays identified by their index
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A value present only in the new document
+    Added {
+        /// Path of the value
+        path: String,
+        /// The new value
+        value: Hocon,
+    },
+    /// A value present only in the old document
+    Removed {
+        /// Path of the value
+        path: String,
+        /// The old value
+        value: Hocon,
+    },
+    /// A value that is different between the two documents
+    Changed {
+        /// Path of the value
+        path: String,
+        /// The old value
+        old: Hocon,
+        /// The new value
+        new: Hocon,
+    },
+}
+
+impl Change {
+    /// Path of the value that changed
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl Hocon {
+    /// List the differences to go from this document to `other`.
+    ///
+    /// Objects and arrays are compared recursively, so a change deep in a document is
+    /// reported at its own path. A value that changed type is reported as
+    /// [`Change::Changed`](enum.Change.html#variant.Changed) with both values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Change, Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let running = HoconLoader::new().load_str(r#"{ http.port: 80, hosts: [a, b] }"#)?.hocon()?;
+    /// let proposed = HoconLoader::new().load_str(r#"{ http.port: 8080, hosts: [a], tls: true }"#)?.hocon()?;
+    ///
+    /// assert_eq!(
+    ///     running.diff(&proposed),
+    ///     vec![
+    ///         Change::Changed {
+    ///             path: String::from("http.port"),
+    ///             old: Hocon::Integer(80),
+    ///             new: Hocon::Integer(8080),
+    ///         },
+    ///         Change::Removed {
+    ///             path: String::from("hosts.1"),
+    ///             value: Hocon::String(String::from("b")),
+    ///         },
+    ///         Change::Added {
+    ///             path: String::from("tls"),
+    ///             value: Hocon::Boolean(true),
+    ///         },
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &Hocon) -> Vec<Change> {
+        let mut changes = vec![];
+        diff_at(&mut vec![], self, other, &mut changes);
+        changes
+    }
+
+    /// Compare two documents, ignoring the order of keys in objects, and whether whole
+    /// numbers are represented as [`Hocon::Integer`](enum.Hocon.html#variant.Integer) or
+    /// [`Hocon::Real`](enum.Hocon.html#variant.Real). The order of elements in arrays is
+    /// still significant.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let expected = HoconLoader::new().load_str(r#"{ a: 1, b: [2, 3] }"#)?.hocon()?;
+    /// let loaded = HoconLoader::new().load_str(r#"{ b: [2.0, 3], a: 1.0 }"#)?.hocon()?;
+    ///
+    /// assert_ne!(expected, loaded);
+    /// assert!(expected.semantic_eq(&loaded));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn semantic_eq(&self, other: &Hocon) -> bool {
+        match (self, other) {
+            (Hocon::Hash(hash), Hocon::Hash(other_hash)) => {
+                hash.len() == other_hash.len()
+                    && hash.iter().all(|(key, value)| {
+                        other_hash
+                            .get(key)
+                            .is_some_and(|other_value| value.semantic_eq(other_value))
+                    })
+            }
+            (Hocon::Array(values), Hocon::Array(other_values)) => {
+                values.len() == other_values.len()
+                    && values
+                        .iter()
+                        .zip(other_values)
+                        .all(|(value, other_value)| value.semantic_eq(other_value))
+            }
+            (Hocon::Integer(i), Hocon::Real(f)) | (Hocon::Real(f), Hocon::Integer(i)) => {
+                // i64::MIN is a power of two, so the bounds are exact as floats
+                f.fract() == 0.0
+                    && *f >= i64::MIN as f64
+                    && *f < -(i64::MIN as f64)
+                    && *f as i64 == *i
+            }
+            (value, other_value) => value == other_value,
+        }
+    }
+}
+
+fn diff_at(path: &mut Vec<String>, old: &Hocon, new: &Hocon, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Hocon::Hash(old_hash), Hocon::Hash(new_hash)) => {
+            for (key, old_value) in old_hash {
+                path.push(key.clone());
+                match new_hash.get(key) {
+                    Some(new_value) => diff_at(path, old_value, new_value, changes),
+                    None => changes.push(Change::Removed {
+                        path: crate::helper::join_path(path),
+                        value: old_value.clone(),
+                    }),
+                }
+                path.pop();
+            }
+            for (key, new_value) in new_hash {
+                if !old_hash.contains_key(key) {
+                    path.push(key.clone());
+                    changes.push(Change::Added {
+                        path: crate::helper::join_path(path),
+                        value: new_value.clone(),
+                    });
+                    path.pop();
+                }
+            }
+        }
+        (Hocon::Array(old_values), Hocon::Array(new_values)) => {
+            for idx in 0..old_values.len().max(new_values.len()) {
+                path.push(idx.to_string());
+                match (old_values.get(idx), new_values.get(idx)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_at(path, old_value, new_value, changes)
+                    }
+                    (Some(old_value), None) => changes.push(Change::Removed {
+                        path: crate::helper::join_path(path),
+                        value: old_value.clone(),
+                    }),
+                    (None, Some(new_value)) => changes.push(Change::Added {
+                        path: crate::helper::join_path(path),
+                        value: new_value.clone(),
+                    }),
+                    (None, None) => unreachable!(),
+                }
+                path.pop();
+            }
+        }
+        (old, new) if old != new => changes.push(Change::Changed {
+            path: crate::helper::join_path(path),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compare_semantically() {
+        let value = crate::hocon!({ a { b = 1, c = [1.0, 2] }, d = true });
+
+        assert!(value.semantic_eq(&crate::hocon!({ d = true, a { c = [1, 2.0], b = 1.0 } })));
+        assert!(!value.semantic_eq(&crate::hocon!({ a { b = 1, c = [2, 1] }, d = true })));
+        assert!(!value.semantic_eq(&crate::hocon!({ a { b = 1.5, c = [1, 2] }, d = true })));
+        assert!(!value.semantic_eq(&crate::hocon!({ a { b = 1, c = [1, 2] } })));
+        assert!(!value.semantic_eq(&crate::hocon!({ a { b = 1, c = [1, 2] }, d = true, e = 1 })));
+        assert!(!Hocon::Integer(i64::MAX).semantic_eq(&Hocon::Real(i64::MAX as f64)));
+        assert!(Hocon::Null.semantic_eq(&Hocon::Null));
+    }
+
+    #[test]
+    fn can_diff() {
+        let old = crate::hocon!({ a { b = 1, "c.d" = 2 }, e = [1, { f = 2 }], g = "h", i = 3 });
+        let new =
+            crate::hocon!({ a { b = 1, "c.d" = 3 }, e = [1, { f = 2, j = 4 }, 5], g = { k = 6 } });
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                Change::Changed {
+                    path: String::from(r#"a."c.d""#),
+                    old: Hocon::Integer(2),
+                    new: Hocon::Integer(3),
+                },
+                Change::Added {
+                    path: String::from("e.1.j"),
+                    value: Hocon::Integer(4),
+                },
+                Change::Added {
+                    path: String::from("e.2"),
+                    value: Hocon::Integer(5),
+                },
+                Change::Changed {
+                    path: String::from("g"),
+                    old: Hocon::String(String::from("h")),
+                    new: crate::hocon!({ k = 6 }),
+                },
+                Change::Removed {
+                    path: String::from("i"),
+                    value: Hocon::Integer(3),
+                },
+            ]
+        );
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(Hocon::Integer(1).diff(&Hocon::Integer(2))[0].path(), "");
+    }
+}
@@ -0,0 +1,509 @@
+//! Loaded documents with the details of how they were built
+//!
+//! When tracing is enabled with [`HoconLoader::trace`](../struct.HoconLoader.html#method.trace),
+//! [`HoconLoader::document`](../struct.HoconLoader.html#method.document) returns the
+//! [`Hocon`](../enum.Hocon.html) document along with each step taken to build it: every value
+//! set or overridden while merging the loaded sources, and every substitution resolved.
+//!
+//! ```rust
+//! use hocon::HoconLoader;
+//! use hocon::document::{MergeAction, Resolution, Source};
+//! # use hocon::Error;
+//!
+//! # fn main() -> Result<(), Error> {
+//! let document = HoconLoader::new()
+//!     .no_system()
+//!     .trace()
+//!     .load_str(r#"{ a: 1, b: ${a} }"#)?
+//!     .load_str(r#"{ a: 2 }"#)?
+//!     .document()?;
+//!
+//! let a = document.trace_for("a");
+//! assert_eq!(a[0].action, MergeAction::Set);
+//! assert_eq!(a[1].action, MergeAction::Override);
+//! assert_eq!(a[1].source, Some(Source::Str));
+//!
+//! let b = document.trace_for("b");
+//! assert_eq!(
+//!     b[0].action,
+//!     MergeAction::Resolve {
+//!         substitution: String::from("a"),
+//!         resolution: Resolution::Document,
+//!     }
+//! );
+//! assert_eq!(b[1].action, MergeAction::Set);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Feature
+//!
+//! With feature `tracing`, each step is also emitted as a `tracing` event at the `DEBUG`
+//! level as it is recorded.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use linked_hash_map::LinkedHashMap;
+
+use crate::Hocon;
+use crate::internals::HoconInternal;
+
+/// Where a document was loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A string loaded with [`HoconLoader::load_str`](../struct.HoconLoader.html#method.load_str),
+    /// or bytes loaded with [`HoconLoader::load_bytes`](../struct.HoconLoader.html#method.load_bytes)
+    Str,
+    /// A file, with its full path
+    File(PathBuf),
+    /// An URL
+    Url(String),
+    /// The standard input, loaded with
+    /// [`HoconLoader::load_stdin`](../struct.HoconLoader.html#method.load_stdin)
+    Stdin,
+    /// A reader, loaded with
+    /// [`HoconLoader::load_reader`](../struct.HoconLoader.html#method.load_reader)
+    Reader,
+    /// A key-value store, with the prefix of the keys loaded with
+    /// [`HoconLoader::load_remote`](../struct.HoconLoader.html#method.load_remote)
+    Remote(String),
+    /// Overrides loaded with
+    /// [`HoconLoader::load_args`](../struct.HoconLoader.html#method.load_args)
+    Args,
+    /// Overrides read from environment variables with
+    /// [`HoconLoader::env_overrides`](../struct.HoconLoader.html#method.env_overrides)
+    Env,
+    /// Values given to
+    /// [`HoconLoader::with_overrides`](../struct.HoconLoader.html#method.with_overrides)
+    Overrides,
+    /// A document built from code, loaded with
+    /// [`HoconLoader::load_hocon`](../struct.HoconLoader.html#method.load_hocon)
+    Hocon,
+    /// Documents merged with
+    /// [`HoconLoader::resolve_partially`](../struct.HoconLoader.html#method.resolve_partially)
+    Resolved,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Str => write!(f, "string"),
+            Source::File(path) => write!(f, "file '{}'", path.display()),
+            Source::Url(url) => write!(f, "url '{}'", url),
+            Source::Stdin => write!(f, "standard input"),
+            Source::Reader => write!(f, "reader"),
+            Source::Remote(prefix) => write!(f, "remote source '{}'", prefix),
+            Source::Args => write!(f, "arguments"),
+            Source::Env => write!(f, "environment variables"),
+            Source::Overrides => write!(f, "overrides"),
+            Source::Hocon => write!(f, "document built from code"),
+            Source::Resolved => write!(f, "partially resolved documents"),
+        }
+    }
+}
+
+/// How a substitution was resolved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    /// With a value from the document
+    Document,
+    /// With an environment variable
+    Environment,
+    /// With a system property, set with
+    /// [`HoconLoader::system_properties`](../struct.HoconLoader.html#method.system_properties)
+    SystemProperty,
+    /// With the content of a file, enabled with
+    /// [`HoconLoader::file_substitutions`](../struct.HoconLoader.html#method.file_substitutions)
+    File,
+    /// An optional substitution that was not found, keeping the previous value of the key,
+    /// or using its inline default with
+    /// [`HoconLoader::substitution_defaults`](../struct.HoconLoader.html#method.substitution_defaults)
+    PreviousValue,
+    /// The substitution could not be resolved
+    Unresolved,
+}
+
+/// Where the value at a path of a document comes from, as returned by
+/// [`HoconDocument::origin`](struct.HoconDocument.html#method.origin)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    /// Set by a loaded document
+    Document(Source),
+    /// Resolved with an environment variable, with the name of the substitution
+    EnvVar(String),
+    /// Resolved with a system property, with the name of the substitution
+    SystemProperty(String),
+    /// Resolved with the content of a file, with its path
+    File(String),
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Document(source) => write!(f, "from {}", source),
+            Origin::EnvVar(name) => write!(f, "from env var {}", name),
+            Origin::SystemProperty(name) => write!(f, "from system property {}", name),
+            Origin::File(path) => write!(f, "from the content of file '{}'", path),
+        }
+    }
+}
+
+/// An action taken while building a document
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeAction {
+    /// A value was set on a path that did not have one yet
+    Set,
+    /// A value replaced the one previously set on this path
+    Override,
+    /// A substitution was resolved
+    Resolve {
+        /// Path of the substitution target
+        substitution: String,
+        /// Where the value came from
+        resolution: Resolution,
+    },
+}
+
+/// A step taken while building a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeStep {
+    /// Path of the value, with keys separated by `.`
+    pub path: String,
+    /// What happened to the value
+    pub action: MergeAction,
+    /// The source of the value that won, when known
+    pub source: Option<Source>,
+}
+
+/// An optional substitution that was not found while building a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedOptional {
+    /// Path of the value where the substitution was, with keys separated by `.`
+    pub path: String,
+    /// Name of the substitution, like `PORT` for `${?PORT}`
+    pub substitution: String,
+}
+
+/// A file or an URL read to build a document, with the documents it included
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedSource {
+    /// Where the document was read from, a [`Source::File`](enum.Source.html#variant.File)
+    /// or a [`Source::Url`](enum.Source.html#variant.Url)
+    pub source: Source,
+    /// Files and URLs included by this document
+    pub includes: Vec<LoadedSource>,
+}
+
+impl LoadedSource {
+    /// This source followed by all the sources it included, directly or not
+    pub fn flatten(&self) -> Vec<&Source> {
+        std::iter::once(&self.source)
+            .chain(self.includes.iter().flat_map(LoadedSource::flatten))
+            .collect()
+    }
+}
+
+/// Comments written around a field of a document, kept with
+/// [`HoconLoader::keep_comments`](../struct.HoconLoader.html#method.keep_comments). Their text
+/// is what follows the `#` or `//` that starts them
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Comments {
+    /// Comments on the lines before the field
+    pub leading: Vec<String>,
+    /// Comment after the value of the field, on the same line
+    pub trailing: Option<String>,
+}
+
+/// An HOCON document with the trace of the steps taken to build it
+#[derive(Debug, Clone)]
+pub struct HoconDocument {
+    pub(crate) hocon: Hocon,
+    pub(crate) trace: Vec<MergeStep>,
+    pub(crate) unresolved_optionals: Vec<UnresolvedOptional>,
+    pub(crate) sources: Vec<LoadedSource>,
+    pub(crate) comments: LinkedHashMap<String, Comments>,
+}
+
+impl HoconDocument {
+    /// The resolved document
+    pub fn hocon(&self) -> &Hocon {
+        &self.hocon
+    }
+
+    /// Consume the document, returning the resolved [`Hocon`](../enum.Hocon.html)
+    pub fn into_hocon(self) -> Hocon {
+        self.hocon
+    }
+
+    /// Steps taken to build the document, in order. This is empty if tracing was not enabled
+    pub fn trace(&self) -> &[MergeStep] {
+        &self.trace
+    }
+
+    /// Steps that affected the value at `path`
+    pub fn trace_for(&self, path: &str) -> Vec<&MergeStep> {
+        self.trace.iter().filter(|step| step.path == path).collect()
+    }
+
+    /// Optional substitutions that were not found in the documents, the system properties
+    /// or the environment, in the order they were met. Their value was left out, or kept
+    /// its previous value when there was one. This does not need tracing to be enabled
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// # use hocon::document::UnresolvedOptional;
+    /// # use hocon::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .load_str(r#"{ server.port: 8080, server.port: ${?HOCON_EXAMPLE_UNSET_PORT} }"#)?
+    ///     .document()?;
+    ///
+    /// assert_eq!(
+    ///     document.unresolved_optionals(),
+    ///     &[UnresolvedOptional {
+    ///         path: String::from("server.port"),
+    ///         substitution: String::from("HOCON_EXAMPLE_UNSET_PORT"),
+    ///     }]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unresolved_optionals(&self) -> &[UnresolvedOptional] {
+        &self.unresolved_optionals
+    }
+
+    /// Files and URLs loaded to build the document, in the order they were loaded, each with
+    /// the documents it included. These are the files to watch to reload the document when
+    /// they change. This does not need tracing to be enabled
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// # use hocon::document::Source;
+    /// # use hocon::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .load_file("tests/data/include_cache/main.conf")?
+    ///     .document()?;
+    ///
+    /// let main = &document.sources()[0];
+    /// assert_eq!(main.includes.len(), 2);
+    ///
+    /// let mut files = main
+    ///     .flatten()
+    ///     .into_iter()
+    ///     .filter_map(|source| match source {
+    ///         Source::File(path) => path.file_name()?.to_str(),
+    ///         _ => None,
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// files.sort();
+    /// files.dedup();
+    /// assert_eq!(files, ["a.conf", "b.conf", "common.conf", "main.conf"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sources(&self) -> &[LoadedSource] {
+        &self.sources
+    }
+
+    /// Where the value at `path` comes from: the environment variable, system property or
+    /// file that resolved its substitution, or else the last document that set it. This is
+    /// `None` if tracing was not enabled or if there is no value at `path`
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// # use hocon::document::Origin;
+    /// # use hocon::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("HOCON_EXAMPLE_PORT", "9090") };
+    /// let document = HoconLoader::new()
+    ///     .trace()
+    ///     .load_str(r#"{ server.port: 8080, server.port: ${?HOCON_EXAMPLE_PORT}, server.host: localhost }"#)?
+    ///     .document()?;
+    ///
+    /// let origin = document.origin("server.port");
+    /// assert_eq!(origin, Some(Origin::EnvVar(String::from("HOCON_EXAMPLE_PORT"))));
+    /// assert_eq!(
+    ///     format!("server.port = {} ({})", document.hocon()["server"]["port"].as_string().unwrap(), origin.unwrap()),
+    ///     "server.port = 9090 (from env var HOCON_EXAMPLE_PORT)"
+    /// );
+    /// assert_eq!(document.origin("server.host").unwrap().to_string(), "from string");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn origin(&self, path: &str) -> Option<Origin> {
+        let keys = crate::helper::split_path(path);
+        if let Hocon::BadValue(_) = self.hocon.at_path(&keys) {
+            return None;
+        }
+        let steps = self.trace_for(path);
+        // substitutions are resolved once the documents are merged, after any value was set
+        let set_at = steps
+            .iter()
+            .rposition(|step| matches!(step.action, MergeAction::Set | MergeAction::Override));
+        let resolved = steps
+            .iter()
+            .skip(set_at.map(|i| i + 1).unwrap_or(0))
+            .rev()
+            .find_map(|step| match &step.action {
+                MergeAction::Resolve {
+                    substitution,
+                    resolution: Resolution::Environment,
+                } => Some(Origin::EnvVar(substitution.clone())),
+                MergeAction::Resolve {
+                    substitution,
+                    resolution: Resolution::SystemProperty,
+                } => Some(Origin::SystemProperty(substitution.clone())),
+                MergeAction::Resolve {
+                    substitution,
+                    resolution: Resolution::File,
+                } => Some(Origin::File(String::from(
+                    substitution.strip_prefix("file:").unwrap_or(substitution),
+                ))),
+                _ => None,
+            });
+        resolved.or_else(|| self.override_chain(path).pop().map(Origin::Document))
+    }
+
+    /// Sources that set the value at `path` or one of its parents, in the order they were
+    /// merged. The last one is the source of the value in the document. This is empty if
+    /// tracing was not enabled
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// # use hocon::document::Source;
+    /// # use hocon::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .trace()
+    ///     .load_file("tests/data/layers/application.conf")?
+    ///     .load_args(vec!["http.port=9000"])?
+    ///     .with_reference("tests/data/layers/reference.conf")?
+    ///     .document()?;
+    ///
+    /// let chain = document.override_chain("http.port");
+    /// assert_eq!(chain.len(), 3);
+    /// assert!(matches!(&chain[0], Source::File(path) if path.ends_with("reference.conf")));
+    /// assert!(matches!(&chain[1], Source::File(path) if path.ends_with("application.conf")));
+    /// assert_eq!(chain[2], Source::Args);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn override_chain(&self, path: &str) -> Vec<Source> {
+        let keys = crate::helper::split_path(path);
+        self.trace
+            .iter()
+            .filter(|step| matches!(step.action, MergeAction::Set | MergeAction::Override))
+            .filter(|step| keys.starts_with(&crate::helper::split_path(&step.path)))
+            .filter_map(|step| step.source.clone())
+            .collect()
+    }
+
+    /// Comments written around the field at `path`, when they were kept with
+    /// [`HoconLoader::keep_comments`](../struct.HoconLoader.html#method.keep_comments). When
+    /// several documents have comments for the field, these are the ones of the last
+    /// document merged
+    pub fn comments(&self, path: &str) -> Option<&Comments> {
+        self.comments.get(path)
+    }
+}
+
+/// A loaded document, kept apart from the others until they are merged
+#[derive(Debug, Clone)]
+pub(crate) struct Layer {
+    pub(crate) source: Source,
+    pub(crate) priority: Priority,
+    pub(crate) internal: HoconInternal,
+    pub(crate) includes: Vec<LoadedSource>,
+    /// Comments of the document, by path, when they were kept
+    pub(crate) comments: Vec<(String, Comments)>,
+}
+
+impl Layer {
+    /// Files and URLs read for this layer
+    pub(crate) fn sources(&self) -> Vec<LoadedSource> {
+        match self.source {
+            Source::File(_) | Source::Url(_) => vec![LoadedSource {
+                source: self.source.clone(),
+                includes: self.includes.clone(),
+            }],
+            _ => self.includes.clone(),
+        }
+    }
+}
+
+/// Comments of the layers by path, each layer replacing the comments of the previous ones
+pub(crate) fn merge_comments(layers: &[Layer]) -> LinkedHashMap<String, Comments> {
+    let mut merged = LinkedHashMap::new();
+    for (path, comments) in layers.iter().flat_map(|layer| layer.comments.iter()) {
+        merged.insert(path.clone(), comments.clone());
+    }
+    merged
+}
+
+/// Layers are merged by ascending priority, then in the order they were loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Priority {
+    /// Defaults, like a library `reference.conf`. Their substitutions are resolved against
+    /// the whole merged document
+    Defaults,
+    /// Documents loaded by the application
+    Document,
+    /// Overrides, like command line arguments, that win over any document
+    Overrides,
+}
+
+#[derive(Debug, Default)]
+struct TraceState {
+    steps: Vec<MergeStep>,
+    current_path: Vec<String>,
+}
+
+/// Collects the steps while a document is built. Clones share the same steps
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tracer(Rc<RefCell<TraceState>>);
+
+impl Tracer {
+    pub(crate) fn record(&self, path: String, action: MergeAction, source: Option<Source>) {
+        let step = MergeStep {
+            path,
+            action,
+            source,
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            path = %step.path,
+            action = ?step.action,
+            source = ?step.source,
+            "hocon merge step"
+        );
+        self.0.borrow_mut().steps.push(step);
+    }
+
+    pub(crate) fn record_resolution(&self, substitution: String, resolution: Resolution) {
+        let path = crate::helper::join_path(&self.0.borrow().current_path);
+        self.record(
+            path,
+            MergeAction::Resolve {
+                substitution,
+                resolution,
+            },
+            None,
+        );
+    }
+
+    pub(crate) fn enter(&self, key: String) {
+        self.0.borrow_mut().current_path.push(key);
+    }
+
+    pub(crate) fn leave(&self) {
+        self.0.borrow_mut().current_path.pop();
+    }
+
+    pub(crate) fn steps(&self) -> Vec<MergeStep> {
+        self.0.borrow().steps.clone()
+    }
+}
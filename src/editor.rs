@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+use crate::render::{BadValues, RenderOptions, key_string};
+use crate::syntax;
+
+/// Edit the values of an HOCON file, changing only the text of the values set and leaving the
+/// rest of the file as it was written, with its comments and formatting
+///
+/// ```rust
+/// # use hocon::{HoconEditor, Error};
+/// # fn main() -> Result<(), Error> {
+/// # let path = std::env::temp_dir().join("hocon_editor_example.conf");
+/// # std::fs::write(&path, "server {\n  host: localhost # for tests\n  port: 8080\n}\n")
+/// #     .map_err(|err| Error::Io { message: err.to_string() })?;
+/// HoconEditor::open(&path)?
+///     .set("server.port", 8081)?
+///     .set("server.tls", true)?
+///     .save()?;
+///
+/// assert_eq!(
+///     std::fs::read_to_string(&path).unwrap(),
+///     "server {\n  host: localhost # for tests\n  port: 8081\n  tls = true\n}\n"
+/// );
+/// # std::fs::remove_file(&path).ok();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HoconEditor {
+    path: PathBuf,
+    text: String,
+}
+
+impl HoconEditor {
+    /// Open the HOCON file at `path` to edit it
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) if the file can not be read
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = std::fs::read_to_string(&path).map_err(|_| Error::File {
+            path: path.display().to_string(),
+        })?;
+        Ok(Self { path, text })
+    }
+
+    /// Set `value` at `path`, with keys separated by `.`. The last value written for the
+    /// path is replaced. When the path has no value, it is added at the end of the deepest
+    /// object written in braces that contains it, or at the end of the document
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the file is not valid HOCON once
+    ///   edited
+    /// * the error of `value`, if it is a [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue)
+    pub fn set<V: Into<Hocon>>(mut self, path: &str, value: V) -> Result<Self> {
+        let keys = crate::helper::split_path(path);
+        let value = value.into().to_hocon_string(RenderOptions {
+            root_braces: true,
+            bad_values: BadValues::Error,
+            ..RenderOptions::default()
+        })?;
+        let fields = syntax::fields(&self.text);
+
+        if let Some(field) = fields.iter().rev().find(|field| field.path == keys) {
+            let indent = self.indent_at(field.value.start);
+            let value = indented(&value, &indent);
+            self.text.replace_range(field.value.clone(), &value);
+        } else if let Some(parent) = fields
+            .iter()
+            .filter(|field| field.path.len() < keys.len() && keys.starts_with(&field.path))
+            .filter(|field| self.text[field.value.clone()].ends_with('}'))
+            .max_by_key(|field| field.path.len())
+        {
+            let key = keys[parent.path.len()..]
+                .iter()
+                .map(|key| key_string(key))
+                .collect::<Vec<_>>()
+                .join(".");
+            let close = parent.value.end - 1;
+            let line_start = self.text[..close]
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1);
+            if self.text[line_start..close].trim().is_empty() && line_start > parent.value.start {
+                // on its own line, indented as the other fields of the object
+                let indent = fields
+                    .iter()
+                    .rev()
+                    .find(|field| {
+                        field.path.len() == parent.path.len() + 1
+                            && field.path.starts_with(&parent.path)
+                            && parent.value.contains(&field.value.start)
+                    })
+                    .map(|field| self.indent_at(field.value.start))
+                    .unwrap_or_else(|| format!("{}  ", self.indent_at(close)));
+                let value = indented(&value, &indent);
+                self.text
+                    .insert_str(line_start, &format!("{indent}{key} = {value}\n"));
+            } else {
+                // inside braces on a single line
+                let content = self.text[parent.value.start + 1..close].trim_end();
+                let separator = if content.trim().is_empty() { " " } else { ", " };
+                let at = parent.value.start + 1 + content.len();
+                let value = indented(&value, &self.indent_at(close));
+                self.text
+                    .replace_range(at..close, &format!("{separator}{key} = {value} "));
+            }
+        } else {
+            let key = keys
+                .iter()
+                .map(|key| key_string(key))
+                .collect::<Vec<_>>()
+                .join(".");
+            if !self.text.is_empty() && !self.text.ends_with('\n') {
+                self.text.push('\n');
+            }
+            let value = indented(&value, "");
+            self.text.push_str(&format!("{key} = {value}\n"));
+        }
+
+        crate::raw::parse(&self.text)?;
+        Ok(self)
+    }
+
+    /// The content of the file, with the edits
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Write the file with the edits
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`](enum.Error.html#variant.Io) if the file can not be written
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, &self.text).map_err(|err| Error::Io {
+            message: err.to_string(),
+        })
+    }
+
+    /// Indentation of the line at `position`
+    fn indent_at(&self, position: usize) -> String {
+        let line_start = self.text[..position]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        self.text[line_start..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect()
+    }
+}
+
+/// Rendered `value` without its final new line, its other lines indented with `indent`
+fn indented(value: &str, indent: &str) -> String {
+    value.trim_end().replace('\n', &format!("\n{indent}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(text: &str, path: &str, value: Hocon) -> Result<String> {
+        HoconEditor {
+            path: PathBuf::new(),
+            text: String::from(text),
+        }
+        .set(path, value)
+        .map(|editor| editor.text)
+    }
+
+    #[test]
+    fn can_replace_values() {
+        let text =
+            "# header\na {\n  b: 1 // one\n  c = [1, 2]\n}\na.b = \"two\" # last\nd = { e: 1 }\n";
+        assert_eq!(
+            edit(text, "a.b", Hocon::Integer(3)),
+            Ok(String::from(
+                "# header\na {\n  b: 1 // one\n  c = [1, 2]\n}\na.b = 3 # last\nd = { e: 1 }\n"
+            ))
+        );
+        assert_eq!(
+            edit(text, "a.c", crate::hocon!([{ f = 1 }])),
+            Ok(String::from(
+                "# header\na {\n  b: 1 // one\n  c = [\n    {\n      f = 1\n    }\n  ]\n}\na.b = \"two\" # last\nd = { e: 1 }\n"
+            ))
+        );
+        assert_eq!(
+            edit(text, "d", Hocon::String(String::from("x y"))),
+            Ok(String::from(
+                "# header\na {\n  b: 1 // one\n  c = [1, 2]\n}\na.b = \"two\" # last\nd = \"x y\"\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn can_add_values() {
+        let text = "a {\n  b: 1\n}\nd = { e: 1 }\nf {}";
+        assert_eq!(
+            edit(text, "a.c.d", Hocon::Boolean(true)),
+            Ok(String::from(
+                "a {\n  b: 1\n  c.d = true\n}\nd = { e: 1 }\nf {}"
+            ))
+        );
+        assert_eq!(
+            edit(text, "d.g", Hocon::Integer(2)),
+            Ok(String::from("a {\n  b: 1\n}\nd = { e: 1, g = 2 }\nf {}"))
+        );
+        assert_eq!(
+            edit(text, "f.h", Hocon::Integer(3)),
+            Ok(String::from("a {\n  b: 1\n}\nd = { e: 1 }\nf { h = 3 }"))
+        );
+        assert_eq!(
+            edit(text, "i.\"j.k\"", Hocon::Null),
+            Ok(String::from(
+                "a {\n  b: 1\n}\nd = { e: 1 }\nf {}\ni.\"j.k\" = null\n"
+            ))
+        );
+        assert_eq!(
+            edit("{\n  a: 1\n}\n", "b", Hocon::Integer(2)),
+            Ok(String::from("{\n  a: 1\n  b = 2\n}\n"))
+        );
+    }
+
+    #[test]
+    fn can_not_set_bad_values() {
+        assert_eq!(
+            edit("a = 1\n", "a", Hocon::BadValue(Error::MissingKey)),
+            Err(Error::MissingKey)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Encoding;
+use crate::Error;
+use crate::Result;
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16_LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16_BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// Text of the document `document`, decoded as UTF-16 or UTF-8 if it starts with their byte
+/// order mark and in `encoding` otherwise, without its byte order mark
+pub(crate) fn decode(mut bytes: Vec<u8>, document: &str, encoding: Encoding) -> Result<String> {
+    let invalid = |message: String| Error::Encoding {
+        document: String::from(document),
+        message,
+    };
+    if let Some(content) = bytes.strip_prefix(UTF16_LE_BOM) {
+        decode_utf16(content, u16::from_le_bytes).map_err(invalid)
+    } else if let Some(content) = bytes.strip_prefix(UTF16_BE_BOM) {
+        decode_utf16(content, u16::from_be_bytes).map_err(invalid)
+    } else if bytes.starts_with(UTF8_BOM) || encoding == Encoding::Utf8 {
+        if bytes.starts_with(UTF8_BOM) {
+            bytes.drain(..UTF8_BOM.len());
+        }
+        String::from_utf8(bytes).map_err(|error| {
+            invalid(format!(
+                "invalid UTF-8 at byte {}",
+                error.utf8_error().valid_up_to()
+            ))
+        })
+    } else {
+        // every byte is the code point of its character in ISO-8859-1
+        Ok(bytes.into_iter().map(char::from).collect())
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> std::result::Result<String, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(String::from("truncated UTF-16, odd number of bytes"));
+    }
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]));
+    let mut text = String::with_capacity(bytes.len() / 2);
+    // offsets count the byte order mark, as in the original bytes
+    let mut offset = UTF16_LE_BOM.len();
+    for c in char::decode_utf16(units) {
+        let c = c.map_err(|_| format!("invalid UTF-16 at byte {offset}"))?;
+        offset += c.len_utf16() * 2;
+        text.push(c);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        text.encode_utf16().flat_map(to_bytes).collect()
+    }
+
+    #[test]
+    fn can_decode_with_byte_order_marks() {
+        assert_eq!(
+            decode(b"a = 1".to_vec(), "doc", Encoding::Utf8),
+            Ok(String::from("a = 1"))
+        );
+        assert_eq!(
+            decode(
+                [UTF8_BOM, "a = é".as_bytes()].concat(),
+                "doc",
+                Encoding::Utf8
+            ),
+            Ok(String::from("a = é"))
+        );
+        assert_eq!(
+            decode(
+                utf16("\u{feff}a = é", u16::to_le_bytes),
+                "doc",
+                Encoding::Utf8
+            ),
+            Ok(String::from("a = é"))
+        );
+        assert_eq!(
+            decode(
+                utf16("\u{feff}a = 𝄞", u16::to_be_bytes),
+                "doc",
+                Encoding::Utf8
+            ),
+            Ok(String::from("a = 𝄞"))
+        );
+    }
+
+    #[test]
+    fn can_decode_latin1() {
+        assert_eq!(
+            decode(vec![b'a', b'=', 0xe9], "doc", Encoding::Latin1),
+            Ok(String::from("a=é"))
+        );
+        // a byte order mark wins over the encoding
+        assert_eq!(
+            decode(
+                [UTF8_BOM, "a=é".as_bytes()].concat(),
+                "doc",
+                Encoding::Latin1
+            ),
+            Ok(String::from("a=é"))
+        );
+    }
+
+    #[test]
+    fn can_not_decode_invalid_data() {
+        assert_eq!(
+            decode(vec![b'a', b'=', 0xff], "doc", Encoding::Utf8),
+            Err(Error::Encoding {
+                document: String::from("doc"),
+                message: String::from("invalid UTF-8 at byte 2"),
+            })
+        );
+        assert!(matches!(
+            decode(vec![0xff, 0xfe, b'a'], "doc", Encoding::Utf8),
+            Err(Error::Encoding { message, .. }) if message.contains("odd number of bytes")
+        ));
+        // a lone surrogate
+        assert!(matches!(
+            decode(vec![0xff, 0xfe, b'a', 0, 0x00, 0xd8], "doc", Encoding::Utf8),
+            Err(Error::Encoding { message, .. }) if message == "invalid UTF-16 at byte 4"
+        ));
+    }
+}
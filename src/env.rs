@@ -0,0 +1,268 @@
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+
+/// How the keys of a path are written from the words of an environment variable name
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyCase {
+    /// `MAX_CONNECTIONS` is read as `max_connections`
+    Lower,
+    /// `MAX_CONNECTIONS` is read as `max-connections`
+    Kebab,
+    /// `MAX_CONNECTIONS` is read as `maxConnections`
+    Camel,
+    /// `MAX_CONNECTIONS` is kept as is
+    Preserve,
+}
+
+/// Configuration of the overrides read from environment variables by
+/// [`HoconLoader::env_overrides_with`](struct.HoconLoader.html#method.env_overrides_with).
+///
+/// A variable is used if its name starts with the prefix followed by `_`. The rest of the
+/// name is split on the separator, `__` by default, to get the keys of the path, so with
+/// prefix `MYAPP`, `MYAPP_SERVER__MAX_CONNECTIONS` overrides `server.max_connections`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvOverrides {
+    prefix: String,
+    separator: String,
+    key_case: KeyCase,
+}
+
+impl EnvOverrides {
+    /// Overrides from environment variables starting with `prefix`
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: format!("{}_", prefix),
+            separator: String::from("__"),
+            key_case: KeyCase::Lower,
+        }
+    }
+
+    /// Set the separator between the keys of a path, by default `__`
+    pub fn separator(self, separator: &str) -> Self {
+        Self {
+            separator: String::from(separator),
+            ..self
+        }
+    }
+
+    /// Set how keys are written, by default [`KeyCase::Lower`](enum.KeyCase.html#variant.Lower)
+    pub fn key_case(self, key_case: KeyCase) -> Self {
+        Self { key_case, ..self }
+    }
+
+    /// Keys of the path overridden by variable `name`, if it is an override
+    pub(crate) fn path(&self, name: &str) -> Option<Vec<String>> {
+        let keys = name
+            .strip_prefix(&self.prefix)?
+            .split(self.separator.as_str())
+            .map(|key| self.convert_case(key))
+            .collect::<Vec<_>>();
+        if keys.iter().any(String::is_empty) {
+            None
+        } else {
+            Some(keys)
+        }
+    }
+
+    fn convert_case(&self, key: &str) -> String {
+        match self.key_case {
+            KeyCase::Lower => key.to_lowercase(),
+            KeyCase::Kebab => key.to_lowercase().replace('_', "-"),
+            KeyCase::Camel => key
+                .split('_')
+                .filter(|word| !word.is_empty())
+                .enumerate()
+                .map(|(i, word)| {
+                    let word = word.to_lowercase();
+                    match (i, word.chars().next()) {
+                        (0, _) | (_, None) => word,
+                        (_, Some(first)) => {
+                            first.to_uppercase().chain(word.chars().skip(1)).collect()
+                        }
+                    }
+                })
+                .collect(),
+            KeyCase::Preserve => String::from(key),
+        }
+    }
+
+    /// Build the document of the overrides found in `vars`
+    pub(crate) fn to_hocon(&self, vars: impl Iterator<Item = (String, String)>) -> Hocon {
+        let mut vars = vars
+            .filter_map(|(name, value)| self.path(&name).map(|path| (path, value)))
+            .collect::<Vec<_>>();
+        // the order of the environment is not stable, keep the result reproducible
+        vars.sort();
+        let mut overrides = Hocon::Hash(Default::default());
+        for (path, value) in vars {
+            // paths only go through objects, setting them can not fail
+            let _ = overrides.set_at(&path, crate::helper::parse_scalar(&value));
+        }
+        overrides
+    }
+}
+
+/// Variables of a `.env` file, in order. A line is `NAME=value`, optionally starting with
+/// `export`. A value in double quotes can hold escaped characters, one in single quotes is
+/// kept as is, and an unquoted value ends before a ` #` comment. Variables in values are not
+/// expanded
+pub(crate) fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = vec![];
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (name, value) = line.split_once('=').ok_or(Error::Parse)?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(Error::Parse);
+        }
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // a quoted value can span several lines
+                let mut quoted = String::from(&value[1..]);
+                let end = loop {
+                    if let Some(end) = closing_quote(&quoted, quote) {
+                        break end;
+                    }
+                    quoted.push('\n');
+                    quoted.push_str(lines.next().ok_or(Error::Parse)?);
+                };
+                match quote {
+                    '"' => unescape_dotenv(&quoted[..end]),
+                    _ => String::from(&quoted[..end]),
+                }
+            }
+            _ => String::from(
+                value
+                    .split_once(" #")
+                    .map_or(value, |(value, _)| value)
+                    .trim_end(),
+            ),
+        };
+        vars.push((String::from(name), value));
+    }
+    Ok(vars)
+}
+
+/// Position of the quote closing a value, after escaped characters in double quotes
+fn closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut chars = value.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            c if c == quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape_dotenv(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_dotenv() {
+        let vars = parse_dotenv(
+            "# local settings\n\
+             DB_HOST=localhost\n\
+             export DB_PORT = 5432 # default port\n\
+             GREETING=\"hello\\n\\\"world\\\"\"\n\
+             PATTERN='a\\d+ # not a comment'\n\
+             KEY=\"first\nsecond\"\n\
+             EMPTY=\n",
+        )
+        .expect("during test");
+        assert_eq!(
+            vars,
+            vec![
+                (String::from("DB_HOST"), String::from("localhost")),
+                (String::from("DB_PORT"), String::from("5432")),
+                (String::from("GREETING"), String::from("hello\n\"world\"")),
+                (
+                    String::from("PATTERN"),
+                    String::from("a\\d+ # not a comment")
+                ),
+                (String::from("KEY"), String::from("first\nsecond")),
+                (String::from("EMPTY"), String::new()),
+            ]
+        );
+        assert_eq!(parse_dotenv("NOT A VARIABLE"), Err(Error::Parse));
+        assert_eq!(parse_dotenv("KEY=\"unclosed"), Err(Error::Parse));
+    }
+
+    #[test]
+    fn can_map_variable_names() {
+        let overrides = EnvOverrides::new("MYAPP");
+        assert_eq!(
+            overrides.path("MYAPP_SERVER__MAX_CONNECTIONS"),
+            Some(vec![
+                String::from("server"),
+                String::from("max_connections")
+            ])
+        );
+        assert_eq!(overrides.path("OTHER_SERVER"), None);
+        assert_eq!(overrides.path("MYAPP_"), None);
+        assert_eq!(overrides.path("MYAPP_A____B"), None);
+
+        let overrides = EnvOverrides::new("MYAPP")
+            .separator("_")
+            .key_case(KeyCase::Preserve);
+        assert_eq!(
+            overrides.path("MYAPP_SERVER_PORT"),
+            Some(vec![String::from("SERVER"), String::from("PORT")])
+        );
+
+        let overrides = EnvOverrides::new("MYAPP").key_case(KeyCase::Camel);
+        assert_eq!(
+            overrides.path("MYAPP_HTTP__MAX_CONNECTIONS"),
+            Some(vec![String::from("http"), String::from("maxConnections")])
+        );
+        let overrides = EnvOverrides::new("MYAPP").key_case(KeyCase::Kebab);
+        assert_eq!(
+            overrides.path("MYAPP_HTTP__MAX_CONNECTIONS"),
+            Some(vec![String::from("http"), String::from("max-connections")])
+        );
+    }
+
+    #[test]
+    fn can_build_overrides() {
+        let vars = vec![
+            (String::from("MYAPP_SERVER__PORT"), String::from("8081")),
+            (String::from("MYAPP_SERVER"), String::from("ignored")),
+            (String::from("MYAPP_NAME"), String::from("app")),
+            (String::from("PATH"), String::from("/bin")),
+        ];
+
+        assert_eq!(
+            EnvOverrides::new("MYAPP").to_hocon(vars.into_iter()),
+            crate::hocon!({ name = "app", server.port = 8081 })
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::document::Source;
+
 /// A Result type alias using this crate's Error type
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -13,6 +15,16 @@ pub enum Error {
         message: String,
     },
 
+    /// Error decoding a document that is neither valid UTF-8, nor valid UTF-16 after a UTF-16
+    /// byte order mark
+    #[error("Error decoding '{document}': {message}")]
+    Encoding {
+        /// The document being decoded
+        document: String,
+        /// What is invalid in the document
+        message: String,
+    },
+
     /// Error reading a file. This can be a file not found, a permission issue, ...
     #[error("Error reading file '{path:?}'")]
     File {
@@ -22,12 +34,47 @@ pub enum Error {
     /// Error while parsing a document. The document is not valid HOCON
     #[error("Error wile parsing document")]
     Parse,
+    /// Error validating a document as pure JSON, with
+    /// [`strict_json`](struct.HoconLoader.html#method.strict_json) or
+    /// [`load_json_strict`](struct.HoconLoader.html#method.load_json_strict). The document
+    /// uses syntax that HOCON adds to JSON, or is not valid JSON. This is an error even when
+    /// not in strict mode
+    #[error("Invalid JSON at line {line}, column {column}: {message}")]
+    InvalidJson {
+        /// Line of the invalid syntax, starting at 1
+        line: usize,
+        /// Column of the invalid syntax in characters, starting at 1
+        column: usize,
+        /// What is invalid in the document
+        message: String,
+    },
     /// Error including a document
     #[error("Error including document at '{path:?}'")]
     Include {
         /// Path of the included file
         path: String,
     },
+    /// Error including a file outside of the root set with
+    /// [`HoconLoader::include_root`](struct.HoconLoader.html#method.include_root)
+    #[error("Error including '{path:?}' outside of the include root")]
+    IncludeOutsideRoot {
+        /// Path of the included file
+        path: String,
+    },
+    /// Error including an URL that is not allowed by the filter set with
+    /// [`HoconLoader::url_filter`](struct.HoconLoader.html#method.url_filter)
+    #[error("Error including '{url:?}' not allowed by the URL filter")]
+    UrlNotAllowed {
+        /// The included URL
+        url: String,
+    },
+    /// Error including a document that is already being included, with the chain of includes
+    /// from the first inclusion of that document
+    #[error("Include cycle {}", .chain.join(" -> "))]
+    IncludeCycle {
+        /// Documents included along the cycle, starting and ending with the same one
+        chain: Vec<String>,
+    },
     /// Error processing deep includes. You can change the maximum depth using max_include_depth
     #[error("Error processing deep includes")]
     TooManyIncludes,
@@ -49,6 +96,65 @@ pub enum Error {
     /// Error getting a value because of an invalid key type
     #[error("Error getting a value because of an invalid key type")]
     InvalidKey,
+    /// Error concatenating a substitution to an array or an object, when its value is of
+    /// another type
+    #[error("Error concatenating substitution '{path:?}' of another type")]
+    InvalidConcatenation {
+        /// Path of the substitution
+        path: String,
+    },
+    /// Error resolving substitutions that reference each other, like `a: ${b}, b: ${a}`
+    #[error("Substitution cycle {}", describe_cycle(.chain, .origins))]
+    SubstitutionCycle {
+        /// Paths of the substitutions in the cycle, the first one is repeated at the end
+        chain: Vec<String>,
+        /// Source of the definition of each path of the chain, when known
+        origins: Vec<Option<Source>>,
+    },
+    /// Error resolving a substitution that is not optional in strict mode, when it is found
+    /// neither in the document nor in the environment
+    #[error(
+        "Substitution '{substitution}' not found{}",
+        describe_location(.key, .origin, .line)
+    )]
+    UnresolvedSubstitution {
+        /// Path of the substitution
+        substitution: String,
+        /// Path of the value defined with the substitution, when known
+        key: Option<String>,
+        /// Source of the document where the substitution is written, when known
+        origin: Option<Source>,
+        /// Line of the substitution in its document, when known
+        line: Option<usize>,
+    },
+    /// Error parsing, resolving substitutions or loading includes beyond a limit of the loader,
+    /// set with
+    /// [`max_substitutions`](struct.HoconLoader.html#method.max_substitutions),
+    /// [`max_value_size`](struct.HoconLoader.html#method.max_value_size),
+    /// [`max_include_size`](struct.HoconLoader.html#method.max_include_size),
+    /// [`max_parsed_values`](struct.HoconLoader.html#method.max_parsed_values) or
+    /// [`url_timeout`](struct.HoconLoader.html#method.url_timeout), like a document that
+    /// keeps concatenating a value to itself. This is an error even when not in strict mode
+    #[error("Resource limit reached: {limit}")]
+    ResourceLimit {
+        /// Description of the limit reached
+        limit: String,
+    },
+    /// Error resolving a substitution that needs more passes than the limit set with
+    /// [`max_resolution_passes`](struct.HoconLoader.html#method.max_resolution_passes), each
+    /// pass following a substitution to another one. This is an error even when not in
+    /// strict mode
+    #[error(
+        "Substitutions still unresolved after {passes} passes: {}",
+        .keys.join(" -> ")
+    )]
+    TooManyResolutionPasses {
+        /// Maximum number of passes
+        passes: usize,
+        /// Paths of the substitutions that were still being resolved, in the order they
+        /// were followed
+        keys: Vec<String>,
+    },
     /// Error deserializing
     #[error("Error deserializing: {message:?}")]
     Deserialization {
@@ -57,6 +163,36 @@ pub enum Error {
     },
 }
 
+fn describe_cycle(chain: &[String], origins: &[Option<Source>]) -> String {
+    chain
+        .iter()
+        .enumerate()
+        .map(|(i, path)| match origins.get(i) {
+            Some(Some(source)) => format!("'{}' ({})", path, source),
+            _ => format!("'{}'", path),
+        })
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn describe_location(
+    key: &Option<String>,
+    origin: &Option<Source>,
+    line: &Option<usize>,
+) -> String {
+    let mut description = String::new();
+    if let Some(key) = key {
+        description.push_str(&format!(" for '{}'", key));
+    }
+    if let Some(origin) = origin {
+        description.push_str(&format!(" in {}", origin));
+    }
+    if let Some(line) = line {
+        description.push_str(&format!(" at line {}", line));
+    }
+    description
+}
+
 /// this is only needed because this crate heavily relies on Clone and io:Error doesnt implement Clone
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
@@ -20,3 +20,185 @@ where
         .map(|v| v.expect("extract_result: got a Err"))
         .collect())
 }
+
+/// Read a value given outside of a document, like on the command line: `null`, booleans,
+/// and numbers are read as such, and anything else is a string. Surrounding double quotes
+/// force a string.
+pub(crate) fn parse_scalar(value: &str) -> crate::Hocon {
+    let trimmed = value.trim();
+    match trimmed {
+        "null" => crate::Hocon::Null,
+        "true" => crate::Hocon::Boolean(true),
+        "false" => crate::Hocon::Boolean(false),
+        _ if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') => {
+            crate::Hocon::String(String::from(&trimmed[1..trimmed.len() - 1]))
+        }
+        _ => match (trimmed.parse::<i64>(), trimmed.parse::<f64>()) {
+            (Ok(i), _) => crate::Hocon::Integer(i),
+            (_, Ok(f))
+                if f.is_finite()
+                    && trimmed.starts_with(|c: char| c == '-' || c.is_ascii_digit()) =>
+            {
+                crate::Hocon::Real(f)
+            }
+            _ => crate::Hocon::String(String::from(trimmed)),
+        },
+    }
+}
+
+/// Split a path expression like `a.b."c.d"` into its keys. Quoted keys can contain dots,
+/// and whitespace around unquoted keys is ignored.
+pub(crate) fn split_path(path: &str) -> Vec<String> {
+    let mut keys = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', _) => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            ('\\', true) => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            ('.', false) => {
+                keys.push(finish_key(&mut current, was_quoted));
+                was_quoted = false;
+            }
+            (c, _) => current.push(c),
+        }
+    }
+    keys.push(finish_key(&mut current, was_quoted));
+    keys
+}
+
+/// Join keys into a path expression that [`split_path`] reads back, quoting keys that would
+/// not be read as a single key otherwise.
+pub(crate) fn join_path<S: AsRef<str>>(keys: &[S]) -> String {
+    keys.iter()
+        .map(|key| {
+            let key = key.as_ref();
+            if key.is_empty() || key.contains(['.', '"', '\\']) || key.trim() != key {
+                format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                key.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn finish_key(current: &mut String, was_quoted: bool) -> String {
+    let key = std::mem::take(current);
+    if was_quoted {
+        key
+    } else {
+        key.trim().to_string()
+    }
+}
+
+/// Whether a path of an include is a glob pattern, with `*` or `?`
+pub(crate) fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Match a file name against a glob pattern, where `*` matches any characters and `?`
+/// matches one character
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // the position of the last `*` in the pattern, and of the name when it was met
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(c) if *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // let the last `*` match one more character
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    n = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, join_path, parse_scalar, split_path};
+
+    #[test]
+    fn can_split_path() {
+        assert_eq!(split_path("a"), vec!["a"]);
+        assert_eq!(split_path("a.b.c"), vec!["a", "b", "c"]);
+        assert_eq!(split_path(" a . b "), vec!["a", "b"]);
+        assert_eq!(split_path(r#"a."b.c".d"#), vec!["a", "b.c", "d"]);
+        assert_eq!(split_path(r#""a \"b\"""#), vec![r#"a "b""#]);
+        assert_eq!(split_path(r#"" a ""#), vec![" a "]);
+    }
+
+    #[test]
+    fn can_parse_scalar() {
+        assert_eq!(parse_scalar("8081"), crate::Hocon::Integer(8081));
+        assert_eq!(parse_scalar("-0.5"), crate::Hocon::Real(-0.5));
+        assert_eq!(parse_scalar("true"), crate::Hocon::Boolean(true));
+        assert_eq!(parse_scalar("null"), crate::Hocon::Null);
+        assert_eq!(
+            parse_scalar(r#""12""#),
+            crate::Hocon::String(String::from("12"))
+        );
+        assert_eq!(
+            parse_scalar("inf"),
+            crate::Hocon::String(String::from("inf"))
+        );
+        assert_eq!(
+            parse_scalar("http://localhost"),
+            crate::Hocon::String(String::from("http://localhost"))
+        );
+        assert_eq!(
+            parse_scalar(" localhost "),
+            crate::Hocon::String(String::from("localhost"))
+        );
+    }
+
+    #[test]
+    fn can_join_path() {
+        assert_eq!(join_path(&["a", "b"]), "a.b");
+        assert_eq!(join_path(&["a", "b.c", " d", ""]), r#"a."b.c"." d"."""#);
+        assert_eq!(join_path(&[r#"a "b""#]), r#""a \"b\"""#);
+        for keys in [vec!["a", "b.c"], vec![" a ", r#"b"c"#, "d"]] {
+            assert_eq!(split_path(&join_path(&keys)), keys);
+        }
+    }
+
+    #[test]
+    fn can_match_globs() {
+        assert!(glob_match("*.conf", "a.conf"));
+        assert!(glob_match("*.conf", ".conf"));
+        assert!(!glob_match("*.conf", "a.json"));
+        assert!(glob_match("a?c*", "abc.conf"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("*a*b", "xaybab"));
+        assert!(!glob_match("*a*b", "xaybx"));
+        assert!(glob_match("10-*.conf", "10-db.conf"));
+    }
+}
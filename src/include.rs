@@ -0,0 +1,183 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Target of an include, given to an [`IncludeResolver`](trait.IncludeResolver.html)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IncludeTarget<'a> {
+    /// `include "..."` or `include file("...")`, with the path relative to the directory of
+    /// the including file
+    File(&'a Path),
+    /// `include url("...")`
+    Url(&'a str),
+    /// `include classpath("...")`
+    Classpath(&'a str),
+}
+
+/// Source of included documents, registered with
+/// [`HoconLoader::include_resolver`](struct.HoconLoader.html#method.include_resolver), to
+/// load them from somewhere else than the file system or HTTP, like a database or an
+/// encrypted bundle.
+///
+/// The content returned is parsed according to the extension of the target, as HOCON if it
+/// has none, and merged as any included document, within the same limits.
+pub trait IncludeResolver {
+    /// Content of the document included by `target`, or `None` to load it as usual
+    ///
+    /// # Errors
+    ///
+    /// An error is handled as an included document that could not be read
+    fn resolve(&self, target: IncludeTarget<'_>) -> crate::Result<Option<String>>;
+}
+
+impl<F> IncludeResolver for F
+where
+    F: Fn(IncludeTarget<'_>) -> crate::Result<Option<String>>,
+{
+    fn resolve(&self, target: IncludeTarget<'_>) -> crate::Result<Option<String>> {
+        self(target)
+    }
+}
+
+/// An include resolver shared by the clones of a loader
+#[derive(Clone)]
+pub(crate) struct SharedResolver(pub(crate) Rc<dyn IncludeResolver>);
+
+impl std::fmt::Debug for SharedResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IncludeResolver")
+    }
+}
+
+/// Order in which the files of an included directory are merged, each winning over the
+/// previous ones
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IncludeOrder {
+    /// By path, comparing characters, so `10-db.conf` comes before `9-app.conf`
+    Lexical,
+    /// By path, comparing numbers by value, so `9-app.conf` comes before `10-db.conf`
+    Natural,
+}
+
+/// Configuration of the directories included with
+/// [`HoconLoader::include_directories_with`](struct.HoconLoader.html#method.include_directories_with).
+///
+/// An included directory loads its `.conf`, `.json` and `.properties` files. Hidden files
+/// are skipped, as are subdirectories unless recursion is enabled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectoryIncludes {
+    recursive: bool,
+    order: IncludeOrder,
+}
+
+impl Default for DirectoryIncludes {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            order: IncludeOrder::Lexical,
+        }
+    }
+}
+
+impl DirectoryIncludes {
+    /// Include the files at the top of directories, in lexical order
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also include the files of subdirectories, ordered with the other files by their path
+    pub fn recursive(self) -> Self {
+        Self {
+            recursive: true,
+            ..self
+        }
+    }
+
+    /// Set the order of the files, by default [`IncludeOrder::Lexical`](enum.IncludeOrder.html#variant.Lexical)
+    pub fn order(self, order: IncludeOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Files to include from directory `dir`, relative to `base`, in order
+    pub(crate) fn files(&self, base: &Path, dir: &Path) -> Vec<PathBuf> {
+        let mut files = vec![];
+        self.collect(base, dir, &mut files);
+        files.sort_by(|a, b| match self.order {
+            IncludeOrder::Lexical => a.cmp(b),
+            IncludeOrder::Natural => natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()),
+        });
+        files
+    }
+
+    fn collect(&self, base: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+        let entries = std::fs::read_dir(base.join(dir)).into_iter().flatten();
+        for name in entries.filter_map(|entry| entry.ok()?.file_name().into_string().ok()) {
+            let path = dir.join(&name);
+            if name.starts_with('.') {
+                continue;
+            } else if base.join(&path).is_dir() {
+                if self.recursive {
+                    self.collect(base, &path, files);
+                }
+            } else if matches!(
+                Path::new(&name).extension().and_then(|ext| ext.to_str()),
+                Some("conf" | "json" | "json5" | "properties")
+            ) {
+                files.push(path);
+            }
+        }
+    }
+}
+
+/// Compare strings with their runs of digits compared by value
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits
+                };
+                let (x, y) = (number(&mut a), number(&mut b));
+                let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x_value
+                    .len()
+                    .cmp(&y_value.len())
+                    .then_with(|| x_value.cmp(y_value))
+                    .then_with(|| x.len().cmp(&y.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compare_naturally() {
+        assert_eq!(natural_cmp("9-app.conf", "10-db.conf"), Ordering::Less);
+        assert_eq!(natural_cmp("a10", "a9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Greater);
+        assert_eq!(natural_cmp("a1b", "a1b"), Ordering::Equal);
+        assert_eq!(natural_cmp("a", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("b1", "a2"), Ordering::Greater);
+    }
+}
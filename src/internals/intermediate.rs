@@ -4,7 +4,8 @@ use std::rc::Rc;
 use crate::Hocon;
 use crate::HoconLoaderConfig;
 
-use super::value::HoconValue;
+use super::internal::HoconInternal;
+use super::value::{HoconValue, SubstitutionChain, is_not_found};
 
 use linked_hash_map::LinkedHashMap;
 
@@ -64,15 +65,56 @@ impl Node {
         }
     }
 
+    /// Number of values held, counted when a substitution copies them
+    pub(crate) fn size(&self) -> usize {
+        match self {
+            Node::Leaf(value) => value.size(),
+            Node::Node { children, .. } => children
+                .iter()
+                .map(|child| child.value.borrow().size())
+                .sum::<usize>()
+                .max(1),
+        }
+    }
+
+    /// Values of the node, with their path from it
+    pub(crate) fn entries(&self) -> Vec<(Vec<HoconValue>, HoconValue)> {
+        match self {
+            Node::Leaf(value) => vec![(vec![], value.clone())],
+            Node::Node { children, key_hint } if children.is_empty() => vec![(
+                vec![],
+                match key_hint {
+                    Some(KeyType::Int) => HoconValue::EmptyArray,
+                    Some(KeyType::String) | None => HoconValue::EmptyObject,
+                },
+            )],
+            Node::Node { children, .. } => children
+                .iter()
+                .flat_map(|child| {
+                    child
+                        .value
+                        .borrow()
+                        .entries()
+                        .into_iter()
+                        .map(|(mut path, value)| {
+                            path.insert(0, child.key.clone());
+                            (path, value)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        }
+    }
+
     pub(crate) fn finalize(
         self,
         root: &HoconIntermediate,
         config: &HoconLoaderConfig,
         included_path: Option<Vec<HoconValue>>,
-        substituting_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
     ) -> Result<Hocon, crate::Error> {
         match self {
-            Node::Leaf(v) => v.finalize(root, config, false, included_path, substituting_path),
+            Node::Leaf(v) => v.finalize(root, config, false, included_path, substitution_chain),
             Node::Node {
                 ref children,
                 ref key_hint,
@@ -84,17 +126,20 @@ impl Node {
                             crate::helper::extract_result(
                                 children
                                     .iter()
-                                    .map(|c| {
-                                        c.value.clone().into_inner().finalize(
+                                    .enumerate()
+                                    .map(|(i, c)| {
+                                        c.finalize_element(
+                                            || i.to_string(),
                                             root,
                                             config,
                                             included_path.clone(),
-                                            substituting_path.clone(),
+                                            substitution_chain.clone(),
                                         )
                                     })
                                     .collect(),
                             )?
                             .into_iter()
+                            .flatten()
                             .flat_map(|v| {
                                 if let Hocon::Array(vs) = v {
                                     vs.into_iter()
@@ -105,42 +150,69 @@ impl Node {
                             .collect(),
                         )),
                         (HoconValue::Integer(_), _) | (HoconValue::Null(_), _) => {
-                            Ok(Hocon::Array(crate::helper::extract_result(
-                                children
-                                    .iter()
-                                    .map(|c| {
-                                        c.value.clone().into_inner().finalize(
-                                            root,
-                                            config,
-                                            included_path.clone(),
-                                            substituting_path.clone(),
-                                        )
-                                    })
-                                    .collect(),
-                            )?))
+                            Ok(Hocon::Array(
+                                crate::helper::extract_result(
+                                    children
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(i, c)| {
+                                            if c.is_concat_substitution() {
+                                                c.finalize_concatenated(
+                                                    true,
+                                                    root,
+                                                    config,
+                                                    included_path.clone(),
+                                                    substitution_chain.clone(),
+                                                )
+                                                .map(|value| match value {
+                                                    Some(Hocon::Array(values)) => values,
+                                                    value => value.into_iter().collect(),
+                                                })
+                                            } else {
+                                                c.finalize_element(
+                                                    || i.to_string(),
+                                                    root,
+                                                    config,
+                                                    included_path.clone(),
+                                                    substitution_chain.clone(),
+                                                )
+                                                .map(|value| value.into_iter().collect())
+                                            }
+                                        })
+                                        .collect(),
+                                )?
+                                .into_iter()
+                                .flatten()
+                                .collect(),
+                            ))
                         }
 
-                        (HoconValue::String(_), _) => Ok(Hocon::Hash(
-                            crate::helper::extract_result(
-                                children
-                                    .iter()
-                                    .map(|c| {
-                                        (
-                                            c.key.clone().string_value(),
-                                            c.value.clone().into_inner().finalize(
-                                                root,
-                                                config,
-                                                included_path.clone(),
-                                                substituting_path.clone(),
-                                            ),
-                                        )
-                                    })
-                                    .map(|(k, v)| v.map(|v| (k, v)))
-                                    .collect(),
-                            )?
-                            .into_iter()
-                            .collect(),
-                        )),
+                        (HoconValue::String(_), _) => {
+                            let mut hash = LinkedHashMap::new();
+                            for c in children {
+                                if c.is_concat_substitution() {
+                                    // a bad value can not be kept without a key
+                                    if let Some(Hocon::Hash(values)) = c.finalize_concatenated(
+                                        false,
+                                        root,
+                                        config,
+                                        included_path.clone(),
+                                        substitution_chain.clone(),
+                                    )? {
+                                        merge_hashes(&mut hash, values);
+                                    }
+                                } else if let Some(value) = c.finalize_element(
+                                    || c.key.clone().string_value(),
+                                    root,
+                                    config,
+                                    included_path.clone(),
+                                    substitution_chain.clone(),
+                                )? {
+                                    hash.insert(c.key.clone().string_value(), value);
+                                }
+                            }
+                            Ok(Hocon::Hash(hash))
+                        }
                         // Keys should only be integer or strings
                         _ => unreachable!(),
                     },
@@ -152,6 +224,17 @@ impl Node {
         }
     }
 
+    /// Element of an array from its index in a path, like `0` in `${servers.0}`
+    fn array_element<'a>(children: &'a [Rc<Child>], key: &HoconValue) -> Option<&'a Rc<Child>> {
+        match (children.first().map(|child| &child.key), key) {
+            (
+                Some(HoconValue::Integer(_)) | Some(HoconValue::Null(_)),
+                HoconValue::String(index),
+            ) => children.get(index.parse::<usize>().ok()?),
+            _ => None,
+        }
+    }
+
     pub(crate) fn find_key(
         &self,
         config: &HoconLoaderConfig,
@@ -170,6 +253,7 @@ impl Node {
                         match children
                             .iter()
                             .find(|child| child.key == first)
+                            .or_else(|| Self::array_element(children, &first))
                             .ok_or(crate::Error::KeyNotFound {
                                 key: path
                                     .into_iter()
@@ -214,6 +298,81 @@ impl Child {
         self.value.clone().into_inner().find_key(config, path)
     }
 
+    fn is_concat_substitution(&self) -> bool {
+        matches!(&*self.value.borrow(), Node::Leaf(value) if value.is_concat_substitution())
+    }
+
+    fn finalize_concatenated(
+        &self,
+        in_array: bool,
+        root: &HoconIntermediate,
+        config: &HoconLoaderConfig,
+        included_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
+    ) -> Result<Option<Hocon>, crate::Error> {
+        match self.value.clone().into_inner() {
+            Node::Leaf(value) => value.finalize_concatenated(
+                in_array,
+                root,
+                config,
+                included_path,
+                substitution_chain,
+            ),
+            node => node
+                .finalize(root, config, included_path, substitution_chain)
+                .map(Some),
+        }
+    }
+
+    /// Finalize an element of an array or a field of an object, `None` for an optional
+    /// substitution that can not be found
+    fn finalize_element(
+        &self,
+        key: impl FnOnce() -> String,
+        root: &HoconIntermediate,
+        config: &HoconLoaderConfig,
+        included_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
+    ) -> Result<Option<Hocon>, crate::Error> {
+        let optional = matches!(
+            &*self.value.borrow(),
+            Node::Leaf(value) if value.is_optional_substitution()
+        );
+        match self.finalize(key, root, config, included_path, substitution_chain) {
+            value if optional && is_not_found(&value) => Ok(None),
+            value => value.map(Some),
+        }
+    }
+
+    fn finalize(
+        &self,
+        key: impl FnOnce() -> String,
+        root: &HoconIntermediate,
+        config: &HoconLoaderConfig,
+        included_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
+    ) -> Result<Hocon, crate::Error> {
+        // only keep track of the path when walking the document, not when finalizing
+        // the value of a substitution
+        let tracer = config
+            .tracer
+            .as_ref()
+            .filter(|_| substitution_chain.is_empty());
+        if let Some(tracer) = tracer {
+            tracer.enter(key());
+        }
+        let value = self.value.clone().into_inner().finalize(
+            root,
+            config,
+            included_path,
+            substitution_chain,
+        );
+        if let Some(tracer) = tracer {
+            tracer.leave();
+        }
+        value
+    }
+
     pub(crate) fn deep_clone(&self) -> Self {
         Self {
             key: self.key.clone(),
@@ -222,16 +381,76 @@ impl Child {
     }
 }
 
+/// Merge objects concatenated together, the values of `values` winning
+fn merge_hashes(hash: &mut LinkedHashMap<String, Hocon>, values: LinkedHashMap<String, Hocon>) {
+    for (key, value) in values {
+        match (hash.get_mut(&key), value) {
+            (Some(Hocon::Hash(existing)), Hocon::Hash(value)) => merge_hashes(existing, value),
+            (_, value) => {
+                hash.insert(key, value);
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct HoconIntermediate {
     pub(crate) tree: Node,
 }
 
 impl HoconIntermediate {
+    /// Resolve the values that can already be resolved, keeping the others as they are to
+    /// resolve them once merged with more documents
+    pub(crate) fn resolve_partially(self, config: &HoconLoaderConfig) -> HoconInternal {
+        // any value that can not be resolved is returned as an error
+        let config = HoconLoaderConfig {
+            strict: true,
+            ..config.clone()
+        };
+        HoconInternal {
+            internal: self
+                .tree
+                .entries()
+                .into_iter()
+                .flat_map(|(path, value)| {
+                    let resolved = if value.has_unresolved_substitution()
+                        && !value.is_concat_substitution()
+                        && self.can_resolve(&value, &config)
+                    {
+                        value
+                            .clone()
+                            .finalize(&self, &config, false, None, vec![])
+                            .ok()
+                    } else {
+                        None
+                    };
+                    match resolved {
+                        Some(hocon) => HoconInternal::from_hocon(hocon).add_to_path(path).internal,
+                        None => vec![(path, value)],
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn can_resolve(&self, value: &HoconValue, config: &HoconLoaderConfig) -> bool {
+        match value {
+            // values that can not be found are skipped in a concatenation, check each of them
+            HoconValue::Concat(values) => {
+                values.iter().all(|value| self.can_resolve(value, config))
+            }
+            value if value.has_unresolved_substitution() => value
+                .clone()
+                .finalize(self, config, false, None, vec![])
+                .is_ok(),
+            _ => true,
+        }
+    }
+
     pub(crate) fn finalize(self, config: &HoconLoaderConfig) -> Result<Hocon, crate::Error> {
         #[allow(clippy::redundant_clone)]
         // looks like https://github.com/rust-lang/rust-clippy/issues/5707
         let refself = &self.clone();
-        self.tree.finalize(refself, config, None, None)
+        self.tree.finalize(refself, config, None, vec![])
     }
 }
@@ -1,10 +1,13 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::ops::Deref;
 use std::rc::Rc;
 
 use crate::HoconLoaderConfig;
+use crate::IncludeTarget;
+use crate::document::{Layer, MergeAction, Priority, Source};
+use crate::loader_config::IncludeCacheEntry;
 
 use super::intermediate::Child;
 use super::intermediate::HoconIntermediate;
@@ -14,12 +17,27 @@ use super::value::HoconValue;
 pub(crate) enum Include<'a> {
     File(Cow<'a, str>),
     Url(Cow<'a, str>),
+    Classpath(Cow<'a, str>),
+    // the content of an environment variable, like `env("APP_CONFIG")`
+    Env(Cow<'a, str>),
+    // a target with substitutions, like `file(${CONF_DIR}"/extra.conf")`, with its text
+    Substituted {
+        text: Cow<'a, str>,
+        parts: Vec<HoconValue>,
+        url: bool,
+    },
+    // an include that fails when the target is missing, like `required(file("secrets.conf"))`
+    Required(Box<Include<'a>>),
 }
 impl<'a> Include<'a> {
     fn included(&self) -> &Cow<'a, str> {
         match self {
             Include::File(s) => s,
             Include::Url(s) => s,
+            Include::Classpath(s) => s,
+            Include::Env(s) => s,
+            Include::Substituted { text, .. } => text,
+            Include::Required(included) => included.included(),
         }
     }
 }
@@ -56,6 +74,42 @@ impl HoconInternal {
         }
     }
 
+    pub(crate) fn from_hocon(hocon: crate::Hocon) -> Self {
+        let mut internal = vec![];
+        Self::flatten_hocon(vec![], hocon, &mut internal);
+        Self { internal }
+    }
+
+    fn flatten_hocon(path: Path, hocon: crate::Hocon, internal: &mut Hash) {
+        let value = match hocon {
+            crate::Hocon::Hash(hash) if hash.is_empty() => HoconValue::EmptyObject,
+            crate::Hocon::Hash(hash) => {
+                for (key, value) in hash {
+                    let mut item_path = path.clone();
+                    item_path.push(HoconValue::String(Rc::from(key)));
+                    Self::flatten_hocon(item_path, value, internal);
+                }
+                return;
+            }
+            crate::Hocon::Array(array) if array.is_empty() => HoconValue::EmptyArray,
+            crate::Hocon::Array(array) => {
+                for (idx, value) in array.into_iter().enumerate() {
+                    let mut item_path = path.clone();
+                    item_path.push(HoconValue::Integer(idx as i64));
+                    Self::flatten_hocon(item_path, value, internal);
+                }
+                return;
+            }
+            crate::Hocon::Real(f) => HoconValue::Real(f),
+            crate::Hocon::Integer(i) => HoconValue::Integer(i),
+            crate::Hocon::String(s) => HoconValue::String(Rc::from(s)),
+            crate::Hocon::Boolean(b) => HoconValue::Boolean(b),
+            crate::Hocon::Null => HoconValue::UnquotedString(Rc::from("null")),
+            crate::Hocon::BadValue(err) => HoconValue::BadValue(err),
+        };
+        internal.push((path, value));
+    }
+
     pub(crate) fn from_value(v: HoconValue) -> Self {
         Self {
             internal: vec![(vec![], v)],
@@ -124,23 +178,38 @@ impl HoconInternal {
             let index_prefix = uuid::Uuid::new_v4().hyphenated().to_string();
             indexer = Box::new(move |i| HoconValue::Null(format!("{}-{}", index_prefix, i)));
         }
+        // the values of a substitution concatenated after the first array are inserted
+        // between its elements, so they can not be indexed while parsing
+        let mut reset = vec![];
+        if a.iter()
+            .any(|item| item.internal.len() == 1 && item.internal[0].1.is_concat_substitution())
+        {
+            let index_prefix = uuid::Uuid::new_v4().hyphenated().to_string();
+            indexer = Box::new(move |i| HoconValue::Null(format!("{}-{}", index_prefix, i)));
+            if !matches!(
+                a[0].internal.first(),
+                Some((_, HoconValue::PathSubstitutionInParent(_)))
+            ) {
+                // replace any previous value, as elements are added to the array by key
+                reset.push((vec![], HoconValue::EmptyArray));
+            }
+        }
         if a.is_empty() {
             Self {
                 internal: vec![(vec![], HoconValue::EmptyArray)],
             }
         } else {
             Self {
-                internal: a
+                internal: reset
                     .into_iter()
-                    .enumerate()
-                    .flat_map(|(i, hw)| {
+                    .chain(a.into_iter().enumerate().flat_map(|(i, hw)| {
                         Self {
                             internal: hw.internal,
                         }
                         .add_to_path(vec![indexer(i as i64)])
                         .internal
                         .into_iter()
-                    })
+                    }))
                     .map(|(k, v)| Self::add_root_to_includes(k, v))
                     .collect(),
             }
@@ -166,28 +235,170 @@ impl HoconInternal {
                 )],
             })
         } else {
-            let included_parsed = match included {
-                Include::File(ref path) => {
-                    let include_config = config
-                        .included_from()
-                        .with_file(std::path::Path::new(path.as_ref()).to_path_buf());
-                    include_config
-                        .read_file()
-                        .map_err(|_| crate::error::Error::Include {
-                            path: path.to_string(),
-                        })
-                        .and_then(|s| include_config.parse_str_to_internal(s))
+            let (included, required) = match included {
+                Include::Required(included) => (*included, true),
+                included => (included, false),
+            };
+            let missing = |path: &str| crate::error::Error::Include {
+                path: String::from(path),
+            };
+            let included = match included {
+                Include::Substituted { .. } if !config.include_substitutions => {
+                    return Err(crate::Error::Parse);
                 }
-                #[cfg(feature = "url-support")]
-                Include::Url(ref url) => {
-                    config
-                        .load_url(url)
-                        .map_err(|_| crate::error::Error::Include {
-                            path: url.to_string(),
+                Include::Env(_) if !config.env_includes => {
+                    return Err(crate::Error::Parse);
+                }
+                Include::Substituted { text, parts, url } => {
+                    match (config.include_target(&parts), url) {
+                        (Some(target), false) => Include::File(Cow::Owned(target)),
+                        (Some(target), true) => Include::Url(Cow::Owned(target)),
+                        (None, _) if required => return Err(missing(&text)),
+                        (None, _) => {
+                            return Ok(Self {
+                                internal: vec![(
+                                    vec![HoconValue::String(Rc::from(text.as_ref()))],
+                                    bad_value_or_err!(
+                                        config,
+                                        crate::Error::Include {
+                                            path: text.to_string(),
+                                        }
+                                    ),
+                                )],
+                            });
+                        }
+                    }
+                }
+                included => included,
+            };
+            let include_file = |path: std::path::PathBuf| {
+                let path = config.locate_include(&path);
+                let name = config.included_name(&path);
+                // a missing file includes nothing, so it cannot be part of a cycle
+                let include_config = match name.as_ref() {
+                    Some(name) => config.entering(name.clone())?,
+                    None => config.clone(),
+                }
+                .included_from()
+                .with_file(path.clone());
+                // a file included again is parsed once, unless it changed in the meantime
+                let cache_key = name.zip(include_config.file_modified());
+                if let Some(cached) = cache_key
+                    .as_ref()
+                    .and_then(|key| config.include_cache.borrow().get(key).cloned())
+                {
+                    let (cached, sources) = cached;
+                    config.count_parsed(cached.internal.len())?;
+                    config.include_sources.borrow_mut().extend(sources);
+                    return Ok(cached);
+                }
+                match include_config.read_file() {
+                    // a file without extension is missing when none of its variants exist
+                    Ok(s) if required && s.is_empty() => Err(missing(&path.display().to_string())),
+                    Ok(s) => {
+                        let read = config.include_sources.borrow().len();
+                        let included =
+                            include_config.parse_source(include_config.read_sources(), s)?;
+                        if let Some(key) = cache_key {
+                            let sources = config.include_sources.borrow()[read..].to_vec();
+                            config
+                                .include_cache
+                                .borrow_mut()
+                                .insert(key, (included.clone(), sources));
+                        }
+                        Ok(included)
+                    }
+                    Err(crate::Error::IncludeOutsideRoot { .. }) => {
+                        Err(crate::Error::IncludeOutsideRoot {
+                            path: path.display().to_string(),
                         })
+                    }
+                    Err(
+                        error
+                        @ (crate::Error::ResourceLimit { .. } | crate::Error::Encoding { .. }),
+                    ) => Err(error),
+                    // as in the spec, a missing file is included as an empty object
+                    Err(_) if !required && !config.strict && !include_config.file_exists() => {
+                        Ok(Self::empty())
+                    }
+                    Err(_) => Err(missing(&path.display().to_string())),
+                }
+            };
+            let include_files = |pattern: &str, files: Vec<std::path::PathBuf>| {
+                if required && files.is_empty() {
+                    return Err(missing(pattern));
+                }
+                files.into_iter().try_fold(Self::empty(), |merged, path| {
+                    include_file(path).map(|included| merged.add(included))
+                })
+            };
+            let target = match included {
+                Include::File(ref path) => {
+                    Some(IncludeTarget::File(std::path::Path::new(path.as_ref())))
                 }
-                #[cfg(not(feature = "url-support"))]
-                _ => Err(crate::error::Error::DisabledExternalUrl),
+                Include::Url(ref url) => Some(IncludeTarget::Url(url)),
+                Include::Classpath(ref name) => Some(IncludeTarget::Classpath(name)),
+                _ => None,
+            };
+            // a registered resolver comes first, the loader resolves what it does not handle
+            let included_parsed = match target.and_then(|target| config.resolve_include(target)) {
+                Some(resolved) => resolved,
+                None => match included {
+                    // files matching a pattern are merged in order, each winning over the previous
+                    Include::File(ref pattern)
+                        if config.include_globs && crate::helper::is_glob(pattern) =>
+                    {
+                        include_files(pattern, config.glob_include(pattern))
+                    }
+                    Include::File(ref path) => match config.directory_include(path) {
+                        // files of a directory are merged in order, each winning over the previous
+                        Some(files) => include_files(path, files),
+                        None => include_file(std::path::Path::new(path.as_ref()).to_path_buf()),
+                    },
+                    Include::Env(ref name) => match config.env_var(name) {
+                        Some(content) => {
+                            config
+                                .entering(format!("env({name})"))
+                                .and_then(|include_config| {
+                                    include_config.included_from().parse_str_to_internal(
+                                        crate::FileRead {
+                                            hocon: Some(content),
+                                            ..Default::default()
+                                        },
+                                    )
+                                })
+                        }
+                        None if required || config.strict => Err(missing(name)),
+                        None => Ok(Self::empty()),
+                    },
+                    Include::Classpath(ref name) => match config
+                        .entering(format!("classpath({name})"))
+                        .map(|include_config| include_config.include_classpath(name))
+                    {
+                        Err(cycle) => Err(cycle),
+                        Ok(Some(included)) => included,
+                        Ok(None) if required || config.strict => Err(missing(name)),
+                        Ok(None) => Ok(Self::empty()),
+                    },
+                    Include::Url(ref url) => {
+                        // fetched with the other URLs included once the document is parsed
+                        if let Some(deferred) = config.defer_url(url, required) {
+                            return Ok(deferred);
+                        }
+                        config.entering(url.to_string()).and_then(|include_config| {
+                            include_config.load_url(url).map_err(|error| match error {
+                                crate::Error::ResourceLimit { .. }
+                                | crate::Error::Encoding { .. }
+                                | crate::Error::DisabledExternalUrl
+                                | crate::Error::UrlNotAllowed { .. } => error,
+                                _ => crate::error::Error::Include {
+                                    path: url.to_string(),
+                                },
+                            })
+                        })
+                    }
+                    Include::Substituted { .. } | Include::Required(_) => unreachable!(),
+                },
             };
 
             match included_parsed {
@@ -207,6 +418,12 @@ impl HoconInternal {
                         })
                         .collect(),
                 }),
+                // exceeding a limit, or including a JSON file that is not pure JSON when it
+                // is checked, is an error even when not in strict mode
+                Err(
+                    error @ (crate::Error::ResourceLimit { .. } | crate::Error::InvalidJson { .. }),
+                ) => Err(error),
+                Err(error) if required => Err(error),
                 Err(error) => Ok(Self {
                     internal: vec![(
                         vec![HoconValue::String(Rc::from(included.included().as_ref()))],
@@ -217,6 +434,91 @@ impl HoconInternal {
         }
     }
 
+    /// Placeholder for a document included later, at the path `marker`
+    pub(crate) fn deferred(marker: String) -> Self {
+        let path = vec![HoconValue::Null(marker)];
+        Self {
+            internal: vec![(
+                path.clone(),
+                HoconValue::Included {
+                    value: Box::new(HoconValue::EmptyObject),
+                    include_root: None,
+                    original_path: path.into(),
+                },
+            )],
+        }
+    }
+
+    /// This document with the documents `included` by marker in place of their placeholders,
+    /// at the paths and with the include roots of the placeholders
+    pub(crate) fn with_deferred(self, included: &HashMap<String, IncludeCacheEntry>) -> Self {
+        Self {
+            internal: self
+                .internal
+                .into_iter()
+                .flat_map(|(path, value)| {
+                    let deferred = match path.last() {
+                        Some(HoconValue::Null(marker)) => included.get(marker),
+                        _ => None,
+                    };
+                    let Some((deferred, _)) = deferred else {
+                        return vec![(path, value)];
+                    };
+                    let prefix = &path[..path.len() - 1];
+                    deferred
+                        .clone()
+                        .with_deferred(included)
+                        .internal
+                        .into_iter()
+                        .map(|(included_path, included_value)| {
+                            (
+                                prefix.iter().chain(&included_path).cloned().collect(),
+                                Self::in_placeholder(value.clone(), &included_path, included_value),
+                            )
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// The value `included` at `path` of a deferred document, in the includes wrapping its
+    /// `placeholder`
+    fn in_placeholder(placeholder: HoconValue, path: &Path, included: HoconValue) -> HoconValue {
+        match (placeholder, included) {
+            (
+                HoconValue::Included {
+                    value,
+                    include_root,
+                    original_path,
+                },
+                included,
+            ) if !matches!(*value, HoconValue::EmptyObject) => HoconValue::Included {
+                value: Box::new(Self::in_placeholder(*value, path, included)),
+                include_root,
+                original_path: original_path[..original_path.len() - 1]
+                    .iter()
+                    .chain(path)
+                    .cloned()
+                    .collect(),
+            },
+            // the include of the deferred document itself
+            (
+                HoconValue::Included { include_root, .. },
+                HoconValue::Included {
+                    value,
+                    original_path,
+                    ..
+                },
+            ) => HoconValue::Included {
+                value,
+                include_root,
+                original_path,
+            },
+            (_, included) => included,
+        }
+    }
+
     pub(crate) fn add_include(
         &mut self,
         included: Include,
@@ -237,6 +539,29 @@ impl HoconInternal {
         })
     }
 
+    /// Record where the substitutions of the document parsed from `text` are written
+    pub(crate) fn locate(self, text: &str, source: &Source) -> Self {
+        let newlines = text
+            .bytes()
+            .enumerate()
+            .filter(|(_, b)| *b == b'\n')
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let line_of = |from_end: usize| {
+            let offset = text.len().saturating_sub(from_end);
+            newlines.partition_point(|i| *i < offset) + 1
+        };
+        self.transform(|k, v| {
+            let keys = k
+                .iter()
+                .flat_map(HoconValue::to_path)
+                .map(HoconValue::key_string)
+                .collect::<Vec<_>>();
+            let v = v.locate(&keys, Some((&line_of, source)));
+            (k, v)
+        })
+    }
+
     pub(crate) fn transform(
         self,
         transform: impl Fn(Vec<HoconValue>, HoconValue) -> (Vec<HoconValue>, HoconValue),
@@ -251,7 +576,7 @@ impl HoconInternal {
     }
 
     pub(crate) fn merge(
-        self,
+        layers: Vec<Layer>,
         config: &HoconLoaderConfig,
     ) -> Result<HoconIntermediate, crate::Error> {
         let root = Rc::new(Child {
@@ -265,10 +590,43 @@ impl HoconInternal {
         let mut concatenated_arrays: HashMap<Path, HashMap<HoconValue, i64>> = HashMap::new();
 
         let mut last_path_encoutered = vec![];
-        for (raw_path, item) in self.internal {
+        // substitutions in defaults, and those targeting an overridden value, are left for
+        // the second pass, once all layers are merged
+        let config = &HoconLoaderConfig {
+            overridden_paths: Rc::new(
+                layers
+                    .iter()
+                    .filter(|layer| layer.priority == Priority::Overrides)
+                    .flat_map(|layer| layer.internal.internal.iter())
+                    .map(|(path, _)| path.clone())
+                    .collect(),
+            ),
+            ..config.clone()
+        };
+        let defaults_config = HoconLoaderConfig {
+            defer_substitutions: true,
+            ..config.clone()
+        };
+        let mut items = layers
+            .into_iter()
+            .flat_map(|layer| {
+                let source = Rc::new(layer.source);
+                let priority = layer.priority;
+                layer
+                    .internal
+                    .internal
+                    .into_iter()
+                    .map(move |item| (Rc::clone(&source), priority, item))
+            })
+            .collect::<VecDeque<_>>();
+        while let Some((source, priority, (raw_path, item))) = items.pop_front() {
             if raw_path.is_empty() {
                 continue;
             }
+            let config = match priority {
+                Priority::Defaults => &defaults_config,
+                Priority::Document | Priority::Overrides => config,
+            };
 
             let full_path = raw_path
                 .clone()
@@ -283,12 +641,25 @@ impl HoconInternal {
                 })
                 .collect::<Vec<_>>();
 
+            // values of a substitution concatenated to an array or an object, that can
+            // already be found, are merged as if they were written in its place
+            if let HoconValue::ConcatSubstitution(ref subst) = item
+                && let Some(entries) = Self::concatenated_entries(subst, config, &root, &full_path)
+            {
+                for entry in entries.into_iter().rev() {
+                    items.push_front((Rc::clone(&source), priority, entry));
+                }
+                continue;
+            }
+
             let (leaf_value, path) = match item {
                 HoconValue::PathSubstitutionInParent(v) => {
                     let subst = HoconValue::PathSubstitution {
                         target: v,
                         optional: false,
                         original: None,
+                        default: None,
+                        location: None,
                     }
                     .substitute(config, &root, &full_path);
                     (subst, full_path.into_iter().rev().skip(1).rev().collect())
@@ -356,6 +727,16 @@ impl HoconInternal {
                 }
             };
 
+            let trace_path = config.tracer.as_ref().map(|_| {
+                crate::helper::join_path(
+                    &path
+                        .iter()
+                        .cloned()
+                        .map(HoconValue::key_string)
+                        .collect::<Vec<_>>(),
+                )
+            });
+
             let mut current_path = vec![];
             let mut current_node = Rc::clone(&root);
             let mut old_node_value_for_optional_substitution = None;
@@ -447,17 +828,48 @@ impl HoconInternal {
                 current_node = target_child;
             }
             let mut leaf = current_node.value.borrow_mut();
+            let leaf_value = leaf_value?;
+
+            if let (Some(tracer), Some(trace_path)) = (config.tracer.as_ref(), trace_path) {
+                let action = match leaf.deref() {
+                    Node::Leaf(HoconValue::Temp) => MergeAction::Set,
+                    Node::Leaf(HoconValue::Null(placeholder)) if placeholder == "0" => {
+                        MergeAction::Set
+                    }
+                    _ => MergeAction::Override,
+                };
+                tracer.record(trace_path, action, Some(source.deref().clone()));
+            }
 
-            *leaf = match leaf_value? {
+            *leaf = match leaf_value {
                 Node::Leaf(HoconValue::PathSubstitution {
                     target,
                     optional,
                     original: previously_set_original,
+                    default,
+                    location,
                 }) => Node::Leaf(HoconValue::PathSubstitution {
                     target,
                     optional,
-                    original: previously_set_original
-                        .or_else(|| old_node_value_for_optional_substitution.map(Box::new)),
+                    // placeholders of new fields and elements are not previous values
+                    original: previously_set_original.or_else(|| {
+                        old_node_value_for_optional_substitution
+                            .filter(|value| !matches!(value, HoconValue::Null(_)))
+                            .map(Box::new)
+                    }),
+                    default,
+                    location,
+                }),
+                Node::Leaf(HoconValue::FileSubstitution {
+                    path,
+                    optional: true,
+                    original: None,
+                }) => Node::Leaf(HoconValue::FileSubstitution {
+                    path,
+                    optional: true,
+                    original: old_node_value_for_optional_substitution
+                        .filter(|value| !matches!(value, HoconValue::Null(_)))
+                        .map(Box::new),
                 }),
                 v => v,
             };
@@ -471,6 +883,63 @@ impl HoconInternal {
                 .into_inner(),
         })
     }
+
+    /// Entries of the value of a substitution concatenated at `at_path`, if they can be
+    /// found now and are of the type of the parent, an array or an object
+    fn concatenated_entries(
+        subst: &HoconValue,
+        config: &HoconLoaderConfig,
+        root: &Rc<Child>,
+        at_path: &[HoconValue],
+    ) -> Option<Hash> {
+        let target = match subst {
+            HoconValue::PathSubstitution { target, .. } => target.to_path(),
+            _ => return None,
+        };
+        let (key, parent) = at_path.split_last()?;
+        if config.defers_substitution(&target, at_path) {
+            return None;
+        }
+        let children = match root.find_key(config, target) {
+            Ok(Node::Node { children, .. }) => children,
+            _ => return None,
+        };
+        let entries = match key {
+            HoconValue::Null(index) => children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| {
+                    (
+                        HoconValue::Null(format!("{}-{}", index, i)),
+                        child.value.borrow().entries(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            _ => children
+                .iter()
+                .map(|child| (child.key.clone(), child.value.borrow().entries()))
+                .collect(),
+        };
+        let in_array = matches!(key, HoconValue::Null(_));
+        if children.iter().any(|child| {
+            matches!(child.key, HoconValue::Integer(_) | HoconValue::Null(_)) != in_array
+        }) {
+            return None;
+        }
+        Some(
+            entries
+                .into_iter()
+                .flat_map(|(key, child_entries)| {
+                    child_entries.into_iter().map(move |(mut path, value)| {
+                        let mut full_path = parent.to_vec();
+                        full_path.push(key.clone());
+                        full_path.append(&mut path);
+                        (full_path, value)
+                    })
+                })
+                .collect(),
+        )
+    }
 }
 
 pub(crate) type Path = Vec<HoconValue>;
@@ -507,28 +976,56 @@ mod tests {
 
     #[test]
     fn missing_file_included() {
+        let config = HoconLoaderConfig {
+            include_depth: 5,
+            file_meta: Some(crate::ConfFileMeta::from_path(
+                std::path::Path::new("file.conf").to_path_buf(),
+            )),
+            ..Default::default()
+        };
         let val = dbg!(HoconInternal::from_include(
             Include::File(Cow::from("file.conf")),
-            &HoconLoaderConfig {
-                include_depth: 5,
-                file_meta: Some(crate::ConfFileMeta::from_path(
-                    std::path::Path::new("file.conf").to_path_buf()
-                )),
-                ..Default::default()
-            }
+            &config
         ))
         .expect("during test");
+        assert_eq!(val, HoconInternal { internal: vec![] });
 
         assert_eq!(
-            val,
-            HoconInternal {
-                internal: vec![(
-                    vec![HoconValue::String(Rc::from("file.conf"))],
-                    HoconValue::BadValue(crate::Error::Include {
-                        path: String::from("file.conf")
-                    })
-                )]
-            }
+            HoconInternal::from_include(
+                Include::Required(Box::new(Include::File(Cow::from("file.conf")))),
+                &config
+            ),
+            Err(crate::Error::Include {
+                path: String::from("file.conf")
+            })
         );
     }
+
+    #[test]
+    fn repeated_includes_are_parsed_once() {
+        let config = HoconLoaderConfig {
+            file_meta: Some(crate::ConfFileMeta::from_path(
+                std::path::Path::new("tests/data/include_cache/main.conf").to_path_buf(),
+            )),
+            ..Default::default()
+        };
+        let common = |config: &HoconLoaderConfig| {
+            HoconInternal::from_include(Include::File(Cow::from("common.conf")), config)
+                .expect("during test")
+        };
+
+        let included = common(&config);
+        HoconInternal::from_include(Include::File(Cow::from("a.conf")), &config)
+            .expect("during test");
+        HoconInternal::from_include(Include::File(Cow::from("b.conf")), &config)
+            .expect("during test");
+        assert_eq!(config.include_cache.borrow().len(), 3);
+        assert_eq!(format!("{:?}", common(&config)), format!("{included:?}"));
+
+        // the cached document is used instead of the file
+        for cached in config.include_cache.borrow_mut().values_mut() {
+            cached.0 = HoconInternal::empty();
+        }
+        assert_eq!(common(&config), HoconInternal { internal: vec![] });
+    }
 }
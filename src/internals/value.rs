@@ -3,6 +3,7 @@ use std::rc::Rc;
 
 use crate::Hocon;
 use crate::HoconLoaderConfig;
+use crate::document::{MergeAction, Resolution, Source, UnresolvedOptional};
 
 use super::intermediate::Child;
 use super::intermediate::HoconIntermediate;
@@ -21,8 +22,24 @@ pub(crate) enum HoconValue {
         target: Box<HoconValue>,
         optional: bool,
         original: Option<Box<HoconValue>>,
+        // inline default, like `8080` in `${?PORT:-8080}`
+        default: Option<Box<HoconValue>>,
+        location: Option<Rc<SubstitutionLocation>>,
     },
     PathSubstitutionInParent(Box<HoconValue>),
+    // Substitution concatenated to an array or an object, like `${?extra}` in `[1, 2] ${?extra}`
+    ConcatSubstitution(Box<HoconValue>),
+    // Substitution written inside a quoted string, like `${host}` in `"https://${host}/api"`,
+    // resolved as a string
+    InterpolatedSubstitution(Box<HoconValue>),
+    // Substitution with the content of a file, like `${file:/run/secrets/password}`, or
+    // `${?file:/run/secrets/password}` if it is optional
+    FileSubstitution {
+        path: Rc<str>,
+        optional: bool,
+        // value kept by an optional substitution if the file can not be read
+        original: Option<Box<HoconValue>>,
+    },
     ToConcatToArray {
         value: Box<HoconValue>,
         original_path: Rc<[HoconValue]>,
@@ -45,6 +62,33 @@ pub(crate) enum HoconValue {
     },
 }
 
+/// Where a substitution is written, to report it if it can not be resolved
+/// Paths followed to resolve a substitution, each with the source of the substitution that
+/// followed it
+pub(crate) type SubstitutionChain = Vec<(Vec<HoconValue>, Option<Source>)>;
+
+#[derive(Clone, Debug)]
+pub(crate) struct SubstitutionLocation {
+    // distance to the end of the input, the only position known while parsing
+    from_end: usize,
+    pub(crate) line: usize,
+    pub(crate) source: Source,
+    pub(crate) key: String,
+}
+
+impl SubstitutionLocation {
+    /// Location of a substitution starting at `input`, to be completed with
+    /// [`HoconValue::locate`] once the whole document is parsed
+    pub(crate) fn parsed(input: &str) -> Option<Rc<Self>> {
+        Some(Rc::new(Self {
+            from_end: input.len(),
+            line: 0,
+            source: Source::Str,
+            key: String::new(),
+        }))
+    }
+}
+
 impl HoconValue {
     pub(crate) fn maybe_concat(values: Vec<HoconValue>) -> HoconValue {
         let nb_values = values.len();
@@ -87,7 +131,7 @@ impl HoconValue {
         config: &HoconLoaderConfig,
         in_concat: bool,
         included_path: Option<Vec<HoconValue>>,
-        substituting_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
     ) -> Result<Hocon, crate::Error> {
         match self {
             HoconValue::Null(_) => Ok(Hocon::Null),
@@ -106,7 +150,9 @@ impl HoconValue {
             }
             HoconValue::Concat(values) => Ok(Hocon::String({
                 let nb_items = values.len();
-                values
+                // an interpolated substitution that can not be a string fails the whole string
+                let mut bad_value = None;
+                let value = values
                     .into_iter()
                     .enumerate()
                     .map(|item| match item {
@@ -119,22 +165,45 @@ impl HoconValue {
                         (_, v) => v,
                     })
                     .map(|v| {
-                        v.finalize(
+                        let interpolated = matches!(v, HoconValue::InterpolatedSubstitution(_));
+                        let value = v.finalize(
                             root,
                             config,
                             true,
                             included_path.clone(),
-                            substituting_path.clone(),
-                        )
+                            substitution_chain.clone(),
+                        );
+                        (interpolated, value)
                     })
-                    .filter_map(|v| v.ok().and_then(|v| v.as_internal_string()))
-                    .collect::<Vec<String>>()
-                    .join("")
+                    .filter_map(|(interpolated, v)| match v {
+                        // a cycle can not be skipped like a missing value
+                        Err(
+                            err @ (crate::Error::SubstitutionCycle { .. }
+                            | crate::Error::UnresolvedSubstitution { .. }
+                            | crate::Error::ResourceLimit { .. }
+                            | crate::Error::TooManyResolutionPasses { .. }),
+                        ) => Some(Err(err)),
+                        Err(err) if interpolated => Some(Err(err)),
+                        Ok(Hocon::BadValue(err)) if interpolated => {
+                            bad_value.get_or_insert(err);
+                            None
+                        }
+                        v => v.ok().and_then(|v| v.as_internal_string()).map(Ok),
+                    })
+                    .collect::<Result<Vec<String>, _>>()?
+                    .join("");
+                if let Some(err) = bad_value {
+                    return Ok(Hocon::BadValue(err));
+                }
+                config.limits.check_size(value.len())?;
+                value
             })),
             HoconValue::PathSubstitution {
                 target: v,
                 optional,
                 original,
+                default,
+                location,
             } => {
                 // second pass for substitution
                 let fixed_up_path = if let Some(included_path) = included_path.clone() {
@@ -148,45 +217,210 @@ impl HoconValue {
                 } else {
                     v.to_path()
                 };
-                if Some(fixed_up_path.clone()) == substituting_path {
+                let path_string = |path: &Vec<HoconValue>| {
+                    crate::helper::join_path(
+                        &path
+                            .iter()
+                            .cloned()
+                            .map(HoconValue::string_value)
+                            .collect::<Vec<_>>(),
+                    )
+                };
+                let cycle_start = substitution_chain
+                    .iter()
+                    .position(|(path, _)| *path == fixed_up_path);
+                if let (Some(_), true) = (cycle_start, optional) {
+                    // an optional substitution that loops back is not found
                     Ok(Hocon::Null)
+                } else if let Some(start) = cycle_start {
+                    let mut cycle = substitution_chain[start..]
+                        .iter()
+                        .map(|(path, _)| path_string(path))
+                        .collect::<Vec<_>>();
+                    // a path is defined where the substitution following the next one is
+                    // written, the last one by this substitution
+                    let mut origins = substitution_chain[start + 1..]
+                        .iter()
+                        .map(|(_, origin)| origin.clone())
+                        .chain(std::iter::once(location.as_ref().map(|l| l.source.clone())))
+                        .collect::<Vec<_>>();
+                    // start from the last path followed, usually the value being resolved
+                    cycle.rotate_right(1);
+                    cycle.push(cycle[0].clone());
+                    origins.rotate_right(1);
+                    origins.push(origins[0].clone());
+                    Ok(public_bad_value_or_err!(
+                        config,
+                        crate::Error::SubstitutionCycle {
+                            chain: cycle,
+                            origins,
+                        }
+                    ))
+                } else if substitution_chain.len() >= config.max_resolution_passes {
+                    Err(crate::Error::TooManyResolutionPasses {
+                        passes: config.max_resolution_passes,
+                        keys: substitution_chain
+                            .iter()
+                            .map(|(path, _)| path)
+                            .chain(std::iter::once(&fixed_up_path))
+                            .map(path_string)
+                            .collect(),
+                    })
                 } else {
-                    match (
-                        config.strict,
-                        config.system,
-                        root.tree
-                            .find_key(config, fixed_up_path.clone())
-                            .and_then(|v| {
-                                v.finalize(root, config, included_path, Some(fixed_up_path))
-                            }),
-                    ) {
-                        (_, true, Err(err)) | (_, true, Ok(Hocon::BadValue(err))) => {
-                            match (
-                                std::env::var(
-                                    v.to_path()
-                                        .into_iter()
-                                        .map(HoconValue::string_value)
-                                        .collect::<Vec<_>>()
-                                        .join("."),
+                    config.limits.expand(1)?;
+                    let mut chain = substitution_chain.clone();
+                    chain.push((
+                        fixed_up_path.clone(),
+                        location.as_ref().map(|l| l.source.clone()),
+                    ));
+                    let unresolved = || crate::Error::UnresolvedSubstitution {
+                        substitution: v.path_string(),
+                        key: location.as_ref().map(|l| l.key.clone()),
+                        origin: location.as_ref().map(|l| l.source.clone()),
+                        line: location.as_ref().map(|l| l.line),
+                    };
+                    let (value, resolution) = match root
+                        .tree
+                        .find_key(config, fixed_up_path)
+                        .and_then(|v| v.finalize(root, config, included_path, chain))
+                    {
+                        Err(
+                            err @ (crate::Error::ResourceLimit { .. }
+                            | crate::Error::TooManyResolutionPasses { .. }),
+                        ) => return Err(err),
+                        Err(
+                            err @ (crate::Error::SubstitutionCycle { .. }
+                            | crate::Error::UnresolvedSubstitution { .. }),
+                        )
+                        | Ok(Hocon::BadValue(err @ crate::Error::SubstitutionCycle { .. })) => (
+                            Ok(public_bad_value_or_err!(config, err)),
+                            Resolution::Unresolved,
+                        ),
+                        Err(err) | Ok(Hocon::BadValue(err))
+                            if config.env || config.use_system_properties =>
+                        {
+                            let system_value = config.system_value(&v.path_string());
+                            // reported once, when walking the document
+                            if optional && system_value.is_none() && substitution_chain.is_empty() {
+                                config
+                                    .unresolved_optionals
+                                    .borrow_mut()
+                                    .push(UnresolvedOptional {
+                                        path: location
+                                            .as_ref()
+                                            .map(|l| l.key.clone())
+                                            .unwrap_or_default(),
+                                        substitution: v.path_string(),
+                                    });
+                            }
+                            match (system_value, optional, default.or(original)) {
+                                (Some((val, resolution)), _, _) => {
+                                    (Ok(Hocon::String(val)), resolution)
+                                }
+                                (_, true, Some(val)) => {
+                                    (val.simple_finalize(), Resolution::PreviousValue)
+                                }
+                                (_, false, _) if config.strict => {
+                                    (Err(unresolved()), Resolution::Unresolved)
+                                }
+                                _ => (
+                                    Ok(public_bad_value_or_err!(config, err)),
+                                    Resolution::Unresolved,
                                 ),
-                                optional,
-                                original,
-                            ) {
-                                (Ok(val), _, _) => Ok(Hocon::String(val)),
-                                (_, true, Some(val)) => val.simple_finalize(),
-                                _ => Ok(public_bad_value_or_err!(config, err)),
                             }
                         }
-                        (true, _, Err(err)) | (true, _, Ok(Hocon::BadValue(err))) => Err(err),
-                        (_, _, v) => v,
+                        // neither an inline default nor the value an optional substitution
+                        // keeps need the environment
+                        Err(_) | Ok(Hocon::BadValue(_))
+                            if default.is_some() || (optional && original.is_some()) =>
+                        {
+                            (
+                                default
+                                    .or(original)
+                                    .map_or(Ok(Hocon::Null), |val| val.simple_finalize()),
+                                Resolution::PreviousValue,
+                            )
+                        }
+                        Err(err) | Ok(Hocon::BadValue(err)) if config.strict => {
+                            return Err(if optional { err } else { unresolved() });
+                        }
+                        v @ (Err(_) | Ok(Hocon::BadValue(_))) => (v, Resolution::Unresolved),
+                        v => (v, Resolution::Document),
+                    };
+                    // substitutions are traced when walking the document, not when
+                    // finalizing the value of another substitution
+                    if let (Some(tracer), true) =
+                        (config.tracer.as_ref(), substitution_chain.is_empty())
+                    {
+                        tracer.record_resolution(v.path_string(), resolution);
                     }
+                    value
                 }
             }
+            HoconValue::ConcatSubstitution(subst) => {
+                subst.finalize(root, config, in_concat, included_path, substitution_chain)
+            }
+            HoconValue::InterpolatedSubstitution(subst) => {
+                let (optional, path) = match subst.as_ref() {
+                    HoconValue::PathSubstitution {
+                        target, optional, ..
+                    } => (*optional, target.path_string()),
+                    _ => (false, String::new()),
+                };
+                match subst.finalize(root, config, true, included_path, substitution_chain) {
+                    value if optional && is_not_found(&value) => Ok(Hocon::String(String::new())),
+                    Ok(Hocon::Hash(_) | Hocon::Array(_)) => Ok(public_bad_value_or_err!(
+                        config,
+                        crate::Error::InvalidConcatenation { path }
+                    )),
+                    value => value,
+                }
+            }
+            HoconValue::FileSubstitution {
+                path,
+                optional,
+                original,
+            } => {
+                let read = config
+                    .check_include_root(std::path::Path::new(path.as_ref()))
+                    .and_then(|_| {
+                        std::fs::read_to_string(path.as_ref()).map_err(|_| crate::Error::File {
+                            path: path.to_string(),
+                        })
+                    });
+                let (value, resolution) = match (read, original) {
+                    (Ok(content), _) => (
+                        Hocon::String(String::from(content.trim_end_matches(['\n', '\r']))),
+                        Resolution::File,
+                    ),
+                    // an optional substitution keeps the previous value of a missing file
+                    (Err(crate::Error::File { .. }), Some(original)) if optional => (
+                        original.finalize(
+                            root,
+                            config,
+                            in_concat,
+                            included_path,
+                            substitution_chain.clone(),
+                        )?,
+                        Resolution::PreviousValue,
+                    ),
+                    (Err(err), _) => (
+                        public_bad_value_or_err!(config, err),
+                        Resolution::Unresolved,
+                    ),
+                };
+                if let (Some(tracer), true) =
+                    (config.tracer.as_ref(), substitution_chain.is_empty())
+                {
+                    tracer.record_resolution(format!("file:{}", path), resolution);
+                }
+                Ok(value)
+            }
             HoconValue::Included {
                 value,
                 include_root,
                 ..
-            } => value.finalize(root, config, in_concat, include_root, None),
+            } => value.finalize(root, config, in_concat, include_root, substitution_chain),
             // These cases should have been replaced during substitution
             // and not exist anymore at this point
             HoconValue::Temp => unreachable!(),
@@ -223,6 +457,202 @@ impl HoconValue {
         }
     }
 
+    /// Whether this is an optional substitution, like `${?HOME}`
+    pub(crate) fn is_optional_substitution(&self) -> bool {
+        match self {
+            HoconValue::PathSubstitution { optional, .. }
+            | HoconValue::FileSubstitution { optional, .. } => *optional,
+            HoconValue::Included { value, .. } => value.is_optional_substitution(),
+            _ => false,
+        }
+    }
+
+    /// Whether this is a substitution concatenated to its parent array or object
+    pub(crate) fn is_concat_substitution(&self) -> bool {
+        match self {
+            HoconValue::ConcatSubstitution(_) => true,
+            HoconValue::Included { value, .. } => value.is_concat_substitution(),
+            _ => false,
+        }
+    }
+
+    /// Finalize a substitution concatenated to its parent array or object. A missing optional
+    /// substitution is concatenated as an empty value, and returns `None`
+    pub(crate) fn finalize_concatenated(
+        self,
+        in_array: bool,
+        root: &HoconIntermediate,
+        config: &HoconLoaderConfig,
+        included_path: Option<Vec<HoconValue>>,
+        substitution_chain: SubstitutionChain,
+    ) -> Result<Option<Hocon>, crate::Error> {
+        match self {
+            HoconValue::Included {
+                value,
+                include_root,
+                ..
+            } => value.finalize_concatenated(
+                in_array,
+                root,
+                config,
+                include_root,
+                substitution_chain,
+            ),
+            HoconValue::ConcatSubstitution(subst) => {
+                let (optional, path) = match subst.as_ref() {
+                    HoconValue::PathSubstitution {
+                        target, optional, ..
+                    } => (*optional, target.path_string()),
+                    _ => (false, String::new()),
+                };
+                match (
+                    subst.finalize(root, config, false, included_path, substitution_chain),
+                    optional,
+                ) {
+                    (ref value, true) if is_not_found(value) => Ok(None),
+                    (Ok(Hocon::Null), true) => Ok(None),
+                    (Ok(value @ Hocon::Array(_)), _) if in_array => Ok(Some(value)),
+                    (Ok(value @ Hocon::Hash(_)), _) if !in_array => Ok(Some(value)),
+                    (Ok(value @ Hocon::BadValue(_)), _) => Ok(Some(value)),
+                    (Ok(_), _) => Ok(Some(public_bad_value_or_err!(
+                        config,
+                        crate::Error::InvalidConcatenation { path }
+                    ))),
+                    (Err(err), _) => Err(err),
+                }
+            }
+            value => value
+                .finalize(root, config, false, included_path, substitution_chain)
+                .map(Some),
+        }
+    }
+
+    /// Complete the locations of the substitutions of this value, set at `key`. `document`
+    /// gives the line of a position while parsing, and the source of the document. It is
+    /// `None` for values of an included document, that are already located, and only get
+    /// their key updated
+    pub(crate) fn locate(
+        self,
+        keys: &[String],
+        document: Option<(&dyn Fn(usize) -> usize, &Source)>,
+    ) -> HoconValue {
+        match self {
+            HoconValue::PathSubstitution {
+                target,
+                optional,
+                original,
+                default,
+                location: Some(location),
+            } => {
+                let mut location = location.as_ref().clone();
+                if let Some((line_of, source)) = document {
+                    location.line = line_of(location.from_end);
+                    location.source = source.clone();
+                }
+                location.key = crate::helper::join_path(keys);
+                HoconValue::PathSubstitution {
+                    target,
+                    optional,
+                    original,
+                    default,
+                    location: Some(Rc::new(location)),
+                }
+            }
+            HoconValue::Concat(values) => HoconValue::Concat(
+                values
+                    .into_iter()
+                    .map(|value| value.locate(keys, document))
+                    .collect(),
+            ),
+            // concatenated to its parent, at a generated key
+            HoconValue::ConcatSubstitution(subst) => HoconValue::ConcatSubstitution(Box::new(
+                subst.locate(&keys[..keys.len().saturating_sub(1)], document),
+            )),
+            HoconValue::InterpolatedSubstitution(subst) => {
+                HoconValue::InterpolatedSubstitution(Box::new(subst.locate(keys, document)))
+            }
+            HoconValue::ToConcatToArray {
+                value,
+                original_path,
+                item_id,
+            } => HoconValue::ToConcatToArray {
+                value: Box::new(value.locate(keys, document)),
+                original_path,
+                item_id,
+            },
+            HoconValue::Included {
+                value,
+                include_root,
+                original_path,
+            } => HoconValue::Included {
+                value: Box::new(value.locate(keys, None)),
+                include_root,
+                original_path,
+            },
+            v => v,
+        }
+    }
+
+    /// Number of values held, counted when a substitution copies them
+    pub(crate) fn size(&self) -> usize {
+        match self {
+            HoconValue::Concat(values) => values.iter().map(HoconValue::size).sum(),
+            HoconValue::Included { value, .. }
+            | HoconValue::ConcatSubstitution(value)
+            | HoconValue::InterpolatedSubstitution(value) => value.size(),
+            _ => 1,
+        }
+    }
+
+    /// Whether this value still needs substitutions to be resolved
+    pub(crate) fn has_unresolved_substitution(&self) -> bool {
+        match self {
+            HoconValue::PathSubstitution { .. } | HoconValue::FileSubstitution { .. } => true,
+            HoconValue::Concat(values) => {
+                values.iter().any(HoconValue::has_unresolved_substitution)
+            }
+            HoconValue::Included { value, .. } | HoconValue::InterpolatedSubstitution(value) => {
+                value.has_unresolved_substitution()
+            }
+            _ => false,
+        }
+    }
+
+    /// Check that the extensions to HOCON used by this value, like inline defaults in
+    /// substitutions, are enabled
+    pub(crate) fn extensions_enabled(&self, config: &HoconLoaderConfig) -> bool {
+        match self {
+            HoconValue::PathSubstitution {
+                default: Some(_), ..
+            } => config.substitution_defaults,
+            HoconValue::FileSubstitution { .. } => config.file_substitutions,
+            HoconValue::Concat(values) => values.iter().all(|v| v.extensions_enabled(config)),
+            HoconValue::InterpolatedSubstitution(subst) => subst.extensions_enabled(config),
+            _ => true,
+        }
+    }
+
+    pub(crate) fn key_string(self) -> String {
+        match self {
+            // elements of an array concatenated to a substitution are indexed with
+            // an unique prefix followed by the index
+            HoconValue::Null(index) => index
+                .rsplit('-')
+                .next()
+                .map(String::from)
+                .unwrap_or_default(),
+            key => key.string_value(),
+        }
+    }
+
+    pub(crate) fn path_string(&self) -> String {
+        self.to_path()
+            .into_iter()
+            .map(HoconValue::string_value)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
     pub(crate) fn substitute(
         self,
         config: &HoconLoaderConfig,
@@ -234,6 +664,23 @@ impl HoconValue {
                 target: path,
                 optional,
                 original,
+                default,
+                location,
+            } if config.defers_substitution(&path.to_path(), at_path) => {
+                Ok(Node::Leaf(HoconValue::PathSubstitution {
+                    target: path,
+                    optional,
+                    original,
+                    default,
+                    location,
+                }))
+            }
+            HoconValue::PathSubstitution {
+                target: path,
+                optional,
+                original,
+                default,
+                location,
             } => {
                 match current_tree.find_key(config, path.to_path()) {
                     Err(_) | Ok(Node::Leaf(HoconValue::BadValue(_))) => {
@@ -242,9 +689,28 @@ impl HoconValue {
                             target: path,
                             optional,
                             original,
+                            default,
+                            location,
+                        }))
+                    }
+                    // If node is itself waiting for a substitution, keep following the
+                    // chain on second pass instead of copying it
+                    Ok(Node::Leaf(ref value))
+                        if value.has_unresolved_substitution() && path.to_path() != at_path =>
+                    {
+                        Ok(Node::Leaf(HoconValue::PathSubstitution {
+                            target: path,
+                            optional,
+                            original,
+                            default,
+                            location,
                         }))
                     }
-                    Ok(v) => Ok(v.deep_clone()),
+                    Ok(v) => {
+                        config.limits.expand(v.size())?;
+                        Self::trace_resolution(config, at_path, &path);
+                        Ok(v.deep_clone())
+                    }
                 }
             }
             HoconValue::Concat(values) => {
@@ -254,6 +720,7 @@ impl HoconValue {
                         .map(|v| v.substitute(config, current_tree, at_path))
                         .map(|v| match v {
                             Ok(node) => Ok(node),
+                            Err(err @ crate::Error::ResourceLimit { .. }) => Err(err),
                             Err(err) => Ok(Node::Leaf(bad_value_or_err!(config, err))),
                         })
                         .collect::<Vec<_>>(),
@@ -305,6 +772,23 @@ impl HoconValue {
                     )))
                 }
             }
+            HoconValue::InterpolatedSubstitution(subst) => {
+                let path = match subst.as_ref() {
+                    HoconValue::PathSubstitution { target, .. } => target.path_string(),
+                    _ => String::new(),
+                };
+                // an object or an array can not be written in a string
+                match subst.substitute(config, current_tree, at_path)? {
+                    Node::Leaf(value) => Ok(Node::Leaf(HoconValue::InterpolatedSubstitution(
+                        Box::new(value),
+                    ))),
+                    Node::Node { .. } => {
+                        Ok(Node::Leaf(HoconValue::InterpolatedSubstitution(Box::new(
+                            bad_value_or_err!(config, crate::Error::InvalidConcatenation { path }),
+                        ))))
+                    }
+                }
+            }
             HoconValue::EmptyObject => Ok(Node::Node {
                 children: vec![],
                 key_hint: Some(KeyType::String),
@@ -332,6 +816,8 @@ impl HoconValue {
                         match current_tree.find_key(config, fixed_up_path) {
                             Ok(Node::Leaf(HoconValue::BadValue(_))) | Err(_) => (),
                             Ok(new_value) => {
+                                config.limits.expand(new_value.size())?;
+                                Self::trace_resolution(config, at_path, &path);
                                 return Ok(new_value.deep_clone());
                             }
                         }
@@ -367,6 +853,41 @@ impl HoconValue {
             v => Ok(Node::Leaf(v)),
         }
     }
+
+    fn trace_resolution(config: &HoconLoaderConfig, at_path: &[HoconValue], target: &HoconValue) {
+        if let Some(tracer) = config.tracer.as_ref() {
+            tracer.record(
+                crate::helper::join_path(
+                    &at_path
+                        .iter()
+                        .cloned()
+                        .map(HoconValue::key_string)
+                        .collect::<Vec<_>>(),
+                ),
+                MergeAction::Resolve {
+                    substitution: target.path_string(),
+                    resolution: Resolution::Document,
+                },
+                None,
+            );
+        }
+    }
+}
+
+/// Whether an optional substitution finalized to `value` could not be found, to leave it out of
+/// its array, object or concatenation
+pub(crate) fn is_not_found(value: &Result<Hocon, crate::Error>) -> bool {
+    matches!(
+        value,
+        Err(err) | Ok(Hocon::BadValue(err))
+            if !matches!(
+                err,
+                crate::Error::SubstitutionCycle { .. }
+                    | crate::Error::ResourceLimit { .. }
+                    | crate::Error::TooManyResolutionPasses { .. }
+                    | crate::Error::IncludeOutsideRoot { .. }
+            )
+    )
 }
 
 impl PartialEq for HoconValue {
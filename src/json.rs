@@ -0,0 +1,188 @@
+use std::convert::TryFrom;
+
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::Error;
+use crate::Hocon;
+
+/// Convert a JSON value. Numbers are converted to [`Hocon::Integer`](enum.Hocon.html#variant.Integer)
+/// when they are integers fitting in an `i64`, and to [`Hocon::Real`](enum.Hocon.html#variant.Real)
+/// otherwise.
+///
+/// # Errors
+///
+/// * [`Error::Deserialization`](enum.Error.html#variant.Deserialization) if a number can't
+///   be represented as an `i64` or an `f64`
+///
+/// # Example
+///
+/// ```rust
+/// # use std::convert::TryFrom;
+/// # use hocon::{Hocon, HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// # #[cfg(feature = "json-support")] {
+/// let json = serde_json::json!({ "replicas": 3, "image": { "tag": "1.2" } });
+///
+/// let doc = HoconLoader::new()
+///     .load_str(r#"{ replicas: 1, image { name: app, tag: latest } }"#)?
+///     .load_hocon(Hocon::try_from(json)?)
+///     .hocon()?;
+///
+/// assert_eq!(doc["replicas"], Hocon::Integer(3));
+/// assert_eq!(doc["image"]["name"].as_string(), Some(String::from("app")));
+/// assert_eq!(doc["image"]["tag"].as_string(), Some(String::from("1.2")));
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Feature
+///
+/// This conversion depends on feature `json-support`
+impl TryFrom<Value> for Hocon {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Value::Null => Hocon::Null,
+            Value::Bool(b) => Hocon::Boolean(b),
+            Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+                (Some(i), _) => Hocon::Integer(i),
+                (None, Some(f)) => Hocon::Real(f),
+                (None, None) => {
+                    return Err(Error::Deserialization {
+                        message: format!("number {} can't be represented", n),
+                    });
+                }
+            },
+            Value::String(s) => Hocon::String(s),
+            Value::Array(values) => Hocon::Array(
+                values
+                    .into_iter()
+                    .map(Hocon::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Object(map) => Hocon::Hash(
+                map.into_iter()
+                    .map(|(key, value)| Hocon::try_from(value).map(|value| (key, value)))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+impl Hocon {
+    /// Convert to a JSON value.
+    ///
+    /// * [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) are left out of objects and
+    ///   arrays, and convert to `null` when they are not inside one
+    /// * [`Hocon::Real`](enum.Hocon.html#variant.Real) that are whole numbers in the range
+    ///   of an `i64` convert to JSON integers, as HOCON documents can't tell `1.0` from `1`
+    ///   once read from a JSON document. Infinite and NaN values, that JSON can't represent,
+    ///   convert to `null`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # #[cfg(feature = "json-support")] {
+    /// let doc = HoconLoader::new()
+    ///     .no_system()
+    ///     .load_str(r#"{ a: 5.0, b: 1.5, c: ${missing}, d: [true, ${missing}] }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(
+    ///     doc.to_json_value(),
+    ///     serde_json::json!({ "a": 5, "b": 1.5, "d": [true] })
+    /// );
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `json-support`
+    pub fn to_json_value(&self) -> Value {
+        self.to_json().unwrap_or(Value::Null)
+    }
+
+    fn to_json(&self) -> Option<Value> {
+        match self {
+            Hocon::Boolean(b) => Some(Value::Bool(*b)),
+            Hocon::Integer(i) => Some(Value::Number(Number::from(*i))),
+            Hocon::Real(f) => Some(
+                if f.fract() == 0.0 && *f >= i64::MIN as f64 && *f <= i64::MAX as f64 {
+                    Value::Number(Number::from(*f as i64))
+                } else {
+                    Number::from_f64(*f).map_or(Value::Null, Value::Number)
+                },
+            ),
+            Hocon::String(s) => Some(Value::String(s.clone())),
+            Hocon::Array(values) => Some(Value::Array(
+                values.iter().filter_map(Hocon::to_json).collect(),
+            )),
+            Hocon::Hash(hash) => Some(Value::Object(
+                hash.iter()
+                    .filter_map(|(key, value)| value.to_json().map(|value| (key.clone(), value)))
+                    .collect(),
+            )),
+            Hocon::Null => Some(Value::Null),
+            Hocon::BadValue(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_from_json() {
+        let json = serde_json::json!({
+            "a": [1, -2, 3.5, u64::MAX],
+            "b": { "c": null, "d": true },
+            "e": "f",
+        });
+
+        let hocon = Hocon::try_from(json).expect("during test");
+
+        assert_eq!(
+            hocon["a"],
+            Hocon::Array(vec![
+                Hocon::Integer(1),
+                Hocon::Integer(-2),
+                Hocon::Real(3.5),
+                Hocon::Real(u64::MAX as f64),
+            ])
+        );
+        assert_eq!(hocon["b"]["c"], Hocon::Null);
+        assert_eq!(hocon["b"]["d"], Hocon::Boolean(true));
+        assert_eq!(hocon["e"], Hocon::String(String::from("f")));
+    }
+
+    #[test]
+    fn can_convert_to_json() {
+        let mut hocon = crate::hocon!({
+            a = [1, 2.0, 2.5, f64::NAN, 1e300],
+            b { c = null, d = "e" },
+        });
+        hocon
+            .set("b.f", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        assert_eq!(
+            hocon.to_json_value(),
+            serde_json::json!({
+                "a": [1, 2, 2.5, null, 1e300],
+                "b": { "c": null, "d": "e" },
+            })
+        );
+        assert_eq!(
+            Hocon::BadValue(Error::MissingKey).to_json_value(),
+            Value::Null
+        );
+    }
+}
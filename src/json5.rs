@@ -0,0 +1,352 @@
+use linked_hash_map::LinkedHashMap;
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+
+/// Object at the root of JSON5 document `text`, as described in <https://spec.json5.org>
+pub(crate) fn parse(text: &str) -> Result<Hocon> {
+    let mut parser = Parser { text, position: 0 };
+    parser.skip_whitespace()?;
+    let root = match parser.peek() {
+        Some('{') => parser.object()?,
+        _ => return Err(Error::Parse),
+    };
+    parser.skip_whitespace()?;
+    if parser.position < text.len() {
+        return Err(Error::Parse);
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(Error::Parse),
+        }
+    }
+
+    /// Skip whitespace and comments
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("//") {
+                let end = rest.find(is_line_terminator).unwrap_or(rest.len());
+                self.position += end;
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment.find("*/").ok_or(Error::Parse)?;
+                self.position += end + 4;
+            } else if self.peek().is_some_and(is_whitespace) {
+                self.next();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Hocon> {
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some(quote @ ('"' | '\'')) => self.string(quote).map(Hocon::String),
+            Some('0'..='9' | '+' | '-' | '.' | 'I' | 'N') => self.number(),
+            Some(_) => match self.identifier()?.as_str() {
+                "true" => Ok(Hocon::Boolean(true)),
+                "false" => Ok(Hocon::Boolean(false)),
+                "null" => Ok(Hocon::Null),
+                _ => Err(Error::Parse),
+            },
+            None => Err(Error::Parse),
+        }
+    }
+
+    fn object(&mut self) -> Result<Hocon> {
+        self.expect('{')?;
+        let mut hash = LinkedHashMap::new();
+        loop {
+            self.skip_whitespace()?;
+            let key = match self.peek() {
+                Some('}') => break,
+                Some(quote @ ('"' | '\'')) => self.string(quote)?,
+                Some(_) => self.identifier()?,
+                None => return Err(Error::Parse),
+            };
+            self.skip_whitespace()?;
+            self.expect(':')?;
+            self.skip_whitespace()?;
+            let value = self.value()?;
+            // as in JSON, a repeated key replaces the previous value
+            hash.remove(&key);
+            hash.insert(key, value);
+            self.skip_whitespace()?;
+            match self.next() {
+                Some(',') => {}
+                Some('}') => return Ok(Hocon::Hash(hash)),
+                _ => return Err(Error::Parse),
+            }
+        }
+        self.next();
+        Ok(Hocon::Hash(hash))
+    }
+
+    fn array(&mut self) -> Result<Hocon> {
+        self.expect('[')?;
+        let mut array = vec![];
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                break;
+            }
+            array.push(self.value()?);
+            self.skip_whitespace()?;
+            match self.next() {
+                Some(',') => {}
+                Some(']') => return Ok(Hocon::Array(array)),
+                _ => return Err(Error::Parse),
+            }
+        }
+        self.next();
+        Ok(Hocon::Array(array))
+    }
+
+    /// An ECMAScript identifier, the unquoted keys of JSON5
+    fn identifier(&mut self) -> Result<String> {
+        let mut identifier = String::new();
+        loop {
+            let c = match self.peek() {
+                Some('\\') => {
+                    self.next();
+                    self.expect('u')?;
+                    self.unicode_escape()?
+                }
+                Some(c) if c == '$' || c == '_' || c.is_alphanumeric() => {
+                    self.next();
+                    c
+                }
+                _ => break,
+            };
+            if identifier.is_empty() && c.is_numeric() {
+                return Err(Error::Parse);
+            }
+            identifier.push(c);
+        }
+        if identifier.is_empty() {
+            Err(Error::Parse)
+        } else {
+            Ok(identifier)
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<String> {
+        self.expect(quote)?;
+        let mut string = String::new();
+        loop {
+            match self.next().ok_or(Error::Parse)? {
+                c if c == quote => return Ok(string),
+                '\\' => match self.next().ok_or(Error::Parse)? {
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'v' => string.push('\u{b}'),
+                    '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => string.push('\0'),
+                    'x' => {
+                        let code = self.hex_digits(2)?;
+                        string.push(char::from_u32(code).ok_or(Error::Parse)?);
+                    }
+                    'u' => string.push(self.unicode_escape()?),
+                    // a backslash before a line terminator continues the string on the next line
+                    '\r' => {
+                        if self.peek() == Some('\n') {
+                            self.next();
+                        }
+                    }
+                    c if is_line_terminator(c) => {}
+                    '1'..='9' | '0' => return Err(Error::Parse),
+                    c => string.push(c),
+                },
+                '\n' | '\r' => return Err(Error::Parse),
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn hex_digits(&mut self, count: usize) -> Result<u32> {
+        let digits = self.rest().get(..count).ok_or(Error::Parse)?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| Error::Parse)?;
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::Parse);
+        }
+        self.position += count;
+        Ok(value)
+    }
+
+    /// Character of `\uXXXX`, after the `\u`, with the low surrogate of a pair
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex_digits(4)?;
+        if (0xd800..0xdc00).contains(&high) && self.rest().starts_with("\\u") {
+            self.position += 2;
+            let low = self.hex_digits(4)?;
+            let code = 0x10000 + ((high - 0xd800) << 10) + low.wrapping_sub(0xdc00);
+            return char::from_u32(code)
+                .filter(|_| (0xdc00..0xe000).contains(&low))
+                .ok_or(Error::Parse);
+        }
+        char::from_u32(high).ok_or(Error::Parse)
+    }
+
+    fn number(&mut self) -> Result<Hocon> {
+        let negative = match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.next();
+                sign == '-'
+            }
+            _ => false,
+        };
+        let sign = if negative { -1.0 } else { 1.0 };
+        let rest = self.rest();
+        if rest.starts_with("Infinity") {
+            self.position += "Infinity".len();
+            return Ok(Hocon::Real(sign * f64::INFINITY));
+        }
+        if rest.starts_with("NaN") {
+            self.position += "NaN".len();
+            return Ok(Hocon::Real(f64::NAN));
+        }
+        if let Some(hex) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+            let len = hex
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(hex.len());
+            let value = i64::from_str_radix(&hex[..len], 16).map_err(|_| Error::Parse)?;
+            self.position += 2 + len;
+            return Ok(Hocon::Integer(if negative { -value } else { value }));
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')))
+            .unwrap_or(rest.len());
+        let number = &rest[..len];
+        let mantissa = number.split(['e', 'E']).next().unwrap_or_default();
+        // as in JSON, only zero can start with `0`
+        if !mantissa.bytes().any(|c| c.is_ascii_digit())
+            || (mantissa.starts_with('0') && mantissa.len() > 1 && !mantissa[1..].starts_with('.'))
+        {
+            return Err(Error::Parse);
+        }
+        let value = if mantissa.contains('.') || number.len() > mantissa.len() {
+            // Rust does not parse a trailing `.`
+            let number = number.replacen(".e", ".0e", 1).replacen(".E", ".0E", 1);
+            let number = number.strip_suffix('.').unwrap_or(&number);
+            Hocon::Real(sign * number.parse::<f64>().map_err(|_| Error::Parse)?)
+        } else {
+            match number.parse::<i64>() {
+                Ok(value) => Hocon::Integer(if negative { -value } else { value }),
+                Err(_) => Hocon::Real(sign * number.parse::<f64>().map_err(|_| Error::Parse)?),
+            }
+        };
+        self.position += len;
+        Ok(value)
+    }
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\u{b}' | '\u{c}' | ' ' | '\u{a0}' | '\u{feff}')
+        || is_line_terminator(c)
+        || (c.is_whitespace() && !c.is_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_json5() {
+        let doc = parse(
+            r#"// a JSON5 document
+            {
+              unquoted: 'single quoted',
+              "double": "quoted\x41é\
+ continued",
+              hex: 0xFF,
+              negative: -0x10,
+              leading: .5,
+              trailing: 5.,
+              positive: +1,
+              exponent: 1e3,
+              infinity: -Infinity,
+              /* block
+                 comment */
+              array: [1, 'two', null, true,],
+              nested: { $key_1: false, },
+            }"#,
+        )
+        .expect("during test");
+        assert_eq!(
+            doc["unquoted"],
+            Hocon::String(String::from("single quoted"))
+        );
+        assert_eq!(
+            doc["double"],
+            Hocon::String(String::from("quotedAé continued"))
+        );
+        assert_eq!(doc["hex"], Hocon::Integer(255));
+        assert_eq!(doc["negative"], Hocon::Integer(-16));
+        assert_eq!(doc["leading"], Hocon::Real(0.5));
+        assert_eq!(doc["trailing"], Hocon::Real(5.0));
+        assert_eq!(doc["positive"], Hocon::Integer(1));
+        assert_eq!(doc["exponent"], Hocon::Real(1000.0));
+        assert_eq!(doc["infinity"], Hocon::Real(f64::NEG_INFINITY));
+        assert_eq!(
+            doc["array"],
+            Hocon::Array(vec![
+                Hocon::Integer(1),
+                Hocon::String(String::from("two")),
+                Hocon::Null,
+                Hocon::Boolean(true),
+            ])
+        );
+        assert_eq!(doc["nested"]["$key_1"], Hocon::Boolean(false));
+    }
+
+    #[test]
+    fn can_not_parse_invalid_json5() {
+        for invalid in [
+            "{ a: 1,, }",
+            "{ a: 'unterminated }",
+            "{ 1a: 1 }",
+            "{ a: 01 }",
+            "{ a: unquoted }",
+            "{ a: 1 } trailing",
+            "{ a: 1 /* unterminated }",
+            "[1, 2]",
+            "",
+        ] {
+            assert_eq!(parse(invalid), Err(Error::Parse), "{invalid}");
+        }
+    }
+}
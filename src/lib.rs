@@ -152,7 +152,9 @@
 //! This feature enable fetching URLs in includes  with `include url("http://mydomain.com/myfile.conf")` (see
 //! [spec](https://github.com/lightbend/config/blob/master/HOCON.md#include-syntax)). If disabled,
 //! includes will only load local files specified with `include "path/to/file.conf"` or
-//! `include file("path/to/file.conf")`.
+//! `include file("path/to/file.conf")`. An `HttpFetcher` can be
+//! registered with `HoconLoader::http_fetcher` to fetch URLs with another client than `reqwest`,
+//! even without this feature.
 //!
 //! ### `serde-support`
 //!
@@ -180,7 +182,9 @@
 //!  ```
 //!
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
 
 mod internals;
 mod parser;
@@ -192,6 +196,58 @@ pub use error::Result;
 pub(crate) mod helper;
 mod loader_config;
 pub(crate) use loader_config::*;
+pub use loader_config::{Encoding, Format};
+mod builder;
+pub mod document;
+pub use builder::HoconBuilder;
+mod diff;
+pub use diff::Change;
+mod editor;
+pub use editor::HoconEditor;
+mod env;
+pub use env::{EnvOverrides, KeyCase};
+mod archive;
+mod encoding;
+mod fetch;
+#[cfg(feature = "url-support-ureq")]
+pub use fetch::UreqFetcher;
+pub use fetch::{HttpFetcher, UrlFilter};
+#[cfg(feature = "url-support")]
+pub use fetch::{ReqwestFetcher, UrlRequests};
+mod properties;
+pub use properties::PropertiesOptions;
+mod render;
+pub use render::{BadValues, FloatFormat, JsonOptions, Quoting, RenderOptions, Separator, escape};
+mod remote;
+#[cfg(feature = "consul")]
+pub use remote::ConsulSource;
+pub use remote::RemoteSource;
+mod include;
+mod json5;
+pub use include::{DirectoryIncludes, IncludeOrder, IncludeResolver, IncludeTarget};
+mod stack;
+mod strict_json;
+mod syntax;
+pub use stack::ConfigStack;
+mod resolve;
+pub use resolve::ResolveOptions;
+#[cfg(feature = "async")]
+mod runtime;
+#[cfg(feature = "async")]
+pub use runtime::AsyncRuntime;
+#[cfg(feature = "smol")]
+pub use runtime::SmolRuntime;
+#[cfg(feature = "tokio")]
+pub use runtime::TokioRuntime;
+pub mod raw;
+use document::{HoconDocument, Layer, Priority, Tracer};
+
+#[cfg(feature = "json-support")]
+mod json;
+#[cfg(feature = "toml-support")]
+mod toml;
+#[cfg(feature = "yaml-support")]
+mod yaml;
 
 #[cfg(feature = "serde-support")]
 mod serde;
@@ -233,7 +289,7 @@ pub use crate::serde::de;
 #[derive(Debug, Clone)]
 pub struct HoconLoader {
     config: HoconLoaderConfig,
-    internal: internals::HoconInternal,
+    layers: Vec<Layer>,
 }
 
 impl Default for HoconLoader {
@@ -247,11 +303,12 @@ impl HoconLoader {
     pub fn new() -> Self {
         Self {
             config: HoconLoaderConfig::default(),
-            internal: internals::HoconInternal::empty(),
+            layers: vec![],
         }
     }
 
-    /// Disable System environment substitutions
+    /// Disable System substitutions, with environment variables and
+    /// [system properties](struct.HoconLoader.html#method.system_properties)
     ///
     /// # Example HOCON document
     ///
@@ -264,226 +321,2581 @@ impl HoconLoader {
     /// }
     /// ```
     ///
-    /// with system:
+    /// with system:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("SHELL", "/bin/bash") };
+    /// # let example = r#"{system.shell: ${SHELL}}"#;
+    /// assert_eq!(
+    ///     HoconLoader::new().load_str(example)?.hocon()?["system"]["shell"],
+    ///     Hocon::String(String::from("/bin/bash"))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// without system:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{system.shell: ${SHELL}}"#;
+    /// assert_eq!(
+    ///     HoconLoader::new().no_system().load_str(example)?.hocon()?["system"]["shell"],
+    ///     Hocon::BadValue(Error::KeyNotFound { key: String::from("SHELL") })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn no_system(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                env: false,
+                use_system_properties: false,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Disable substitutions with environment variables, still using
+    /// [system properties](struct.HoconLoader.html#method.system_properties)
+    pub fn no_env(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                env: false,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Disable substitutions with
+    /// [system properties](struct.HoconLoader.html#method.system_properties), still using
+    /// environment variables
+    pub fn no_system_properties(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                use_system_properties: false,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Register system properties for the application, like the `-D` properties of the JVM.
+    /// A substitution that is not found in the documents is looked for in the properties by
+    /// its path, then in the environment variables. This adds to the properties already
+    /// registered
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .system_properties(vec![("db.host", "10.0.0.1"), ("HOME", "/srv/app")])
+    ///     .load_str(r#"{ url: "postgres://"${db.host}, home: ${HOME} }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["url"].as_string(), Some(String::from("postgres://10.0.0.1")));
+    /// assert_eq!(doc["home"].as_string(), Some(String::from("/srv/app")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn system_properties<I, K, V>(&self, properties: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut system_properties = self.config.system_properties.as_ref().clone();
+        system_properties.extend(
+            properties
+                .into_iter()
+                .map(|(key, value)| (String::from(key.as_ref()), String::from(value.as_ref()))),
+        );
+        Self {
+            config: HoconLoaderConfig {
+                system_properties: Rc::new(system_properties),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Only allow System environment substitutions of the variables in `names`, so that
+    /// untrusted documents can't read other environment variables. This adds to the
+    /// variables already allowed
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("APP_PORT", "8080") };
+    /// let doc = HoconLoader::new()
+    ///     .allow_env(&["APP_PORT"])
+    ///     .load_str(r#"{ port: ${APP_PORT}, home: ${?HOME} }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["port"].as_i64(), Some(8080));
+    /// assert_eq!(doc["home"], Hocon::BadValue(Error::MissingKey));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn allow_env(&self, names: &[&str]) -> Self {
+        let mut env_allowlist = self.config.env_allowlist.clone().unwrap_or_default();
+        env_allowlist.extend(names.iter().map(|name| String::from(*name)));
+        Self {
+            config: HoconLoaderConfig {
+                env_allowlist: Some(env_allowlist),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Disable System environment substitutions of the variables in `names`. This adds to
+    /// the variables already denied
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("AWS_SECRET_ACCESS_KEY", "s3cr3t") };
+    /// let doc = HoconLoader::new()
+    ///     .deny_env(&["AWS_SECRET_ACCESS_KEY"])
+    ///     .load_str(r#"{ key: ${AWS_SECRET_ACCESS_KEY} }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(
+    ///     doc["key"],
+    ///     Hocon::BadValue(Error::KeyNotFound { key: String::from("AWS_SECRET_ACCESS_KEY") })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deny_env(&self, names: &[&str]) -> Self {
+        let mut env_denylist = self.config.env_denylist.clone();
+        env_denylist.extend(names.iter().map(|name| String::from(*name)));
+        Self {
+            config: HoconLoaderConfig {
+                env_denylist,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Match the names of environment variables in substitutions regardless of case, as on
+    /// Windows, so that `${Path}` reads `PATH` on every platform. A variable with the exact
+    /// name is used first. The variables of [`allow_env`](struct.HoconLoader.html#method.allow_env)
+    /// and [`deny_env`](struct.HoconLoader.html#method.deny_env) are also matched regardless
+    /// of case
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("HOCON_EXAMPLE_REGION", "eu-west-1") };
+    /// let doc = HoconLoader::new()
+    ///     .env_case_insensitive()
+    ///     .load_str(r#"{ region: ${hocon_example_region} }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["region"].as_string(), Some(String::from("eu-west-1")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn env_case_insensitive(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                env_case_insensitive: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Read environment variables from `vars` instead of the environment of the process, for
+    /// example to test substitutions of environment variables without changing the process
+    /// environment, which is not safe while other threads read it. The variables are used
+    /// for substitutions, [`env_overrides`](struct.HoconLoader.html#method.env_overrides),
+    /// [`profile_from_env`](struct.HoconLoader.html#method.profile_from_env) and
+    /// [`load_standard`](struct.HoconLoader.html#method.load_standard). This replaces the
+    /// variables of a previous call
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .with_env_source(vec![("PORT", "9090")])
+    ///     .load_str(r#"{ port: 8080, port: ${?PORT}, home: ${?HOME} }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["port"].as_string(), Some(String::from("9090")));
+    /// assert_eq!(doc["home"].as_string(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_env_source<I, K, V>(&self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self {
+            config: HoconLoaderConfig {
+                env_source: Some(Rc::new(
+                    vars.into_iter()
+                        .map(|(var, value)| {
+                            (String::from(var.as_ref()), String::from(value.as_ref()))
+                        })
+                        .collect(),
+                )),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Disable loading included files from external urls.
+    ///
+    /// # Example HOCON document
+    ///
+    /// ```no_test
+    /// include url("https://raw.githubusercontent.com/mockersf/hocon.rs/master/tests/data/basic.conf")
+    /// ```
+    ///
+    /// with url include:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// assert_eq!(
+    ///     HoconLoader::new().load_file("tests/data/include_url.conf")?.hocon()?["d"],
+    ///     Hocon::Boolean(true)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// without url include:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// assert_eq!(
+    ///     HoconLoader::new().no_url_include().load_file("tests/data/include_url.conf")?.hocon()?["d"],
+    ///     Hocon::BadValue(Error::MissingKey)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `url-support` or `url-support-ureq`
+    #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
+    pub fn no_url_include(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                external_url: false,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Sets the HOCON loader to return the first [`Error`](enum.Error.html) encoutered instead
+    /// of wrapping it in a [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) and
+    /// continuing parsing. This also keeps an include of a missing file an error, as with
+    /// `include required(...)`, where it is skipped otherwise
+    ///
+    /// # Example HOCON document
+    ///
+    /// ```no_test
+    /// {
+    ///     a = ${b}
+    /// }
+    /// ```
+    ///
+    /// in permissive mode:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ a = ${b} }"#;
+    /// assert_eq!(
+    ///     HoconLoader::new().load_str(example)?.hocon()?["a"],
+    ///     Hocon::BadValue(Error::KeyNotFound { key: String::from("b") })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// in strict mode:
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # use hocon::document::Source;
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ a = ${b} }"#;
+    /// assert_eq!(
+    ///     HoconLoader::new().strict().load_str(example)?.hocon(),
+    ///     Err(Error::UnresolvedSubstitution {
+    ///         substitution: String::from("b"),
+    ///         key: Some(String::from("a")),
+    ///         origin: Some(Source::Str),
+    ///         line: Some(1),
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn strict(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                strict: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Parse JSON files as pure JSON, rejecting the syntax HOCON adds to JSON like unquoted
+    /// keys, `=`, comments or substitutions, to check that a configuration can be read by
+    /// any JSON parser. This applies to `.json` files, loaded or included, and to documents
+    /// loaded as [`Format::Json`](enum.Format.html#variant.Json)
+    ///
+    /// ```rust
+    /// # use hocon::{Error, Format, HoconLoader};
+    /// # fn main() -> Result<(), Error> {
+    /// let loader = HoconLoader::new().strict_json();
+    /// assert!(loader.load_bytes_as(br#"{ "port": 8080 }"#, Format::Json).is_ok());
+    /// assert_eq!(
+    ///     loader.load_bytes_as(b"{ port: 8080 }", Format::Json).err(),
+    ///     Some(Error::InvalidJson {
+    ///         line: 1,
+    ///         column: 3,
+    ///         message: String::from("unquoted keys are not JSON"),
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn strict_json(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                strict_json: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Enable shell-style inline defaults in substitutions, an extension to HOCON: the
+    /// default is used when the substitution is not found in the document or the
+    /// environment. Defaults can be a quoted string, a number, a boolean or an unquoted
+    /// string, and an empty default, as in `${?NAME:-}`, is an empty string. Without this, a
+    /// document using it is invalid
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ port: ${?HOCON_EXAMPLE_PORT:-8080}, host: ${HOCON_EXAMPLE_HOST:-"0.0.0.0"} }"#;
+    /// let doc = HoconLoader::new().substitution_defaults().load_str(example)?.hocon()?;
+    ///
+    /// assert_eq!(doc["port"], Hocon::Integer(8080));
+    /// assert_eq!(doc["host"].as_string(), Some(String::from("0.0.0.0")));
+    ///
+    /// assert_eq!(HoconLoader::new().load_str(example).err(), Some(Error::Parse));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn substitution_defaults(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                substitution_defaults: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Enable substitutions with the content of a file, like `${file:/run/secrets/password}`,
+    /// an extension to HOCON. The file is read when the document is resolved, and trailing
+    /// line breaks are removed. This is useful to read secrets mounted as files by Docker or
+    /// Kubernetes. With an optional substitution, like `${?file:/run/secrets/password}`, a
+    /// file that can not be read leaves the value undefined, or to its previous value, as a
+    /// missing optional substitution. Without this, a document using it is invalid
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ db.password: ${file:tests/data/secrets/db_password} }"#;
+    /// let doc = HoconLoader::new().file_substitutions().load_str(example)?.hocon()?;
+    ///
+    /// assert_eq!(doc["db"]["password"].as_string(), Some(String::from("s3cr3t")));
+    ///
+    /// assert_eq!(HoconLoader::new().load_str(example).err(), Some(Error::Parse));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn file_substitutions(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                file_substitutions: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Interpolate substitutions written inside quoted strings, like
+    /// `"https://${HOST}:${PORT}/api"`, a lenient extension to HOCON where they are otherwise
+    /// kept as text. With this, `${` always starts a substitution when it is followed by a
+    /// valid one. The value is always a string: as for a substitution outside of a string, one
+    /// of a missing path that is not optional makes it a bad value, and so does one of an
+    /// object or an array
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ host: localhost, port: 8080, url: "https://${host}:${port}/api" }"#;
+    /// let doc = HoconLoader::new().string_interpolation().load_str(example)?.hocon()?;
+    /// assert_eq!(doc["url"].as_string(), Some(String::from("https://localhost:8080/api")));
+    ///
+    /// let doc = HoconLoader::new().load_str(example)?.hocon()?;
+    /// assert_eq!(doc["url"].as_string(), Some(String::from("https://${host}:${port}/api")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn string_interpolation(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                string_interpolation: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Enable substitutions in the targets of includes, an extension to HOCON, like
+    /// `include file(${CONF_DIR}"/extra.conf")` or `include ${extra_config}`. They are resolved
+    /// when the document is loaded, with the values of the documents loaded before it, then
+    /// with environment variables. Without this, a document using it is invalid
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_substitutions()
+    ///     .load_str(r#"{ variant: extra }"#)?
+    ///     .load_file("tests/data/include_substitution/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include file(${variant}".conf")`
+    /// assert_eq!(doc["extra"].as_bool(), Some(true));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_substitutions(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                include_substitutions: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Expand glob patterns in the paths of included files, an extension to HOCON, like
+    /// `include "conf.d/*.conf"`. `*` matches any characters and `?` one character, in any
+    /// component of the path. The matching files are included sorted by path, each winning
+    /// over the previous ones, and a pattern matching no files includes nothing. Without this,
+    /// a pattern is the name of a file
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_globs()
+    ///     .load_file("tests/data/include_glob/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include "conf.d/*.conf"`, with `database.port = 5432` in
+    /// // conf.d/10-database.conf and `database.port = 6432` in conf.d/20-override.conf
+    /// assert_eq!(doc["database"]["port"], Hocon::Integer(6432));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_globs(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                include_globs: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Enable includes of the content of environment variables, an extension to HOCON, like
+    /// `include env("APP_CONFIG")`. The content is parsed as HOCON, and a variable that is
+    /// not set is skipped as a missing file. Variables are read as for substitutions, with
+    /// the same [allowed](struct.HoconLoader.html#method.allow_env) variables. Without this,
+    /// a document using it is invalid
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .env_includes()
+    ///     .with_env_source(vec![("APP_CONFIG", "database { host = db.internal, port = 5432 }")])
+    ///     .load_file("tests/data/include_env/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include env("APP_CONFIG")`
+    /// assert_eq!(doc["database"]["port"], Hocon::Integer(5432));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn env_includes(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                env_includes: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Enable reading files from tar archives, an extension to HOCON, with paths like
+    /// `bundle.tar!defaults.conf` for the file `defaults.conf` in the archive `bundle.tar`,
+    /// both in includes and in [`load_file`](struct.HoconLoader.html#method.load_file). The
+    /// relative includes of a file of an archive are read from the same archive. Without
+    /// this, such a path is read as a file of that name
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .archive_includes()
+    ///     .load_file("tests/data/archive/bundle.tar!defaults.conf")?
+    ///     .hocon()?;
+    ///
+    /// // defaults.conf includes `logging.conf` from the archive
+    /// assert_eq!(doc["logging"]["level"].as_string(), Some(String::from("info")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn archive_includes(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                archive_includes: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Include every `.conf`, `.json`, `.json5` and `.properties` file of a directory, an
+    /// extension to HOCON, like `include "conf.d/"`. The files are included in lexical order
+    /// of their names, each winning over the previous ones. Without this, including a
+    /// directory includes nothing
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_directories()
+    ///     .load_file("tests/data/include_directory/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include "conf.d/"`, with `database.port = 5432` in
+    /// // conf.d/10-database.conf and `database.port = 6432` in conf.d/20-override.json
+    /// assert_eq!(doc["database"]["port"], Hocon::Integer(6432));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_directories(&self) -> Self {
+        self.include_directories_with(DirectoryIncludes::new())
+    }
+
+    /// Include the files of directories, with recursion into subdirectories or a custom order
+    ///
+    /// ```rust
+    /// # use hocon::{DirectoryIncludes, Hocon, HoconLoader, IncludeOrder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_directories_with(DirectoryIncludes::new().recursive().order(IncludeOrder::Natural))
+    ///     .load_file("tests/data/include_directory/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // conf.d/services/9-cache.conf comes before conf.d/services/10-cache.conf
+    /// assert_eq!(doc["cache"]["size"], Hocon::Integer(200));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_directories_with(&self, options: DirectoryIncludes) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                directory_includes: Some(options),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the directories where included files are looked for, in order, when they are not
+    /// found relative to the including file. Paths of includes are relative to the search
+    /// path where they are found, and an absolute path is never searched
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .with_search_paths(["tests/data/search_paths/etc", "tests/data/search_paths/defaults"])
+    ///     .load_file("tests/data/search_paths/app/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include "logging.conf"`, found in `etc`
+    /// assert_eq!(doc["logging"]["level"].as_string(), Some(String::from("warn")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_search_paths<I, P>(&self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        Self {
+            config: HoconLoaderConfig {
+                search_paths: Rc::new(
+                    paths
+                        .into_iter()
+                        .map(|path| path.as_ref().to_path_buf())
+                        .collect(),
+                ),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Add a directory where `include classpath("...")` looks for resources, after the
+    /// directories added before. A resource is found relative to the directory, and a
+    /// resource without extension is looked for with extension `.conf`, `.json` or
+    /// `.properties`, as for files
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .classpath_dir("tests/data/classpath/resources")
+    ///     .load_file("tests/data/classpath/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include classpath("application")`
+    /// assert_eq!(doc["app"]["port"], Hocon::Integer(8080));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn classpath_dir<P: AsRef<Path>>(&self, dir: P) -> Self {
+        let mut classpath = self.config.classpath.as_ref().clone();
+        classpath.dirs.push(dir.as_ref().to_path_buf());
+        Self {
+            config: HoconLoaderConfig {
+                classpath: Rc::new(classpath),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Register the content of a resource for `include classpath("...")`, for example a
+    /// document embedded in the binary with `include_str!`. Registered resources are looked
+    /// for before the [classpath directories](struct.HoconLoader.html#method.classpath_dir),
+    /// and parsed according to the extension of `name`, as HOCON if it has none
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .classpath_resource("embedded.conf", "embedded = true")
+    ///     .load_file("tests/data/classpath/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include classpath("embedded.conf")`
+    /// assert_eq!(doc["embedded"], Hocon::Boolean(true));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn classpath_resource(&self, name: &str, content: &str) -> Self {
+        let mut classpath = self.config.classpath.as_ref().clone();
+        classpath
+            .resources
+            .insert(String::from(name), String::from(content));
+        Self {
+            config: HoconLoaderConfig {
+                classpath: Rc::new(classpath),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Reject included files outside of directory `root`, once symbolic links and `..` are
+    /// resolved, for example to load configurations given by users. This also applies to
+    /// files included with `file://` URLs, from [directories](struct.HoconLoader.html#method.include_directories)
+    /// or the [classpath](struct.HoconLoader.html#method.classpath_dir), and to the files of
+    /// [file substitutions](struct.HoconLoader.html#method.file_substitutions). Other URLs
+    /// can be disabled with [`no_url_include`](struct.HoconLoader.html#method.no_url_include),
+    /// or restricted with [`url_filter`](struct.HoconLoader.html#method.url_filter)
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_root("tests/data/include_root/root")
+    ///     .load_file("tests/data/include_root/root/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include "../secret.conf"`
+    /// assert_eq!(
+    ///     doc["../secret.conf"],
+    ///     Hocon::BadValue(Error::IncludeOutsideRoot {
+    ///         path: String::from("../secret.conf")
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_root<P: AsRef<Path>>(&self, root: P) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                include_root: Some(root.as_ref().to_path_buf()),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Register a source for included documents, asked first for each file, URL or classpath
+    /// include. The loader still handles the includes it does not resolve, and parses and
+    /// merges the documents it returns, within the same limits
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, IncludeTarget, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .include_resolver(|target: IncludeTarget<'_>| match target {
+    ///         IncludeTarget::Url(url) if url.starts_with("vault://") => {
+    ///             Ok(Some(String::from("db.password = secret")))
+    ///         }
+    ///         _ => Ok(None),
+    ///     })
+    ///     .load_file("tests/data/include_resolver/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include url("vault://db")`
+    /// assert_eq!(doc["db"]["password"].as_string(), Some(String::from("secret")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn include_resolver(&self, resolver: impl IncludeResolver + 'static) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                include_resolver: Some(include::SharedResolver(Rc::new(resolver))),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Register a client to fetch the documents included with `include url("...")`, instead
+    /// of `reqwest`. It is also used if the crate was built without feature `url-support`.
+    /// The URLs included by a document are fetched concurrently, on several threads
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .http_fetcher(|url: &str| match url {
+    ///         "https://config.example.com/db.conf" => Ok(String::from("db.port = 5432")),
+    ///         _ => Err(Error::Include {
+    ///             path: String::from(url),
+    ///         }),
+    ///     })
+    ///     .load_file("tests/data/http_fetcher/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include url("https://config.example.com/db.conf")`
+    /// assert_eq!(doc["db"]["port"], Hocon::Integer(5432));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn http_fetcher(&self, fetcher: impl HttpFetcher + Send + Sync + 'static) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                http_fetcher: Some(fetch::SharedFetcher(Arc::new(fetcher))),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Only include the URLs allowed by `filter`, by their scheme and host. Other URLs are
+    /// not fetched, and are included as
+    /// [`Error::UrlNotAllowed`](enum.Error.html#variant.UrlNotAllowed)
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, UrlFilter, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .url_filter(UrlFilter::new().schemes(["https"]).allow_hosts(["*.example.com"]))
+    ///     .load_file("tests/data/url_filter/main.conf")?
+    ///     .hocon()?;
+    ///
+    /// // main.conf contains `include url("http://169.254.169.254/latest/user-data")`
+    /// assert_eq!(
+    ///     doc["http://169.254.169.254/latest/user-data"],
+    ///     Hocon::BadValue(Error::UrlNotAllowed {
+    ///         url: String::from("http://169.254.169.254/latest/user-data")
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn url_filter(&self, filter: UrlFilter) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                url_filter: filter,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set a new maximum include depth, by default 10
+    ///
+    /// A document included again while it is already being included is an
+    /// [`Error::IncludeCycle`](enum.Error.html#variant.IncludeCycle), whatever the depth
+    pub fn max_include_depth(&self, new_max_depth: u8) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                max_include_depth: new_max_depth,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum size in bytes of an included file or of a document fetched from an URL,
+    /// not limited by default. A larger document fails the load with
+    /// [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit) without being read
+    /// entirely
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// assert!(matches!(
+    ///     HoconLoader::new()
+    ///         .max_include_size(64)
+    ///         .load_file("tests/data/include_limits/main.conf"),
+    ///     Err(Error::ResourceLimit { .. })
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_include_size(&self, max_include_size: u64) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                max_include_size: Some(max_include_size),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the encoding of the documents in `format` read from files, bytes or readers. They
+    /// are UTF-8 by default, except Java properties files that are ISO-8859-1 as in their
+    /// specification. A UTF-8 or UTF-16 byte order mark wins over the encoding
+    ///
+    /// ```rust
+    /// # use hocon::{Encoding, Format, Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let latin1 = HoconLoader::new().load_bytes_as(b"city=Z\xfcrich", Format::Properties)?;
+    /// assert_eq!(latin1.hocon()?["city"], Hocon::String(String::from("Zürich")));
+    ///
+    /// let utf8 = HoconLoader::new()
+    ///     .encoding(Format::Properties, Encoding::Utf8)
+    ///     .load_bytes_as("city=Zürich".as_bytes(), Format::Properties)?;
+    /// assert_eq!(utf8.hocon()?["city"], Hocon::String(String::from("Zürich")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encoding(&self, format: Format, encoding: Encoding) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                encodings: self.config.encodings.with(format, encoding),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set how Java properties documents are parsed, from files, includes, strings or bytes.
+    /// They follow the rules of `java.util.Properties` by default
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error, PropertiesOptions};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .properties_options(PropertiesOptions::new().no_whitespace_separator())
+    ///     .load_properties_str("display name = My App")?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["display name"], Hocon::String(String::from("My App")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn properties_options(&self, options: PropertiesOptions) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                properties_options: options,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum number of values parsed from a document and all its includes, not
+    /// limited by default. A document with more values fails the load with
+    /// [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit), each included document
+    /// being counted as soon as it is parsed
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let loader = HoconLoader::new().max_parsed_values(3);
+    ///
+    /// assert!(loader.clone().load_str("a: 1, b: [2, 3]").is_ok());
+    /// assert!(matches!(
+    ///     loader.load_str("a: 1, b: [2, 3], c { d: 4 }"),
+    ///     Err(Error::ResourceLimit { .. })
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_parsed_values(&self, max_parsed_values: usize) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                max_parsed_values: Some(max_parsed_values),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum duration to fetch a document from an URL, not limited by default. A
+    /// slower fetch fails the load with
+    /// [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit)
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// let loader = HoconLoader::new().url_timeout(std::time::Duration::from_secs(5));
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `url-support` or `url-support-ureq`
+    #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
+    pub fn url_timeout(&self, url_timeout: std::time::Duration) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                url_timeout: Some(url_timeout),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Configure the HTTP requests made to fetch included URLs, to authenticate them, send
+    /// headers or retry them when the server is unavailable.
+    /// [`url_timeout`](struct.HoconLoader.html#method.url_timeout) applies to each attempt
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, UrlRequests};
+    /// # use std::time::Duration;
+    /// let loader = HoconLoader::new().url_requests_with(
+    ///     UrlRequests::new()
+    ///         .connect_timeout(Duration::from_secs(2))
+    ///         .retries(3, Duration::from_millis(200))
+    ///         .header("X-Environment", "staging")
+    ///         .bearer_auth("token"),
+    /// );
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `url-support`
+    #[cfg(feature = "url-support")]
+    pub fn url_requests_with(&self, requests: UrlRequests) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                url_requests: requests,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Fetch included URLs with `client`, for example to trust other root certificates,
+    /// authenticate with a client certificate or share its connection pool. The proxies,
+    /// redirect policy and connect timeout of the client are used instead of those of
+    /// [`url_requests_with`](struct.HoconLoader.html#method.url_requests_with)
+    ///
+    /// ```rust
+    /// # use hocon::HoconLoader;
+    /// # fn main() -> Result<(), reqwest::Error> {
+    /// let client = reqwest::blocking::Client::builder()
+    ///     .user_agent("my-service")
+    ///     .build()?;
+    /// let loader = HoconLoader::new().reqwest_client(client);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `url-support`
+    #[cfg(feature = "url-support")]
+    pub fn reqwest_client(&self, client: reqwest::blocking::Client) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                reqwest_client: Some(client),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum number of passes to resolve a substitution, by default 100. Each pass
+    /// follows a substitution to the value of another one that is not resolved yet, like in
+    /// `a: ${b}, b: ${c}, c: 1`. Substitutions of values set before them are resolved while
+    /// merging the documents, and do not need a pass. Going over the limit fails the
+    /// resolution with
+    /// [`Error::TooManyResolutionPasses`](enum.Error.html#variant.TooManyResolutionPasses),
+    /// with the substitutions that were still being resolved
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ a: ${b}, b: ${c}, c: ${d}, d: 1 }"#;
+    /// assert_eq!(
+    ///     HoconLoader::new().max_resolution_passes(2).load_str(example)?.hocon(),
+    ///     Err(Error::TooManyResolutionPasses {
+    ///         passes: 2,
+    ///         keys: vec![String::from("b"), String::from("c"), String::from("d")],
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_resolution_passes(&self, max_resolution_passes: usize) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                max_resolution_passes,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum number of values expanded by substitutions when resolving the
+    /// documents, by default 100 000. A substitution expands all the values it copies, so a
+    /// document that keeps concatenating a value to itself fails quickly with
+    /// [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit) instead of exhausting
+    /// memory
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = (0..64).fold(String::from("a: lol\n"), |doc, _| doc + "a: ${a}${a}\n");
+    ///
+    /// assert!(matches!(
+    ///     HoconLoader::new().max_substitutions(10_000).load_str(&doc)?.hocon(),
+    ///     Err(Error::ResourceLimit { .. })
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_substitutions(&self, max_substitutions: usize) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                limits: ResolutionLimits {
+                    max_substitutions,
+                    ..self.config.limits.clone()
+                },
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the maximum size in bytes of a string built by concatenating values and
+    /// substitutions, not limited by default. A longer string fails the resolution with
+    /// [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit)
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # let example = r#"{ a: "0123456789", b: ${a}${a}, c: ${b}${b} }"#;
+    /// let loader = HoconLoader::new().max_value_size(32).load_str(example)?;
+    ///
+    /// assert!(matches!(loader.hocon(), Err(Error::ResourceLimit { .. })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_value_size(&self, max_value_size: usize) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                limits: ResolutionLimits {
+                    max_value_size: Some(max_value_size),
+                    ..self.config.limits.clone()
+                },
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Make each loaded document a fallback for the documents loaded before it, like chaining
+    /// `withFallback` with Typesafe Config: the first document that sets a value wins. By
+    /// default, the last document wins. Defaults and overrides are still merged before and
+    /// after the other documents, in reverse order as well.
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .first_wins()
+    ///     .load_str(r#"{ a: 1 }"#)?
+    ///     .load_str(r#"{ a: 2, b: 3 }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["a"], Hocon::Integer(1));
+    /// assert_eq!(doc["b"], Hocon::Integer(3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn first_wins(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                first_wins: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the profile of the application, like `prod` or `dev`. When loading a file with
+    /// [`load_file`](struct.HoconLoader.html#method.load_file), the overlay of this profile
+    /// is then loaded on top of it if it exists: `application.prod.conf` for
+    /// `application.conf`, or `application.prod.{conf,json,properties}` for `application`
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// // application.prod.conf contains { http.port: 443 }
+    /// let doc = HoconLoader::new()
+    ///     .profile("prod")
+    ///     .load_file("tests/data/layers/application.conf")?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["http"]["port"], Hocon::Integer(443));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn profile(&self, profile: &str) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                profile: Some(String::from(profile)),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Set the profile of the application from environment variable `var`, as with
+    /// [`profile`](struct.HoconLoader.html#method.profile). Nothing changes if the variable
+    /// is not set or empty
+    pub fn profile_from_env(&self, var: &str) -> Self {
+        match self.config.read_env(var) {
+            Some(profile) if !profile.is_empty() => self.profile(&profile),
+            _ => self.clone(),
+        }
+    }
+
+    /// Keep the comments of the HOCON and JSON documents loaded, not those of the documents
+    /// they include, to write them back with
+    /// [`HoconDocument::to_hocon_string`](document/struct.HoconDocument.html#method.to_hocon_string).
+    /// The comments on the lines before a field, and after its value on the same line, are
+    /// kept with the field, available with
+    /// [`HoconDocument::comments`](document/struct.HoconDocument.html#method.comments)
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error, RenderOptions};
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .keep_comments()
+    ///     .load_str("# the port to listen on\nport = 8080 # or 80 in production\n")?
+    ///     .document()?;
+    ///
+    /// let comments = document.comments("port").unwrap();
+    /// assert_eq!(comments.leading, vec![String::from(" the port to listen on")]);
+    /// assert_eq!(comments.trailing, Some(String::from(" or 80 in production")));
+    /// assert_eq!(
+    ///     document.to_hocon_string(RenderOptions::default())?,
+    ///     "# the port to listen on\nport = 8080 # or 80 in production\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keep_comments(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                keep_comments: true,
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Record each step taken while merging the loaded documents and resolving substitutions.
+    /// The steps are available on the [`HoconDocument`](document/struct.HoconDocument.html)
+    /// returned by [`document`](struct.HoconLoader.html#method.document)
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # use hocon::document::MergeAction;
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .trace()
+    ///     .load_str(r#"{ a: 1 }"#)?
+    ///     .load_str(r#"{ a: 2 }"#)?
+    ///     .document()?;
+    ///
+    /// let actions = document.trace_for("a").into_iter().map(|step| step.action.clone());
+    /// assert_eq!(
+    ///     actions.collect::<Vec<_>>(),
+    ///     vec![MergeAction::Set, MergeAction::Override]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trace(&self) -> Self {
+        Self {
+            config: HoconLoaderConfig {
+                tracer: Some(Tracer::default()),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Configuration to parse a new document, with the documents already loaded when
+    /// includes can use their values
+    pub(crate) fn parsing_config(&self) -> HoconLoaderConfig {
+        // files included several times by a document are parsed once, and values and
+        // included sources are recorded, while parsing it
+        let config = HoconLoaderConfig {
+            include_cache: Rc::default(),
+            include_sources: Rc::default(),
+            prefetched: Rc::default(),
+            parsed_values: Rc::default(),
+            ..self.config.clone()
+        };
+        if self.config.include_substitutions {
+            HoconLoaderConfig {
+                loaded_layers: Rc::new(self.layers.clone()),
+                ..config
+            }
+        } else {
+            config
+        }
+    }
+
+    pub(crate) fn load_from_str_of_conf_file(self, s: FileRead) -> Result<Self> {
+        let config = self.parsing_config();
+        self.load_parsed_with(config, s)
+    }
+
+    /// Load a document parsed with `config`, configured by
+    /// [`parsing_config`](#method.parsing_config)
+    fn load_parsed_with(self, config: HoconLoaderConfig, s: FileRead) -> Result<Self> {
+        let comments = self.comments(&s);
+        let internal = config.parse_str_to_internal(s)?;
+        Ok(self.push_parsed(&config, internal, comments))
+    }
+
+    /// Comments of a document, if they are kept
+    fn comments(&self, s: &FileRead) -> Vec<(String, document::Comments)> {
+        if self.config.keep_comments {
+            s.json
+                .iter()
+                .chain(&s.hocon)
+                .flat_map(|text| syntax::comments(text))
+                .collect()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Push a document of the file of the loader parsed with `config`, with its `comments`
+    fn push_parsed(
+        self,
+        config: &HoconLoaderConfig,
+        internal: internals::HoconInternal,
+        comments: Vec<(String, document::Comments)>,
+    ) -> Self {
+        let source = self.config.source();
+        let mut loader = self.push_document(source, config, internal);
+        if let Some(layer) = loader.layers.last_mut() {
+            layer.comments = comments;
+        }
+        loader
+    }
+
+    fn push_layer(
+        mut self,
+        source: document::Source,
+        priority: Priority,
+        internal: internals::HoconInternal,
+    ) -> Self {
+        self.layers.push(Layer {
+            source,
+            priority,
+            internal,
+            includes: vec![],
+            comments: vec![],
+        });
+        self
+    }
+
+    /// Push a document parsed with `config`, keeping the files and URLs it included
+    fn push_document(
+        self,
+        source: document::Source,
+        config: &HoconLoaderConfig,
+        internal: internals::HoconInternal,
+    ) -> Self {
+        let mut loader = self.push_layer(source, Priority::Document, internal);
+        if let Some(layer) = loader.layers.last_mut() {
+            layer.includes = config.include_sources.take();
+        }
+        loader
+    }
+
+    /// Load a string containing an `Hocon` document. Includes are not supported when
+    /// loading from a string
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    ///
+    /// # Additional errors in strict mode
+    ///
+    /// * [`Error::IncludeNotAllowedFromStr`](enum.Error.html#variant.IncludeNotAllowedFromStr)
+    ///   if there is an include in the string
+    pub fn load_str(self, s: &str) -> Result<Self> {
+        self.load_from_str_of_conf_file(FileRead {
+            hocon: Some(String::from(s)),
+            ..Default::default()
+        })
+    }
+
+    /// Load bytes containing an `Hocon` document, like the content of a file exported by
+    /// Windows tools. A UTF-8 or UTF-16 byte order mark is skipped, and the document is
+    /// decoded as UTF-16 after a UTF-16 one, as UTF-8 otherwise. Includes are not supported,
+    /// as when loading from a string
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// // UTF-16, little endian, with its byte order mark
+    /// let bytes: Vec<u8> = "\u{feff}a = é".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    /// let doc = HoconLoader::new().load_bytes(&bytes)?.hocon()?;
+    ///
+    /// assert_eq!(doc["a"], Hocon::String(String::from("é")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Encoding`](enum.Error.html#variant.Encoding) if the document is not valid
+    ///   UTF-8 or UTF-16
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    pub fn load_bytes(&self, bytes: &[u8]) -> Result<Self> {
+        self.load_bytes_as(bytes, Format::Hocon)
+    }
+
+    /// Load bytes containing a document in `format`, decoded as with
+    /// [`load_bytes`](struct.HoconLoader.html#method.load_bytes)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_bytes`](struct.HoconLoader.html#method.load_bytes)
+    pub fn load_bytes_as(&self, bytes: &[u8], format: Format) -> Result<Self> {
+        self.clone()
+            .load_from_reader(bytes, format, document::Source::Str)
+    }
+
+    /// Load a string containing a Java properties document, like a payload sent by another
+    /// system. Keys are split on `.` into paths, and values are strings, converted when they
+    /// are read, as for a `.properties` file
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ db { host: localhost, port: 5432 } }"#)?
+    ///     .load_properties_str("db.host=10.0.0.1\ndb.pool.size=8")?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["db"]["host"], Hocon::String(String::from("10.0.0.1")));
+    /// assert_eq!(doc["db"]["port"], Hocon::Integer(5432));
+    /// assert_eq!(doc["db"]["pool"]["size"].as_i64(), Some(8));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document has an invalid
+    ///   unicode escape
+    pub fn load_properties_str(self, s: &str) -> Result<Self> {
+        self.load_from_str_of_conf_file(FileRead {
+            properties: Some(String::from(s)),
+            ..Default::default()
+        })
+    }
+
+    /// Load a string containing a pure JSON document, failing on the syntax HOCON adds to
+    /// JSON as with [`strict_json`](struct.HoconLoader.html#method.strict_json)
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_json_strict(r#"{ "db": { "host": "localhost" } }"#)?
+    ///     .hocon()?;
+    /// assert_eq!(doc["db"]["host"], Hocon::String(String::from("localhost")));
+    ///
+    /// let invalid = HoconLoader::new().load_json_strict(r#"{ "db": ${DB} }"#);
+    /// assert!(matches!(invalid, Err(Error::InvalidJson { .. })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidJson`](enum.Error.html#variant.InvalidJson) if the document is not
+    ///   pure JSON
+    pub fn load_json_strict(self, s: &str) -> Result<Self> {
+        strict_json::validate(s)?;
+        self.load_from_str_of_conf_file(FileRead {
+            json: Some(String::from(s)),
+            ..Default::default()
+        })
+    }
+
+    /// Load an [`Hocon`](enum.Hocon.html) document, for example one built with an
+    /// [`HoconBuilder`](struct.HoconBuilder.html). It is merged with the other loaded
+    /// documents as if it had been parsed at this point
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconBuilder, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ a: { b: 1, c: 2 } }"#)?
+    ///     .load_hocon(HoconBuilder::new().put("a.b", 3).build()?)
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["a"]["b"], Hocon::Integer(3));
+    /// assert_eq!(doc["a"]["c"], Hocon::Integer(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_hocon(self, hocon: Hocon) -> Self {
+        self.push_layer(
+            document::Source::Hocon,
+            Priority::Document,
+            internals::HoconInternal::from_hocon(hocon),
+        )
+    }
+
+    /// Load an [`Hocon`](enum.Hocon.html) document holding default values, for example
+    /// defaults compiled in a library. As with
+    /// [`with_reference`](struct.HoconLoader.html#method.with_reference), defaults are
+    /// merged before the other documents, whatever the order they were loaded in
+    ///
+    /// ```rust
+    /// # use hocon::{hocon, Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ pool.size: 16 }"#)?
+    ///     .with_defaults(hocon!({ pool { size = 4, timeout = 30 } }))
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["pool"]["size"], Hocon::Integer(16));
+    /// assert_eq!(doc["pool"]["timeout"], Hocon::Integer(30));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_defaults(self, defaults: Hocon) -> Self {
+        self.push_layer(
+            document::Source::Hocon,
+            Priority::Defaults,
+            internals::HoconInternal::from_hocon(defaults),
+        )
+    }
+
+    /// Load overrides given as `key.path=value` pairs, for example from command line
+    /// arguments. Values are read as HOCON scalars: `null`, booleans and numbers are read as
+    /// such, and anything else is a string, unless surrounded by double quotes.
+    ///
+    /// Overrides win over all the documents, whatever the order they were loaded in.
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_args(vec!["server.port=8081", "server.host=example.com"])?
+    ///     .load_str(r#"{ server { host: localhost, port: 80, tls: false } }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["server"]["port"], Hocon::Integer(8081));
+    /// assert_eq!(doc["server"]["host"].as_string(), Some(String::from("example.com")));
+    /// assert_eq!(doc["server"]["tls"], Hocon::Boolean(false));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if a pair has no `=`, or an empty key
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if a key indexes an array
+    ///   past its end
+    pub fn load_args<I, S>(self, args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut overrides = Hocon::Hash(Default::default());
+        for arg in args {
+            let (path, value) = arg
+                .as_ref()
+                .split_once('=')
+                .filter(|(path, _)| !path.trim().is_empty())
+                .ok_or(Error::Parse)?;
+            overrides.set(path, helper::parse_scalar(value))?;
+        }
+        Ok(self.load_overrides(document::Source::Args, overrides))
+    }
+
+    /// Load overrides from memory, for example values fetched from a secret store. Keys are
+    /// HOCON path expressions, and each value replaces the one at its path in the documents.
+    /// Substitutions see the overrides too, which makes them usable like environment variables
+    /// that are not in the process environment.
+    ///
+    /// Overrides win over all the documents, whatever the order they were loaded in.
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut secrets = HashMap::new();
+    /// secrets.insert("db.password", "s3cr3t");
+    /// secrets.insert("API_TOKEN", "abcd");
+    ///
+    /// let doc = HoconLoader::new()
+    ///     .no_system()
+    ///     .load_str(r#"{
+    ///         db { user: app, password: changeme, url: "postgres://"${db.user}":"${db.password}"@db" }
+    ///         api.token: ${API_TOKEN}
+    ///     }"#)?
+    ///     .with_overrides(secrets)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["db"]["url"].as_string(), Some(String::from("postgres://app:s3cr3t@db")));
+    /// assert_eq!(doc["api"]["token"].as_string(), Some(String::from("abcd")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if a key indexes an array
+    ///   past its end
+    pub fn with_overrides<I, K, V>(self, overrides: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<Hocon>,
+    {
+        let mut document = Hocon::Hash(Default::default());
+        for (path, value) in overrides {
+            document.set(path.as_ref(), value.into())?;
+        }
+        Ok(self.load_overrides(document::Source::Overrides, document))
+    }
+
+    fn load_overrides(self, source: document::Source, overrides: Hocon) -> Self {
+        self.push_layer(
+            source,
+            Priority::Overrides,
+            internals::HoconInternal::from_hocon(overrides),
+        )
+    }
+
+    /// Load overrides from the environment variables starting with `prefix` followed by `_`.
+    /// Keys of the path are separated by `__` and read in lower case, so with prefix `MYAPP`,
+    /// `MYAPP_SERVER__PORT=8081` overrides `server.port`. Values are read as with
+    /// [`load_args`](struct.HoconLoader.html#method.load_args), and win over all the documents.
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("MYAPP_SERVER__PORT", "8081") };
+    /// // with MYAPP_SERVER__PORT=8081
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ server { host: localhost, port: 80 } }"#)?
+    ///     .env_overrides("MYAPP")
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["server"]["port"], Hocon::Integer(8081));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn env_overrides(self, prefix: &str) -> Self {
+        self.env_overrides_with(EnvOverrides::new(prefix))
+    }
+
+    /// Load overrides from environment variables, with a custom separator and case mapping
+    ///
+    /// ```rust
+    /// # use hocon::{EnvOverrides, Hocon, HoconLoader, KeyCase, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("APP_HTTP_MAX_CONNECTIONS", "16") };
+    /// // with APP_HTTP_MAX_CONNECTIONS=16
+    /// let doc = HoconLoader::new()
+    ///     .env_overrides_with(EnvOverrides::new("APP").separator("_").key_case(KeyCase::Preserve))
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["HTTP"]["MAX"]["CONNECTIONS"], Hocon::Integer(16));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn env_overrides_with(self, overrides: EnvOverrides) -> Self {
+        let vars = self.config.read_env_vars();
+        self.load_overrides(document::Source::Env, overrides.to_hocon(vars.into_iter()))
+    }
+
+    /// Load the variables of a `.env` file, as used in local development to stand for the
+    /// environment of production. They can be read by substitutions like `${?DB_HOST}` or by
+    /// [`env_overrides`](struct.HoconLoader.html#method.env_overrides) as environment
+    /// variables, and are also loaded as a document with a key per variable. A variable set
+    /// in the environment wins over the `.env` file, in the document too
+    ///
+    /// A line of the file is `NAME=value`, optionally starting with `export`. Values can be
+    /// quoted, with escaped characters in double quotes, and variables in them are not
+    /// expanded
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// // .env contains DB_HOST=db.local
+    /// let doc = HoconLoader::new()
+    ///     .load_dotenv("tests/data/dotenv/.env")?
+    ///     .load_str(r#"{ db { host: localhost, host: ${?DB_HOST} } }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["db"]["host"].as_string(), Some(String::from("db.local")));
+    /// assert_eq!(doc["DB_HOST"].as_string(), Some(String::from("db.local")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) if the file can not be read
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if a line is not a variable
+    pub fn load_dotenv<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read(path)
+            .map_err(Error::from)
+            .and_then(|content| {
+                encoding::decode(content, &path.display().to_string(), Encoding::Utf8)
+            })
+            .map_err(file_error)?;
+        let vars = env::parse_dotenv(&content)?;
+
+        let mut dotenv = self.config.dotenv.as_ref().clone();
+        dotenv.extend(vars.iter().cloned());
+        // the environment also wins in the document, so substitutions get the same values
+        let internal = internals::HoconInternal::from_object(
+            vars.into_iter()
+                .map(|(var, value)| {
+                    let value = HoconLoaderConfig {
+                        dotenv: Rc::default(),
+                        ..self.config.clone()
+                    }
+                    .read_env(&var)
+                    .unwrap_or(value);
+                    (
+                        vec![internals::HoconValue::String(Rc::from(var))],
+                        internals::HoconValue::String(Rc::from(value)),
+                    )
+                })
+                .collect(),
+        );
+        let loader = Self {
+            config: HoconLoaderConfig {
+                dotenv: Rc::new(dotenv),
+                ..self.config.clone()
+            },
+            ..self.clone()
+        };
+        let source = self.file_config(path)?.source();
+        Ok(loader.push_layer(source, Priority::Document, internal))
+    }
+
+    /// Load the HOCON configuration file containing an `Hocon` document. If a
+    /// [`profile`](struct.HoconLoader.html#method.profile) is set, its overlay is loaded
+    /// after the file
+    ///
+    /// A directory is loaded as its `application` file, merging `application.conf`,
+    /// `application.json` and `application.properties` as for a file without extension
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new().load_file("tests/data/layers")?.hocon()?;
+    ///
+    /// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
+    /// assert_eq!(doc["http"]["host"].as_string(), Some(String::from("example.com")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) if there was an error reading the
+    ///   file content, or if a directory has no `application` file
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    /// * [`Error::Include`](enum.Error.html#variant.Include) if the target of an
+    ///   `include required(...)` is missing
+    ///
+    /// # Additional errors in strict mode
+    ///
+    /// * [`Error::TooManyIncludes`](enum.Error.html#variant.TooManyIncludes)
+    ///   if there are too many included files within included files. The limit can be
+    ///   changed with [`max_include_depth`](struct.HoconLoader.html#method.max_include_depth)
+    /// * [`Error::Include`](enum.Error.html#variant.Include) if an included file is missing
+    ///   or can not be read. Otherwise, a missing file is included as an empty object
+    pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        self.files_to_load(path.as_ref())?
+            .iter()
+            .try_fold(self.clone(), |loader, file| loader.load_single_file(file))
+    }
+
+    /// Files loaded for `path` by [`load_file`](struct.HoconLoader.html#method.load_file), in
+    /// the order they are merged
+    fn files_to_load(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let path = match path {
+            dir if dir.is_dir() => {
+                let application = dir.join("application");
+                if !["conf", "json", "properties"]
+                    .iter()
+                    .any(|ext| application.with_extension(ext).is_file())
+                {
+                    return Err(Error::File {
+                        path: dir.display().to_string(),
+                    });
+                }
+                application
+            }
+            path => path.to_path_buf(),
+        };
+        let mut files = vec![path.clone()];
+        if let Some(profile) = self.config.profile.as_ref() {
+            files.extend(
+                profile_overlays(&path, profile)
+                    .into_iter()
+                    .filter(|overlay| overlay.exists()),
+            );
+        }
+        // overlays still win over the file when documents are merged in reverse order
+        if self.config.first_wins {
+            files.reverse();
+        }
+        Ok(files)
+    }
+
+    /// Load several files in order, as with
+    /// [`load_file`](struct.HoconLoader.html#method.load_file) for each of them, so that a
+    /// file wins over the files before it
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_files([
+    ///         "tests/data/search_paths/defaults/logging.conf",
+    ///         "tests/data/search_paths/etc/logging.conf",
+    ///     ])?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["logging"]["level"].as_string(), Some(String::from("warn")));
+    /// assert_eq!(doc["logging"]["format"].as_string(), Some(String::from("json")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file), for the first file
+    /// that fails to load
+    pub fn load_files<I, P>(&self, paths: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        paths
+            .into_iter()
+            .try_fold(self.clone(), |loader, path| loader.load_file(path))
+    }
+
+    /// Load a directory with a file per key, as Kubernetes mounts the entries of a ConfigMap
+    /// or a Secret in a pod. The name of each file is a key, and its content the value, as a
+    /// string without its final line ending. A file with extension `.conf`, `.json`, `.json5`
+    /// or `.properties` is parsed, and its document is the value of its name without extension.
+    ///
+    /// The files are loaded in lexical order, each as a document. Subdirectories are skipped,
+    /// as are the entries starting with `..` that Kubernetes uses to update the files
+    /// atomically. Keys are not split on dots, so file `log.level` sets key `"log.level"`
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_key_per_file("tests/data/key_per_file")?
+    ///     .hocon()?;
+    ///
+    /// // the content of file `db_password`
+    /// assert_eq!(doc["db_password"].as_string(), Some(String::from("s3cr3t")));
+    /// // the document of file `http.conf`
+    /// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) if the directory can not be read
+    /// * and the errors of [`load_file`](struct.HoconLoader.html#method.load_file) for each
+    ///   file
+    pub fn load_key_per_file<P: AsRef<Path>>(&self, dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut files = std::fs::read_dir(dir)
+            .map_err(|_| Error::File {
+                path: dir.display().to_string(),
+            })?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| !name.starts_with(".."))
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+            .iter()
+            .try_fold(self.clone(), |loader, file| loader.load_key_file(file))
+    }
+
+    /// Load the file of a key of [`load_key_per_file`](#method.load_key_per_file)
+    fn load_key_file(self, path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let config = self.file_config(path)?;
+        let source = config.source();
+        let key = |key: &str| vec![internals::HoconValue::String(Rc::from(key))];
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext @ ("conf" | "json" | "json5" | "properties")) => {
+                let contents = config.read_file().map_err(file_error)?;
+                let config = Self {
+                    config,
+                    ..self.clone()
+                }
+                .parsing_config();
+                let internal = config
+                    .parse_str_to_internal(contents)?
+                    .add_to_path(key(&name[..name.len() - ext.len() - 1]));
+                Ok(self.push_document(source, &config, internal))
+            }
+            _ => {
+                let contents = std::fs::read(path).map_err(|_| Error::File {
+                    path: path.display().to_string(),
+                })?;
+                let value =
+                    encoding::decode(contents, &path.display().to_string(), Encoding::Utf8)?;
+                let value = value
+                    .strip_suffix('\n')
+                    .map(|value| value.strip_suffix('\r').unwrap_or(value))
+                    .unwrap_or(&value);
+                let internal = internals::HoconInternal::from_object(vec![(
+                    key(name),
+                    internals::HoconValue::String(Rc::from(value)),
+                )]);
+                Ok(self.push_layer(source, Priority::Document, internal))
+            }
+        }
+    }
+
+    /// Load the values of the keys under `prefix` in a key-value store, like Consul or etcd, as
+    /// a document merged with the others. Each key is a path in the document, and its value a
+    /// string, converted as in a properties file
+    ///
     /// ```rust
+    /// # use std::collections::HashMap;
     /// # use hocon::{Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// # // SAFETY: This is a single-threaded doctest
-    /// # unsafe { std::env::set_var("SHELL", "/bin/bash") };
-    /// # let example = r#"{system.shell: ${SHELL}}"#;
-    /// assert_eq!(
-    ///     HoconLoader::new().load_str(example)?.hocon()?["system"]["shell"],
-    ///     Hocon::String(String::from("/bin/bash"))
-    /// );
+    /// let store = |prefix: &str| {
+    ///     assert_eq!(prefix, "config/myapp");
+    ///     Ok(HashMap::from([(String::from("http.port"), String::from("9000"))]))
+    /// };
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ http { host: localhost, port: 8080 } }"#)?
+    ///     .load_remote(&store, "config/myapp")?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["http"]["port"].as_i64(), Some(9000));
+    /// assert_eq!(doc["http"]["host"].as_string(), Some(String::from("localhost")));
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// without system:
+    /// # Errors
+    ///
+    /// The error of the source if it could not be read
+    pub fn load_remote(&self, source: &impl RemoteSource, prefix: &str) -> Result<Self> {
+        let values = source.fetch(prefix)?;
+        Ok(self.clone().push_layer(
+            document::Source::Remote(String::from(prefix)),
+            Priority::Document,
+            internals::HoconInternal::from_properties(values),
+        ))
+    }
+
+    /// Load the HOCON configuration file containing an `Hocon` document as
+    /// [`load_file`](struct.HoconLoader.html#method.load_file), without blocking an
+    /// asynchronous runtime. The file is read with `runtime`, and the URLs it includes,
+    /// directly or through the documents it includes, are fetched concurrently with it once
+    /// it is parsed
+    ///
+    /// Included local files are still read synchronously, and as a loader is not `Send`,
+    /// neither is the future. With feature `tokio`,
+    /// [`load_file_async`](struct.HoconLoader.html#method.load_file_async) loads with the
+    /// tokio runtime
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file)
+    #[cfg(feature = "async")]
+    pub async fn load_file_async_with<P: AsRef<Path>>(
+        &self,
+        runtime: &impl AsyncRuntime,
+        path: P,
+    ) -> Result<Self> {
+        let mut loader = self.clone();
+        for file in self.files_to_load(path.as_ref())? {
+            loader = loader.load_single_file_async(runtime, &file).await?;
+        }
+        Ok(loader)
+    }
+
+    /// Load the `Hocon` document at `url`, fetching it with `runtime` so that it does not
+    /// block, as for [`load_file_async_with`](struct.HoconLoader.html#method.load_file_async_with).
+    /// Includes are not supported, as when loading from a string
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Include`](enum.Error.html#variant.Include) if the document could not be
+    ///   fetched, or if external URLs are disabled
+    /// * [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit) if it is larger than
+    ///   [`max_include_size`](struct.HoconLoader.html#method.max_include_size) or takes longer
+    ///   than [`url_timeout`](struct.HoconLoader.html#method.url_timeout) to fetch
+    /// * Otherwise, same as [`load_file`](struct.HoconLoader.html#method.load_file)
+    #[cfg(feature = "async")]
+    pub async fn load_url_async_with(
+        &self,
+        runtime: &impl AsyncRuntime,
+        url: &str,
+    ) -> Result<Self> {
+        let config = self.async_parsing_config();
+        config.fetch_async(runtime, [String::from(url)]).await;
+        self.clone().load_url_with(config, url)
+    }
+
+    /// Load the HOCON configuration file containing an `Hocon` document as
+    /// [`load_file_async_with`](struct.HoconLoader.html#method.load_file_async_with) with the
+    /// [tokio](https://tokio.rs) runtime, within which it must be run. The URLs are fetched
+    /// on its blocking thread pool
+    ///
     /// ```rust
     /// # use hocon::{Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// # let example = r#"{system.shell: ${SHELL}}"#;
-    /// assert_eq!(
-    ///     HoconLoader::new().no_system().load_str(example)?.hocon()?["system"]["shell"],
-    ///     Hocon::BadValue(Error::KeyNotFound { key: String::from("SHELL") })
-    /// );
+    /// # let runtime = tokio::runtime::Builder::new_current_thread()
+    /// #     .enable_all()
+    /// #     .build()
+    /// #     .unwrap();
+    /// # runtime.block_on(async {
+    /// let doc = HoconLoader::new()
+    ///     .load_file_async("tests/data/layers")
+    ///     .await?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
     /// # Ok(())
+    /// # })
     /// # }
     /// ```
-    pub fn no_system(&self) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_file_async_with`](struct.HoconLoader.html#method.load_file_async_with)
+    #[cfg(feature = "tokio")]
+    pub async fn load_file_async<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        self.load_file_async_with(&TokioRuntime, path).await
+    }
+
+    /// Load the `Hocon` document at `url` as
+    /// [`load_url_async_with`](struct.HoconLoader.html#method.load_url_async_with) with the
+    /// [tokio](https://tokio.rs) runtime, within which it must be run
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_url_async_with`](struct.HoconLoader.html#method.load_url_async_with)
+    #[cfg(feature = "tokio")]
+    pub async fn load_url_async(&self, url: &str) -> Result<Self> {
+        self.load_url_async_with(&TokioRuntime, url).await
+    }
+
+    #[cfg(feature = "async")]
+    async fn load_single_file_async(
+        self,
+        runtime: &impl AsyncRuntime,
+        path: &Path,
+    ) -> Result<Self> {
+        if path == Path::new("-") {
+            let contents = runtime
+                .spawn_blocking(|| {
+                    let mut contents = vec![];
+                    std::io::Read::read_to_end(&mut std::io::stdin(), &mut contents)
+                        .map(|_| contents)
+                })
+                .await
+                .map_err(|_| Error::File {
+                    path: String::from("-"),
+                })?;
+            return self.load_from_reader(
+                contents.as_slice(),
+                Format::Hocon,
+                document::Source::Stdin,
+            );
+        }
+        let conf = self.file_config(path)?;
+        let contents = conf.read_file_async(runtime).await.map_err(file_error)?;
+        let loader = Self {
+            config: conf,
+            ..self
+        };
+        let config = loader.async_parsing_config();
+        let comments = loader.comments(&contents);
+        let internal = config.parse_async(runtime, contents).await?;
+        Ok(loader.push_parsed(&config, internal, comments))
+    }
+
+    /// Configuration to parse a new document once the URLs it includes were fetched
+    #[cfg(feature = "async")]
+    fn async_parsing_config(&self) -> HoconLoaderConfig {
+        HoconLoaderConfig {
+            async_loading: true,
+            ..self.parsing_config()
+        }
+    }
+
+    fn load_single_file<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        if path.as_ref() == Path::new("-") {
+            return self.load_stdin();
+        }
+        let conf = self.file_config(path.as_ref())?;
+        let contents = conf.read_file().map_err(file_error)?;
         Self {
-            config: HoconLoaderConfig {
-                system: false,
-                ..self.config.clone()
-            },
+            config: conf,
             ..self.clone()
         }
+        .load_from_str_of_conf_file(contents)
     }
 
-    /// Disable loading included files from external urls.
-    ///
-    /// # Example HOCON document
+    /// Configuration to read the file at `path`, relative to the current directory
+    fn file_config(&self, path: &Path) -> Result<HoconLoaderConfig> {
+        let mut file_path = path.to_path_buf();
+        if !file_path.has_root() {
+            let mut current_path = std::env::current_dir().map_err(|_| Error::File {
+                path: String::from(path.to_str().unwrap_or("invalid path")),
+            })?;
+            current_path.push(path);
+            file_path = current_path;
+        }
+        Ok(self.config.with_file(file_path))
+    }
+
+    /// Load an `Hocon` document from the standard input, for example to read a configuration
+    /// piped to a command line tool. [`load_file`](struct.HoconLoader.html#method.load_file)
+    /// also reads the standard input with path `-`. Includes are not supported, as when
+    /// loading from a string
     ///
-    /// ```no_test
-    /// include url("https://raw.githubusercontent.com/mockersf/hocon.rs/master/tests/data/basic.conf")
+    /// ```rust,no_run
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// // cat merged.conf | mytool
+    /// let doc = HoconLoader::new().load_stdin()?.hocon()?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
-    /// with url include:
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) with path `-` if the standard input
+    ///   could not be read
+    /// * [`Error::Encoding`](enum.Error.html#variant.Encoding) if it is not valid UTF-8 or
+    ///   UTF-16
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    pub fn load_stdin(&self) -> Result<Self> {
+        self.load_stdin_as(Format::Hocon)
+    }
+
+    /// Load a document in `format` from the standard input, as with
+    /// [`load_stdin`](struct.HoconLoader.html#method.load_stdin)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_stdin`](struct.HoconLoader.html#method.load_stdin)
+    pub fn load_stdin_as(&self, format: Format) -> Result<Self> {
+        self.clone()
+            .load_from_reader(std::io::stdin().lock(), format, document::Source::Stdin)
+    }
+
+    /// Load a document in `format` from any reader, like a socket, a decompression stream or
+    /// an in-memory buffer, without going through a file or a string first. The reader is
+    /// consumed until its end. Includes are not supported, as when loading from a string
+    ///
     /// ```rust
-    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # use hocon::{Format, Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// assert_eq!(
-    ///     HoconLoader::new().load_file("tests/data/include_url.conf")?.hocon()?["d"],
-    ///     Hocon::Boolean(true)
-    /// );
+    /// let reader = std::io::Cursor::new(r#"{ "a": { "b": "c" } }"#);
+    /// let doc = HoconLoader::new().load_reader(reader, Format::Json)?.hocon()?;
+    ///
+    /// assert_eq!(doc["a"]["b"], Hocon::String(String::from("c")));
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// without url include:
+    /// # Errors
+    ///
+    /// * [`Error::Io`](enum.Error.html#variant.Io) if the reader failed
+    /// * [`Error::Encoding`](enum.Error.html#variant.Encoding) if the content is not valid
+    ///   UTF-8 or UTF-16
+    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    pub fn load_reader(&self, reader: impl std::io::Read, format: Format) -> Result<Self> {
+        self.clone()
+            .load_from_reader(reader, format, document::Source::Reader)
+    }
+
+    fn load_from_reader(
+        self,
+        mut reader: impl std::io::Read,
+        format: Format,
+        source: document::Source,
+    ) -> Result<Self> {
+        let mut contents = vec![];
+        reader
+            .read_to_end(&mut contents)
+            .map_err(|error| match source {
+                document::Source::Stdin => Error::File {
+                    path: String::from("-"),
+                },
+                _ => Error::from(error),
+            })?;
+        let contents = encoding::decode(
+            contents,
+            &source.to_string(),
+            self.config.encodings.of(format),
+        )?;
+        let config = self.parsing_config();
+        let internal = config.parse_str_to_internal(FileRead::from_format(format, contents))?;
+        Ok(self.push_document(source, &config, internal))
+    }
+
+    /// Load an HOCON file holding default values, like the `reference.conf` of a library.
+    ///
+    /// Defaults are always merged before the other documents, whatever the order they were
+    /// loaded in, so any document can override them. Their substitutions are resolved against
+    /// the whole merged document, so a default can refer to a value set by the application.
+    ///
     /// ```rust
     /// # use hocon::{Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// assert_eq!(
-    ///     HoconLoader::new().no_url_include().load_file("tests/data/include_url.conf")?.hocon()?["d"],
-    ///     Hocon::BadValue(Error::MissingKey)
-    /// );
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ http.port: 8080 }"#)?
+    ///     .with_reference("tests/data/layers/reference.conf")?
+    ///     .hocon()?;
+    ///
+    /// // reference.conf contains { http { host: localhost, port: 80, url: "http://"${http.host}":"${http.port} } }
+    /// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
+    /// assert_eq!(doc["http"]["url"].as_string(), Some(String::from("http://localhost:8080")));
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// # Feature
+    /// # Errors
     ///
-    /// This method depends on feature `url-support`
-    #[cfg(feature = "url-support")]
-    pub fn no_url_include(&self) -> Self {
-        Self {
-            config: HoconLoaderConfig {
-                external_url: false,
-                ..self.config.clone()
-            },
-            ..self.clone()
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file)
+    pub fn with_reference<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        let mut loader = self.load_file(path)?;
+        for layer in loader.layers.iter_mut().skip(self.layers.len()) {
+            layer.priority = Priority::Defaults;
         }
+        Ok(loader)
     }
 
-    /// Sets the HOCON loader to return the first [`Error`](enum.Error.html) encoutered instead
-    /// of wrapping it in a [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) and
-    /// continuing parsing
+    /// Load the configuration of an application from the current directory, following the
+    /// Typesafe Config convention: `reference.conf` holds the defaults, loaded with
+    /// [`with_reference`](struct.HoconLoader.html#method.with_reference), then
+    /// `application.conf`, `application.json` and `application.properties` are loaded on top
+    /// of it. Missing files are skipped.
     ///
-    /// # Example HOCON document
+    /// # Errors
     ///
-    /// ```no_test
-    /// {
-    ///     a = ${b}
-    /// }
-    /// ```
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file), except for missing files
+    pub fn load_defaults(&self) -> Result<Self> {
+        self.load_defaults_from(".")
+    }
+
+    /// Same as [`load_defaults`](struct.HoconLoader.html#method.load_defaults), looking for
+    /// the files in directory `dir`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file), except for missing files
+    pub fn load_defaults_from<P: AsRef<Path>>(&self, dir: P) -> Result<Self> {
+        self.with_reference_if_exists(dir.as_ref().join("reference.conf"))?
+            .load_file(dir.as_ref().join("application"))
+    }
+
+    /// Load the configuration of application `name` from the current directory: defaults
+    /// from `reference.conf` as with [`load_defaults`](struct.HoconLoader.html#method.load_defaults),
+    /// then `name.conf`, `name.json` and `name.properties`. Missing files are skipped.
+    ///
+    /// The application configuration can be swapped at deployment time with environment
+    /// variables, taking precedence over the files of the application:
+    /// * `HOCON_CONFIG_FILE` with the path to a file to load,
+    /// * `HOCON_CONFIG_URL` with an URL to load, if crate was built with feature `url-support`.
     ///
-    /// in permissive mode:
     /// ```rust
     /// # use hocon::{Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// # let example = r#"{ a = ${b} }"#;
-    /// assert_eq!(
-    ///     HoconLoader::new().load_str(example)?.hocon()?["a"],
-    ///     Hocon::BadValue(Error::KeyNotFound { key: String::from("b") })
-    /// );
+    /// # // SAFETY: This is a single-threaded doctest
+    /// # unsafe { std::env::set_var("HOCON_CONFIG_FILE", "tests/data/basic.conf") };
+    /// // with HOCON_CONFIG_FILE=tests/data/basic.conf
+    /// let doc = HoconLoader::new().load_standard("application")?.hocon()?;
+    ///
+    /// assert_eq!(doc["a"], Hocon::Integer(5));
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// in strict mode:
+    /// # Errors
+    ///
+    /// * [`Error::File`](enum.Error.html#variant.File) if there was an error reading the
+    ///   file from `HOCON_CONFIG_FILE`
+    /// * [`Error::Include`](enum.Error.html#variant.Include) if there was an error loading the
+    ///   URL from `HOCON_CONFIG_URL`
+    /// * [`Error::DisabledExternalUrl`](enum.Error.html#variant.DisabledExternalUrl) if crate
+    ///   was built without feature `url-support` and `HOCON_CONFIG_URL` is set
+    /// * and the errors of [`load_file`](struct.HoconLoader.html#method.load_file)
+    pub fn load_standard(&self, name: &str) -> Result<Self> {
+        let loader = self.with_reference_if_exists("reference.conf")?;
+        if let Some(path) = self.config.read_env("HOCON_CONFIG_FILE") {
+            loader.load_file(path)
+        } else if let Some(url) = self.config.read_env("HOCON_CONFIG_URL") {
+            loader.load_url(&url)
+        } else {
+            loader.load_file(name)
+        }
+    }
+
+    /// Load the configuration of command line tool `name` from the directories where the
+    /// platform keeps configurations, the system-wide ones first so that the configuration of
+    /// the user wins over them. In each of them, the file `name/config` is loaded as a file
+    /// without extension, merging `config.conf`, `config.json` and `config.properties`.
+    /// Missing files are skipped.
+    ///
+    /// The directories are, from the system-wide ones to the one of the user:
+    /// * on Linux and other Unix systems, `/etc`, each directory of `$XDG_CONFIG_DIRS`
+    ///   (`/etc/xdg` if not set), then `$XDG_CONFIG_HOME` (`~/.config` if not set),
+    /// * on macOS, `/Library/Application Support`, then `~/Library/Application Support`, or
+    ///   `$XDG_CONFIG_HOME` if it is set,
+    /// * on Windows, `%PROGRAMDATA%`, then `%APPDATA%`.
+    ///
     /// ```rust
     /// # use hocon::{Hocon, HoconLoader, Error};
     /// # fn main() -> Result<(), Error> {
-    /// # let example = r#"{ a = ${b} }"#;
-    /// assert_eq!(
-    ///     HoconLoader::new().strict().load_str(example)?.hocon(),
-    ///     Err(Error::KeyNotFound { key: String::from("b") })
-    /// );
+    /// # #[cfg(all(unix, not(target_os = "macos")))] {
+    /// let doc = HoconLoader::new()
+    ///     .with_env_source([
+    ///         ("XDG_CONFIG_DIRS", "tests/data/user_config/system"),
+    ///         ("XDG_CONFIG_HOME", "tests/data/user_config/home"),
+    ///     ])
+    ///     .load_user_config("mytool")?
+    ///     .hocon()?;
+    ///
+    /// // the configuration of the user wins over the system-wide one
+    /// assert_eq!(doc["color"], Hocon::Boolean(false));
+    /// assert_eq!(doc["editor"].as_string(), Some(String::from("vi")));
+    /// # }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn strict(&self) -> Self {
-        Self {
-            config: HoconLoaderConfig {
-                strict: true,
-                ..self.config.clone()
-            },
-            ..self.clone()
+    ///
+    /// # Errors
+    ///
+    /// Same as [`load_file`](struct.HoconLoader.html#method.load_file), except for missing files
+    pub fn load_user_config(&self, name: &str) -> Result<Self> {
+        self.user_config_dirs()
+            .into_iter()
+            .map(|dir| dir.join(name).join("config"))
+            .filter(|file| {
+                ["conf", "json", "properties"]
+                    .iter()
+                    .any(|ext| file.with_extension(ext).is_file())
+            })
+            .try_fold(self.clone(), |loader, file| loader.load_file(file))
+    }
+
+    /// Directories of the configurations of the platform, in the order they are merged
+    fn user_config_dirs(&self) -> Vec<PathBuf> {
+        let env_dir = |var: &str| {
+            self.config
+                .read_env(var)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        };
+        let home = || env_dir("HOME");
+        let mut dirs = vec![];
+        if cfg!(windows) {
+            dirs.extend(env_dir("PROGRAMDATA"));
+            dirs.extend(env_dir("APPDATA"));
+        } else if cfg!(target_os = "macos") {
+            dirs.push(PathBuf::from("/Library/Application Support"));
+            dirs.extend(
+                env_dir("XDG_CONFIG_HOME")
+                    .or_else(|| home().map(|home| home.join("Library/Application Support"))),
+            );
+        } else {
+            dirs.push(PathBuf::from("/etc"));
+            // the first directory of XDG_CONFIG_DIRS is the most important one
+            let system_dirs = self
+                .config
+                .read_env("XDG_CONFIG_DIRS")
+                .filter(|dirs| !dirs.is_empty())
+                .unwrap_or_else(|| String::from("/etc/xdg"));
+            dirs.extend(
+                system_dirs
+                    .split(':')
+                    .filter(|dir| !dir.is_empty())
+                    .rev()
+                    .map(PathBuf::from),
+            );
+            dirs.extend(
+                env_dir("XDG_CONFIG_HOME").or_else(|| home().map(|home| home.join(".config"))),
+            );
         }
+        dirs
     }
 
-    /// Set a new maximum include depth, by default 10
-    pub fn max_include_depth(&self, new_max_depth: u8) -> Self {
-        Self {
-            config: HoconLoaderConfig {
-                max_include_depth: new_max_depth,
-                ..self.config.clone()
-            },
-            ..self.clone()
+    fn with_reference_if_exists<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
+        if path.as_ref().exists() {
+            self.with_reference(path)
+        } else {
+            Ok(self.clone())
         }
     }
 
-    pub(crate) fn load_from_str_of_conf_file(self, s: FileRead) -> Result<Self> {
-        Ok(Self {
-            internal: self.internal.add(self.config.parse_str_to_internal(s)?),
-            config: self.config,
-        })
+    fn load_url(self, url: &str) -> Result<Self> {
+        let config = self.parsing_config();
+        self.load_url_with(config, url)
     }
 
-    /// Load a string containing an `Hocon` document. Includes are not supported when
-    /// loading from a string
+    fn load_url_with(self, config: HoconLoaderConfig, url: &str) -> Result<Self> {
+        let internal = config.load_url(url)?;
+        // the URL itself was recorded as read by the document
+        let includes = config
+            .include_sources
+            .take()
+            .into_iter()
+            .flat_map(|source| source.includes)
+            .collect();
+        let mut loader = self.push_layer(
+            document::Source::Url(String::from(url)),
+            Priority::Document,
+            internal,
+        );
+        if let Some(layer) = loader.layers.last_mut() {
+            layer.includes = includes;
+        }
+        Ok(loader)
+    }
+
+    /// Merge the loaded documents as a single one, resolving the substitutions that can
+    /// already be resolved. More documents can then be loaded, and the remaining
+    /// substitutions are resolved with them when getting the final document. Overrides like
+    /// [`load_args`](struct.HoconLoader.html#method.load_args) still win over the documents
+    /// loaded afterwards
     ///
-    /// # Errors
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, ResolveOptions, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let loader = HoconLoader::new()
+    ///     .load_str(r#"{ name: app, greeting: "hello "${name}, url: ${base}"/api" }"#)?
+    ///     .resolve_partially(ResolveOptions {
+    ///         allow_unresolved: true,
+    ///         use_env: false,
+    ///     })?;
     ///
-    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
+    /// let doc = loader
+    ///     .load_str(r#"{ name: other, base: "http://localhost" }"#)?
+    ///     .hocon()?;
     ///
-    /// # Additional errors in strict mode
+    /// assert_eq!(doc["greeting"].as_string(), Some(String::from("hello app")));
+    /// assert_eq!(doc["url"].as_string(), Some(String::from("http://localhost/api")));
+    /// # Ok(())
+    /// # }
+    /// ```
     ///
-    /// * [`Error::IncludeNotAllowedFromStr`](enum.Error.html#variant.IncludeNotAllowedFromStr)
-    ///   if there is an include in the string
-    pub fn load_str(self, s: &str) -> Result<Self> {
-        self.load_from_str_of_conf_file(FileRead {
-            hocon: Some(String::from(s)),
-            ..Default::default()
+    /// # Errors in strict mode
+    ///
+    /// Without `allow_unresolved`, same as [`hocon`](struct.HoconLoader.html#method.hocon)
+    pub fn resolve_partially(self, options: ResolveOptions) -> Result<Self> {
+        let config = HoconLoaderConfig {
+            env: options.use_env && self.config.env,
+            tracer: None,
+            limits: self.config.limits.restarted(),
+            ..self.config.clone()
+        };
+        let layers = merge_order(self.layers, config.first_wins);
+        // overrides are kept apart so they still win over the next documents
+        let overrides = layers
+            .iter()
+            .filter(|layer| layer.priority == Priority::Overrides)
+            .cloned()
+            .collect::<Vec<_>>();
+        let includes = layers.iter().flat_map(Layer::sources).collect();
+        let layers_comments = document::merge_comments(&layers).into_iter().collect();
+        let merged = internals::HoconInternal::merge(layers, &config)?;
+        let internal = if options.allow_unresolved {
+            merged.resolve_partially(&config)
+        } else {
+            internals::HoconInternal::from_hocon(merged.finalize(&config)?)
+        };
+        Ok(Self {
+            layers: std::iter::once(Layer {
+                source: document::Source::Resolved,
+                priority: Priority::Document,
+                internal,
+                includes,
+                comments: layers_comments,
+            })
+            .chain(overrides)
+            .collect(),
+            config: self.config,
         })
     }
 
-    /// Load the HOCON configuration file containing an `Hocon` document
+    /// Load the documents as HOCON
     ///
     /// # Errors
     ///
-    /// * [`Error::File`](enum.Error.html#variant.File) if there was an error reading the
-    ///   file content
-    /// * [`Error::Parse`](enum.Error.html#variant.Parse) if the document is invalid
-    ///
-    /// # Additional errors in strict mode
-    ///
-    /// * [`Error::TooManyIncludes`](enum.Error.html#variant.TooManyIncludes)
-    ///   if there are too many included files within included files. The limit can be
-    ///   changed with [`max_include_depth`](struct.HoconLoader.html#method.max_include_depth)
-    pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<Self> {
-        let mut file_path = path.as_ref().to_path_buf();
-        // pub fn load_file(&self, path: &str) -> Result<Self, Error> {
-        // let mut file_path = Path::new(path).to_path_buf();
-        if !file_path.has_root() {
-            let mut current_path = std::env::current_dir().map_err(|_| Error::File {
-                path: String::from(path.as_ref().to_str().unwrap_or("invalid path")),
-            })?;
-            current_path.push(path.as_ref());
-            file_path = current_path;
-        }
-        let conf = self.config.with_file(file_path);
-        let contents = conf.read_file().map_err(|err| {
-            let path = match err {
-                Error::File { path } => path,
-                Error::Include { path } => path,
-                Error::Io { message } => message,
-                _ => "unmatched error".to_string(),
-            };
-            Error::File { path }
-        })?;
-        Self {
-            config: conf,
-            ..self.clone()
-        }
-        .load_from_str_of_conf_file(contents)
-    }
-
-    /// Load the documents as HOCON
+    /// * [`Error::ResourceLimit`](enum.Error.html#variant.ResourceLimit) if resolving the
+    ///   substitutions goes over the limits set with
+    ///   [`max_substitutions`](struct.HoconLoader.html#method.max_substitutions) or
+    ///   [`max_value_size`](struct.HoconLoader.html#method.max_value_size)
+    /// * [`Error::TooManyResolutionPasses`](enum.Error.html#variant.TooManyResolutionPasses)
+    ///   if a substitution needs more passes than set with
+    ///   [`max_resolution_passes`](struct.HoconLoader.html#method.max_resolution_passes)
     ///
     /// # Errors in strict mode
     ///
     /// * [`Error::Include`](enum.Error.html#variant.Include) if there was an issue with an
     ///   included file
+    /// * [`Error::UnresolvedSubstitution`](enum.Error.html#variant.UnresolvedSubstitution) if
+    ///   a substitution that is not optional can not be resolved, with the key it defines and
+    ///   where it is written
     /// * [`Error::KeyNotFound`](enum.Error.html#variant.KeyNotFound) if there is a substitution
     ///   with a key that is not present in the document
+    /// * [`Error::SubstitutionCycle`](enum.Error.html#variant.SubstitutionCycle) if
+    ///   substitutions reference each other
     /// * [`Error::DisabledExternalUrl`](enum.Error.html#variant.DisabledExternalUrl) if crate
     ///   was built without feature `url-support` and an `include url("...")` was found
     pub fn hocon(self) -> Result<Hocon> {
-        let config = &self.config;
-        self.internal.merge(config)?.finalize(config)
+        self.document().map(HoconDocument::into_hocon)
+    }
+
+    /// Load the documents as HOCON, keeping the steps recorded if tracing was enabled with
+    /// [`trace`](struct.HoconLoader.html#method.trace)
+    ///
+    /// # Errors in strict mode
+    ///
+    /// Same as [`hocon`](struct.HoconLoader.html#method.hocon)
+    pub fn document(self) -> Result<HoconDocument> {
+        let config = HoconLoaderConfig {
+            // each document gets its own trace, even when built from clones of a loader
+            tracer: self.config.tracer.as_ref().map(|_| Tracer::default()),
+            unresolved_optionals: Rc::default(),
+            limits: self.config.limits.restarted(),
+            ..self.config
+        };
+        let sources = self.layers.iter().flat_map(Layer::sources).collect();
+        let layers = merge_order(self.layers, config.first_wins);
+        let comments = document::merge_comments(&layers);
+        let hocon = internals::HoconInternal::merge(layers.clone(), &config)
+            .and_then(|merged| merged.finalize(&config))?;
+        Ok(HoconDocument {
+            hocon,
+            trace: config
+                .tracer
+                .map(|tracer| tracer.steps())
+                .unwrap_or_default(),
+            unresolved_optionals: config.unresolved_optionals.take(),
+            sources,
+            comments,
+        })
     }
 
     /// Deserialize the loaded documents to the target type
@@ -497,6 +2909,9 @@ impl HoconLoader {
     ///
     /// * [`Error::Include`](enum.Error.html#variant.Include) if there was an issue with an
     ///   included file
+    /// * [`Error::UnresolvedSubstitution`](enum.Error.html#variant.UnresolvedSubstitution) if
+    ///   a substitution that is not optional can not be resolved, with the key it defines and
+    ///   where it is written
     /// * [`Error::KeyNotFound`](enum.Error.html#variant.KeyNotFound) if there is a substitution
     ///   with a key that is not present in the document
     /// * [`Error::DisabledExternalUrl`](enum.Error.html#variant.DisabledExternalUrl) if crate
@@ -510,6 +2925,44 @@ impl HoconLoader {
     }
 }
 
+/// Layers in the order they are merged, by priority then in the order they were loaded,
+/// or in reverse with [`first_wins`](struct.HoconLoader.html#method.first_wins)
+fn merge_order(mut layers: Vec<Layer>, first_wins: bool) -> Vec<Layer> {
+    if first_wins {
+        layers.reverse();
+    }
+    layers.sort_by_key(|layer| layer.priority);
+    layers
+}
+
+/// Error of a file loaded by the user that could not be read
+fn file_error(err: Error) -> Error {
+    let path = match err {
+        Error::Encoding { .. } => return err,
+        Error::File { path } => path,
+        Error::Include { path } => path,
+        Error::Io { message } => message,
+        _ => "unmatched error".to_string(),
+    };
+    Error::File { path }
+}
+
+/// Files of the overlay of `profile` for the file at `path`, in the order they are merged
+fn profile_overlays(path: &Path, profile: &str) -> Vec<PathBuf> {
+    let (Some(stem), Some(name)) = (path.file_stem(), path.file_name()) else {
+        return vec![];
+    };
+    match path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some(ext @ ("conf" | "json" | "json5" | "properties")) => {
+            vec![path.with_file_name(format!("{}.{profile}.{ext}", stem.to_string_lossy()))]
+        }
+        _ => ["properties", "json", "conf"]
+            .iter()
+            .map(|ext| path.with_file_name(format!("{}.{profile}.{ext}", name.to_string_lossy())))
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ConfFileMeta, Hocon, HoconLoader, HoconLoaderConfig};
@@ -664,6 +3117,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_from_reader() {
+        for (content, format) in [
+            ("a.b: c", super::Format::Hocon),
+            (r#"{ "a": { "b": "c" } }"#, super::Format::Json),
+            ("a.b=c", super::Format::Properties),
+        ] {
+            let loader = HoconLoader::new()
+                .load_from_reader(content.as_bytes(), format, super::document::Source::Stdin)
+                .expect("during test");
+            assert_eq!(loader.layers[0].source, super::document::Source::Stdin);
+
+            let doc = loader.hocon().expect("during test");
+            assert_eq!(doc["a"]["b"].as_string(), Some(String::from("c")));
+        }
+    }
+
     #[cfg(feature = "url-support")]
     #[test]
     fn can_disable_url_include() {
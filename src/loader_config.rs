@@ -1,29 +1,221 @@
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
 
+#[cfg(feature = "async")]
+use crate::AsyncRuntime;
+use crate::DirectoryIncludes;
 use crate::Error;
+use crate::PropertiesOptions;
 use crate::Result;
-use crate::internals::HoconInternal;
+use crate::document::{Layer, LoadedSource, Resolution, Source, Tracer, UnresolvedOptional};
+#[cfg(all(feature = "url-support-ureq", not(feature = "url-support")))]
+use crate::fetch::UreqFetcher;
+use crate::fetch::{HttpFetcher, SharedFetcher, UrlFilter};
+#[cfg(feature = "url-support")]
+use crate::fetch::{ReqwestFetcher, UrlRequests};
+use crate::include::{IncludeTarget, SharedResolver};
+use crate::internals::{HoconInternal, HoconValue, Include};
+use crate::json5;
 use crate::parser;
+use crate::strict_json;
+#[cfg(feature = "yaml-support")]
+use crate::yaml;
 
 #[derive(Debug, Clone)]
 pub(crate) enum FileType {
     Properties,
     Hocon,
     Json,
+    Json5,
+    #[cfg(feature = "yaml-support")]
+    Yaml,
+    #[cfg(feature = "toml-support")]
+    Toml,
     All,
 }
 
+/// Format of a document read without a file name, as from
+/// [`HoconLoader::load_stdin_as`](struct.HoconLoader.html#method.load_stdin_as)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// An HOCON document, that can also be JSON
+    Hocon,
+    /// A JSON document
+    Json,
+    /// A [JSON5](https://json5.org) document, JSON with comments, unquoted keys, single
+    /// quoted strings, trailing commas and hexadecimal numbers
+    Json5,
+    /// A YAML document, that can be a stream of documents merged in order
+    ///
+    /// # Feature
+    ///
+    /// This variant depends on feature `yaml-support`
+    #[cfg(feature = "yaml-support")]
+    Yaml,
+    /// A TOML document, with its datetimes read as strings
+    ///
+    /// # Feature
+    ///
+    /// This variant depends on feature `toml-support`
+    #[cfg(feature = "toml-support")]
+    Toml,
+    /// A Java properties document
+    Properties,
+}
+
+impl FileType {
+    /// Format of a document of this type, HOCON if it is not known
+    fn format(&self) -> Format {
+        match self {
+            FileType::Properties => Format::Properties,
+            FileType::Json => Format::Json,
+            FileType::Json5 => Format::Json5,
+            #[cfg(feature = "yaml-support")]
+            FileType::Yaml => Format::Yaml,
+            #[cfg(feature = "toml-support")]
+            FileType::Toml => Format::Toml,
+            FileType::Hocon | FileType::All => Format::Hocon,
+        }
+    }
+}
+
+/// Character encoding of the documents read from files or bytes, set for each format with
+/// [`HoconLoader::encoding`](struct.HoconLoader.html#method.encoding). A byte order mark at
+/// the start of a document wins over it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// UTF-8, the encoding of HOCON and JSON documents
+    Utf8,
+    /// ISO-8859-1, also known as Latin-1, the encoding of Java properties files
+    Latin1,
+}
+
+/// Encoding of the documents of each format
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Encodings {
+    hocon: Encoding,
+    json: Encoding,
+    json5: Encoding,
+    #[cfg(feature = "yaml-support")]
+    yaml: Encoding,
+    #[cfg(feature = "toml-support")]
+    toml: Encoding,
+    properties: Encoding,
+}
+
+impl Default for Encodings {
+    fn default() -> Self {
+        Self {
+            hocon: Encoding::Utf8,
+            json: Encoding::Utf8,
+            json5: Encoding::Utf8,
+            #[cfg(feature = "yaml-support")]
+            yaml: Encoding::Utf8,
+            #[cfg(feature = "toml-support")]
+            toml: Encoding::Utf8,
+            properties: Encoding::Latin1,
+        }
+    }
+}
+
+impl Encodings {
+    pub(crate) fn of(&self, format: Format) -> Encoding {
+        match format {
+            Format::Hocon => self.hocon,
+            Format::Json => self.json,
+            Format::Json5 => self.json5,
+            #[cfg(feature = "yaml-support")]
+            Format::Yaml => self.yaml,
+            #[cfg(feature = "toml-support")]
+            Format::Toml => self.toml,
+            Format::Properties => self.properties,
+        }
+    }
+
+    pub(crate) fn with(self, format: Format, encoding: Encoding) -> Self {
+        match format {
+            Format::Hocon => Self {
+                hocon: encoding,
+                ..self
+            },
+            Format::Json => Self {
+                json: encoding,
+                ..self
+            },
+            Format::Json5 => Self {
+                json5: encoding,
+                ..self
+            },
+            #[cfg(feature = "yaml-support")]
+            Format::Yaml => Self {
+                yaml: encoding,
+                ..self
+            },
+            #[cfg(feature = "toml-support")]
+            Format::Toml => Self {
+                toml: encoding,
+                ..self
+            },
+            Format::Properties => Self {
+                properties: encoding,
+                ..self
+            },
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub(crate) struct FileRead {
     pub(crate) properties: Option<String>,
     pub(crate) json: Option<String>,
+    pub(crate) json5: Option<String>,
+    #[cfg(feature = "yaml-support")]
+    pub(crate) yaml: Option<String>,
+    #[cfg(feature = "toml-support")]
+    pub(crate) toml: Option<String>,
     pub(crate) hocon: Option<String>,
 }
 impl FileRead {
+    pub(crate) fn from_format(format: Format, s: String) -> Self {
+        let file_type = match format {
+            Format::Hocon => FileType::Hocon,
+            Format::Json => FileType::Json,
+            Format::Json5 => FileType::Json5,
+            #[cfg(feature = "yaml-support")]
+            Format::Yaml => FileType::Yaml,
+            #[cfg(feature = "toml-support")]
+            Format::Toml => FileType::Toml,
+            Format::Properties => FileType::Properties,
+        };
+        Self::from_file_type(&file_type, s)
+    }
+
+    /// Whether no file was read
+    pub(crate) fn is_empty(&self) -> bool {
+        #[cfg(feature = "yaml-support")]
+        if self.yaml.is_some() {
+            return false;
+        }
+        #[cfg(feature = "toml-support")]
+        if self.toml.is_some() {
+            return false;
+        }
+        self.properties.is_none()
+            && self.json.is_none()
+            && self.json5.is_none()
+            && self.hocon.is_none()
+    }
+
     fn from_file_type(ft: &FileType, s: String) -> Self {
         match ft {
             FileType::Properties => Self {
@@ -34,11 +226,25 @@ impl FileRead {
                 json: Some(s),
                 ..Default::default()
             },
-            FileType::Hocon => Self {
+            FileType::Json5 => Self {
+                json5: Some(s),
+                ..Default::default()
+            },
+            #[cfg(feature = "yaml-support")]
+            FileType::Yaml => Self {
+                yaml: Some(s),
+                ..Default::default()
+            },
+            #[cfg(feature = "toml-support")]
+            FileType::Toml => Self {
+                toml: Some(s),
+                ..Default::default()
+            },
+            // a single document of unknown type is read as HOCON, a superset of JSON
+            FileType::Hocon | FileType::All => Self {
                 hocon: Some(s),
                 ..Default::default()
             },
-            FileType::All => unimplemented!(),
         }
     }
 }
@@ -49,6 +255,14 @@ pub(crate) struct ConfFileMeta {
     full_path: PathBuf,
     file_type: FileType,
 }
+/// Name of a file for include cycles, canonical if it exists
+fn canonical_name(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
 impl ConfFileMeta {
     pub(crate) fn from_path(path: PathBuf) -> Self {
         let file = path
@@ -65,6 +279,11 @@ impl ConfFileMeta {
             file_type: match Path::new(file).extension().and_then(OsStr::to_str) {
                 Some("properties") => FileType::Properties,
                 Some("json") => FileType::Json,
+                Some("json5") => FileType::Json5,
+                #[cfg(feature = "yaml-support")]
+                Some("yaml" | "yml") => FileType::Yaml,
+                #[cfg(feature = "toml-support")]
+                Some("toml") => FileType::Toml,
                 Some("conf") => FileType::Hocon,
                 _ => FileType::All,
             },
@@ -72,27 +291,206 @@ impl ConfFileMeta {
     }
 }
 
+/// Where `include classpath("...")` looks for resources: resources registered with their
+/// content first, then files in the search directories, in order
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Classpath {
+    pub(crate) dirs: Vec<PathBuf>,
+    pub(crate) resources: HashMap<String, String>,
+}
+
+/// Limits on the values built by substitutions while resolving a document. Clones share the
+/// count of values already expanded
+#[derive(Debug, Clone)]
+pub(crate) struct ResolutionLimits {
+    pub(crate) max_substitutions: usize,
+    pub(crate) max_value_size: Option<usize>,
+    pub(crate) expanded: Rc<Cell<usize>>,
+}
+
+impl Default for ResolutionLimits {
+    fn default() -> Self {
+        Self {
+            max_substitutions: 100_000,
+            max_value_size: None,
+            expanded: Rc::default(),
+        }
+    }
+}
+
+impl ResolutionLimits {
+    /// Same limits, with nothing expanded yet, to resolve a new document
+    pub(crate) fn restarted(&self) -> Self {
+        Self {
+            expanded: Rc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Count `size` more values expanded by substitutions
+    pub(crate) fn expand(&self, size: usize) -> Result<()> {
+        let expanded = self.expanded.get().saturating_add(size);
+        self.expanded.set(expanded);
+        if expanded > self.max_substitutions {
+            Err(Error::ResourceLimit {
+                limit: format!(
+                    "more than {} values expanded by substitutions",
+                    self.max_substitutions
+                ),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check the size in bytes of a string built by a concatenation
+    pub(crate) fn check_size(&self, size: usize) -> Result<()> {
+        match self.max_value_size {
+            Some(max_value_size) if size > max_value_size => Err(Error::ResourceLimit {
+                limit: format!("value of more than {} bytes", max_value_size),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct HoconLoaderConfig {
     pub(crate) include_depth: u8,
     pub(crate) file_meta: Option<ConfFileMeta>,
-    pub(crate) system: bool,
-    #[cfg(feature = "url-support")]
+    pub(crate) env: bool,
+    pub(crate) use_system_properties: bool,
+    pub(crate) system_properties: Rc<HashMap<String, String>>,
+    #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
     pub(crate) external_url: bool,
     pub(crate) strict: bool,
+    pub(crate) strict_json: bool,
+    pub(crate) keep_comments: bool,
     pub(crate) max_include_depth: u8,
+    pub(crate) max_include_size: Option<u64>,
+    pub(crate) max_parsed_values: Option<usize>,
+    pub(crate) parsed_values: Rc<Cell<usize>>,
+    pub(crate) encodings: Encodings,
+    pub(crate) properties_options: PropertiesOptions,
+    #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
+    pub(crate) url_timeout: Option<std::time::Duration>,
+    #[cfg(feature = "url-support")]
+    pub(crate) url_requests: UrlRequests,
+    #[cfg(feature = "url-support")]
+    pub(crate) reqwest_client: Option<reqwest::blocking::Client>,
+    pub(crate) max_resolution_passes: usize,
+    pub(crate) tracer: Option<Tracer>,
+    pub(crate) unresolved_optionals: Rc<RefCell<Vec<UnresolvedOptional>>>,
+    pub(crate) defer_substitutions: bool,
+    pub(crate) overridden_paths: Rc<Vec<Vec<HoconValue>>>,
+    pub(crate) profile: Option<String>,
+    pub(crate) first_wins: bool,
+    pub(crate) env_allowlist: Option<Vec<String>>,
+    pub(crate) env_denylist: Vec<String>,
+    pub(crate) env_case_insensitive: bool,
+    pub(crate) env_source: Option<Rc<HashMap<String, String>>>,
+    pub(crate) dotenv: Rc<HashMap<String, String>>,
+    pub(crate) substitution_defaults: bool,
+    pub(crate) file_substitutions: bool,
+    pub(crate) string_interpolation: bool,
+    pub(crate) include_substitutions: bool,
+    pub(crate) include_globs: bool,
+    pub(crate) env_includes: bool,
+    pub(crate) archive_includes: bool,
+    pub(crate) directory_includes: Option<DirectoryIncludes>,
+    pub(crate) classpath: Rc<Classpath>,
+    pub(crate) search_paths: Rc<Vec<PathBuf>>,
+    pub(crate) include_root: Option<PathBuf>,
+    pub(crate) include_resolver: Option<SharedResolver>,
+    pub(crate) http_fetcher: Option<SharedFetcher>,
+    pub(crate) url_filter: UrlFilter,
+    pub(crate) include_chain: Rc<Vec<String>>,
+    pub(crate) include_cache: Rc<RefCell<HashMap<(String, SystemTime), IncludeCacheEntry>>>,
+    pub(crate) include_sources: Rc<RefCell<Vec<LoadedSource>>>,
+    pub(crate) deferred_urls: Option<Rc<RefCell<Vec<DeferredUrl>>>>,
+    pub(crate) prefetched: Rc<RefCell<HashMap<String, Result<String>>>>,
+    #[cfg(feature = "async")]
+    pub(crate) async_loading: bool,
+    pub(crate) loaded_layers: Rc<Vec<Layer>>,
+    pub(crate) limits: ResolutionLimits,
+}
+
+/// A document included while parsing another one, with the sources read to include it
+pub(crate) type IncludeCacheEntry = (HoconInternal, Vec<LoadedSource>);
+
+/// An URL included while parsing a document, fetched with the other URLs it includes once
+/// it is parsed, then included in place of its placeholder
+#[derive(Debug)]
+pub(crate) struct DeferredUrl {
+    marker: String,
+    url: String,
+    required: bool,
+    config: HoconLoaderConfig,
 }
 
+/// Number of threads fetching the URLs included by a document
+const FETCH_THREADS: usize = 8;
+
 impl Default for HoconLoaderConfig {
     fn default() -> Self {
         Self {
             include_depth: 0,
             file_meta: None,
-            system: true,
-            #[cfg(feature = "url-support")]
+            env: true,
+            use_system_properties: true,
+            system_properties: Rc::default(),
+            #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
             external_url: true,
             strict: false,
+            strict_json: false,
+            keep_comments: false,
             max_include_depth: 10,
+            max_include_size: None,
+            max_parsed_values: None,
+            parsed_values: Rc::default(),
+            encodings: Encodings::default(),
+            properties_options: PropertiesOptions::default(),
+            #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
+            url_timeout: None,
+            #[cfg(feature = "url-support")]
+            url_requests: UrlRequests::default(),
+            #[cfg(feature = "url-support")]
+            reqwest_client: None,
+            max_resolution_passes: 100,
+            tracer: None,
+            unresolved_optionals: Rc::default(),
+            defer_substitutions: false,
+            overridden_paths: Rc::new(vec![]),
+            profile: None,
+            first_wins: false,
+            env_allowlist: None,
+            env_denylist: vec![],
+            env_case_insensitive: false,
+            env_source: None,
+            dotenv: Rc::default(),
+            substitution_defaults: false,
+            file_substitutions: false,
+            string_interpolation: false,
+            include_substitutions: false,
+            include_globs: false,
+            env_includes: false,
+            archive_includes: false,
+            directory_includes: None,
+            classpath: Rc::default(),
+            search_paths: Rc::default(),
+            include_root: None,
+            include_resolver: None,
+            http_fetcher: None,
+            url_filter: UrlFilter::default(),
+            include_chain: Rc::default(),
+            include_cache: Rc::default(),
+            include_sources: Rc::default(),
+            deferred_urls: None,
+            prefetched: Rc::default(),
+            #[cfg(feature = "async")]
+            async_loading: false,
+            loaded_layers: Rc::new(vec![]),
+            limits: ResolutionLimits::default(),
         }
     }
 }
@@ -105,32 +503,379 @@ impl HoconLoaderConfig {
         }
     }
 
-    pub(crate) fn with_file(&self, path: PathBuf) -> Self {
-        match self.file_meta.as_ref() {
-            Some(file_meta) => Self {
-                file_meta: Some(ConfFileMeta::from_path(file_meta.clone().path.join(path))),
-                ..self.clone()
-            },
-            None => Self {
-                file_meta: Some(ConfFileMeta::from_path(path)),
+    /// Config to load the document included as `target`, a canonical path or an URL, failing
+    /// if it is already being included along the current chain of includes
+    pub(crate) fn entering(&self, target: String) -> Result<Self> {
+        let mut chain = match self.file_meta.as_ref() {
+            Some(file_meta) if self.include_chain.is_empty() => {
+                vec![canonical_name(&file_meta.full_path)]
+            }
+            _ => self.include_chain.to_vec(),
+        };
+        let cycle_start = chain.iter().position(|included| *included == target);
+        chain.push(target);
+        match cycle_start {
+            Some(start) => Err(Error::IncludeCycle {
+                chain: chain.split_off(start),
+            }),
+            None => Ok(Self {
+                include_chain: Rc::new(chain),
                 ..self.clone()
-            },
+            }),
+        }
+    }
+
+    /// Canonical name of the file included as `path`, relative to the current file, `None`
+    /// if it does not exist
+    pub(crate) fn included_name(&self, path: &Path) -> Option<String> {
+        let path = match self.file_meta.as_ref() {
+            Some(file_meta) => file_meta.path.join(path),
+            None => path.to_path_buf(),
+        };
+        path.exists().then(|| canonical_name(&path))
+    }
+
+    /// Substitutions are left for the second pass, once all layers are merged, when
+    /// resolving them now could miss a value from a later layer
+    pub(crate) fn defers_substitution(
+        &self,
+        target: &[HoconValue],
+        at_path: &[HoconValue],
+    ) -> bool {
+        target != at_path
+            && (self.defer_substitutions
+                || self
+                    .overridden_paths
+                    .iter()
+                    .any(|path| path.starts_with(target) || target.starts_with(path)))
+    }
+
+    /// Value of `name` when it is not in the documents, from the system properties first,
+    /// then the environment variables
+    pub(crate) fn system_value(&self, name: &str) -> Option<(String, Resolution)> {
+        self.system_properties
+            .get(name)
+            .filter(|_| self.use_system_properties)
+            .map(|value| (value.clone(), Resolution::SystemProperty))
+            .or_else(|| {
+                self.env_var(name)
+                    .map(|value| (value, Resolution::Environment))
+            })
+    }
+
+    /// Value of environment variable `name`, if substitutions can read it
+    pub(crate) fn env_var(&self, name: &str) -> Option<String> {
+        let same_name = |other: &String| {
+            if self.env_case_insensitive {
+                other.eq_ignore_ascii_case(name)
+            } else {
+                other == name
+            }
+        };
+        let allowed = self.env
+            && self
+                .env_allowlist
+                .as_ref()
+                .is_none_or(|allowlist| allowlist.iter().any(same_name))
+            && !self.env_denylist.iter().any(same_name);
+        if !allowed {
+            None
+        } else if self.env_case_insensitive {
+            self.read_env(name).or_else(|| {
+                let mut vars = self
+                    .read_env_vars()
+                    .into_iter()
+                    .filter(|(var, _)| same_name(var))
+                    .collect::<Vec<_>>();
+                // variables can differ only by case, keep the result reproducible
+                vars.sort();
+                vars.into_iter().next().map(|(_, value)| value)
+            })
+        } else {
+            self.read_env(name)
+        }
+    }
+
+    /// Value of environment variable `name`, from the process environment or the source set
+    /// with `with_env_source`, or else from the loaded `.env` files
+    pub(crate) fn read_env(&self, name: &str) -> Option<String> {
+        match &self.env_source {
+            Some(vars) => vars.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        }
+        .or_else(|| self.dotenv.get(name).cloned())
+    }
+
+    /// All the environment variables with a name and value in unicode
+    pub(crate) fn read_env_vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = match &self.env_source {
+            Some(vars) => vars
+                .iter()
+                .map(|(var, value)| (var.clone(), value.clone()))
+                .collect(),
+            None => std::env::vars_os()
+                .filter_map(|(var, value)| {
+                    Some((var.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+        };
+        // the environment wins over the `.env` files
+        let dotenv = self
+            .dotenv
+            .iter()
+            .filter(|(var, _)| !vars.iter().any(|(name, _)| name == *var))
+            .map(|(var, value)| (var.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        vars.extend(dotenv);
+        vars
+    }
+
+    /// Target of an include built with substitutions, resolved against the documents loaded
+    /// before this one, then the environment. `None` if a substitution is not found
+    pub(crate) fn include_target(&self, parts: &[HoconValue]) -> Option<String> {
+        let config = Self {
+            strict: false,
+            tracer: None,
+            ..self.clone()
+        };
+        let loaded = HoconInternal::merge(self.loaded_layers.as_ref().clone(), &config)
+            .and_then(|merged| merged.finalize(&config))
+            .ok();
+        parts
+            .iter()
+            .map(|part| match part {
+                HoconValue::String(s) => Some(s.to_string()),
+                HoconValue::PathSubstitution {
+                    target, optional, ..
+                } => {
+                    let keys = target
+                        .to_path()
+                        .into_iter()
+                        .map(HoconValue::key_string)
+                        .collect::<Vec<_>>();
+                    loaded
+                        .as_ref()
+                        .and_then(|loaded| loaded.at_path(&keys).as_string())
+                        .or_else(|| {
+                            self.system_value(&target.path_string())
+                                .map(|(value, _)| value)
+                        })
+                        .or_else(|| optional.then(String::new))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn source(&self) -> Source {
+        match self.file_meta.as_ref() {
+            Some(file_meta) => Source::File(file_meta.full_path.clone()),
+            None => Source::Str,
+        }
+    }
+
+    pub(crate) fn with_file(&self, path: PathBuf) -> Self {
+        let path = match self.file_meta.as_ref() {
+            Some(file_meta) => file_meta.path.join(path),
+            None => path,
+        };
+        let mut file_meta = ConfFileMeta::from_path(path);
+        // files at the root of an archive include the other files of the archive
+        if let Some((archive, entry)) = self.archive_entry(&file_meta.full_path)
+            && !entry.contains('/')
+        {
+            let mut root = archive.into_os_string();
+            root.push("!");
+            file_meta.path = PathBuf::from(root);
+        }
+        Self {
+            file_meta: Some(file_meta),
+            ..self.clone()
+        }
+    }
+
+    /// Document of an include from the registered include resolver, `None` if there is no
+    /// resolver or it lets the loader resolve the include
+    pub(crate) fn resolve_include(&self, target: IncludeTarget) -> Option<Result<HoconInternal>> {
+        let resolver = self.include_resolver.as_ref()?;
+        let (include_config, name) = match target {
+            IncludeTarget::File(path) => (
+                self.included_from().with_file(path.to_path_buf()),
+                path.to_path_buf(),
+            ),
+            IncludeTarget::Url(name) | IncludeTarget::Classpath(name) => {
+                (self.included_from(), PathBuf::from(name))
+            }
+        };
+        let file_type = match name.extension().and_then(OsStr::to_str) {
+            Some("properties") => FileType::Properties,
+            Some("json") => FileType::Json,
+            Some("json5") => FileType::Json5,
+            #[cfg(feature = "yaml-support")]
+            Some("yaml" | "yml") => FileType::Yaml,
+            #[cfg(feature = "toml-support")]
+            Some("toml") => FileType::Toml,
+            _ => FileType::Hocon,
+        };
+        match resolver.0.resolve(target) {
+            Ok(None) => None,
+            Ok(Some(content)) => Some(
+                include_config.parse_str_to_internal(FileRead::from_file_type(&file_type, content)),
+            ),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Document of resource `name` of the classpath, `None` if it is not found. As for files,
+    /// a name without extension matches resources with extension `.conf`, `.json` or
+    /// `.properties`
+    pub(crate) fn include_classpath(&self, name: &str) -> Option<Result<HoconInternal>> {
+        let variants = match Path::new(name).extension() {
+            Some(_) => vec![String::from(name)],
+            None => ["", ".conf", ".json", ".properties"]
+                .iter()
+                .map(|extension| format!("{}{}", name, extension))
+                .collect(),
+        };
+        let included_from = self.included_from();
+        if let Some((resource, content)) = variants
+            .iter()
+            .find_map(|variant| self.classpath.resources.get_key_value(variant))
+        {
+            let file_type = ConfFileMeta::from_path(PathBuf::from(resource)).file_type;
+            return Some(
+                included_from
+                    .parse_str_to_internal(FileRead::from_file_type(&file_type, content.clone())),
+            );
+        }
+        self.classpath
+            .dirs
+            .iter()
+            .filter_map(|dir| std::path::absolute(dir).ok())
+            .find(|dir| variants.iter().any(|variant| dir.join(variant).is_file()))
+            .map(|dir| {
+                let include_config = included_from.with_file(dir.join(name));
+                include_config
+                    .read_file()
+                    .map_err(|_| Error::Include {
+                        path: String::from(name),
+                    })
+                    .and_then(|s| include_config.parse_source(include_config.read_sources(), s))
+            })
+    }
+
+    /// Path of a file to include, relative to the directory of the including file or else
+    /// found in the first search path where it exists. `path` is kept if it is found in none
+    pub(crate) fn locate_include(&self, path: &Path) -> PathBuf {
+        // a file without extension exists when one of its variants does
+        let exists = |path: &Path| {
+            ["", ".conf", ".json", ".properties"]
+                .iter()
+                .any(|extension| {
+                    let mut variant = path.as_os_str().to_os_string();
+                    variant.push(extension);
+                    Path::new(&variant).exists()
+                })
+        };
+        let base = self
+            .file_meta
+            .as_ref()
+            .map(|file_meta| file_meta.path.clone())
+            .unwrap_or_default();
+        if path.is_absolute() || exists(&base.join(path)) {
+            return path.to_path_buf();
+        }
+        self.search_paths
+            .iter()
+            .filter_map(|dir| std::path::absolute(dir).ok())
+            .map(|dir| dir.join(path))
+            .find(|path| exists(path))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// Files of the directory at `path` to include, relative to the directory of the
+    /// including file. `None` if `path` is not a directory or directories can not be included
+    pub(crate) fn directory_include(&self, path: &str) -> Option<Vec<PathBuf>> {
+        let base = self
+            .file_meta
+            .as_ref()
+            .map(|file_meta| file_meta.path.clone())
+            .unwrap_or_default();
+        self.directory_includes
+            .as_ref()
+            .filter(|_| base.join(path).is_dir())
+            .map(|options| options.files(&base, Path::new(path)))
+    }
+
+    /// Files matching the glob pattern of an include, relative to the directory of the
+    /// including file, sorted by path. Wildcards can be in any component of the path, but do
+    /// not match hidden files unless the component starts with `.`
+    pub(crate) fn glob_include(&self, pattern: &str) -> Vec<PathBuf> {
+        let base = self
+            .file_meta
+            .as_ref()
+            .map(|file_meta| file_meta.path.clone())
+            .unwrap_or_default();
+        let mut matches = vec![PathBuf::new()];
+        for component in Path::new(pattern).components() {
+            let component = component.as_os_str().to_string_lossy();
+            matches = if crate::helper::is_glob(&component) {
+                matches
+                    .into_iter()
+                    .flat_map(|dir| {
+                        let mut entries = std::fs::read_dir(base.join(&dir))
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                            .filter(|name| !name.starts_with('.') || component.starts_with('.'))
+                            .filter(|name| crate::helper::glob_match(&component, name))
+                            .map(|name| dir.join(name))
+                            .collect::<Vec<_>>();
+                        entries.sort();
+                        entries
+                    })
+                    .collect()
+            } else {
+                matches
+                    .into_iter()
+                    .map(|dir| dir.join(component.as_ref()))
+                    .collect()
+            };
         }
+        matches.retain(|path| base.join(path).is_file());
+        matches
     }
 
     pub(crate) fn parse_str_to_internal(&self, s: FileRead) -> Result<HoconInternal> {
+        // the documents included are parsed in the scope of the one including them
+        if self.deferred_urls.is_some() || !self.defers_urls() {
+            return self.parse_document(s);
+        }
+        let config = self.deferring_urls();
+        let parsed_before = self.parsed_values.get();
+        let internal = config.parse_document(s)?;
+        let mut included = HashMap::new();
+        while let Some(deferred) = config.take_deferred_urls() {
+            config.fetch_concurrently(deferred.iter().map(|deferred| deferred.url.clone()));
+            config.include_deferred_urls(deferred, &mut included)?;
+        }
+        config.with_deferred_urls(internal, &included, parsed_before)
+    }
+
+    /// Parse a document, with its includes
+    fn parse_document(&self, s: FileRead) -> Result<HoconInternal> {
+        let parsed_before = self.parsed_values.get();
         let mut internal = HoconInternal::empty();
         if let Some(properties) = s.properties {
-            internal = internal.add(
-                java_properties::read(properties.as_bytes())
-                    .map(HoconInternal::from_properties)
-                    .map_err(|_| Error::Parse)?,
-            );
+            let values = self.properties_options.parse(&properties)?;
+            internal = internal.add(HoconInternal::from_properties(values));
         };
         if let Some(json) = s.json {
-            let input = format!("{}\n\0", json.replace('\r', "\n"));
+            if self.strict_json {
+                strict_json::validate(&json)?;
+            }
             internal = internal.add(
-                parser::root(self)(&input)
+                parser::root(self)(&json)
                     .map_err(|_| Error::Parse)
                     .and_then(|(remaining, parsed)| {
                         if Self::remaining_only_whitespace(remaining) {
@@ -142,13 +887,24 @@ impl HoconLoaderConfig {
                         } else {
                             parsed
                         }
-                    })?,
+                    })?
+                    .locate(&json, &self.source()),
             );
         };
+        if let Some(json5) = s.json5 {
+            internal = internal.add(HoconInternal::from_hocon(json5::parse(&json5)?));
+        };
+        #[cfg(feature = "yaml-support")]
+        if let Some(yaml) = s.yaml {
+            internal = internal.add(yaml::parse(&yaml)?);
+        };
+        #[cfg(feature = "toml-support")]
+        if let Some(toml) = s.toml {
+            internal = internal.add(HoconInternal::from_hocon(crate::toml::parse(&toml)?));
+        };
         if let Some(hocon) = s.hocon {
-            let input = format!("{}\n\0", hocon.replace('\r', "\n"));
             internal = internal.add(
-                parser::root(self)(&input)
+                parser::root(self)(&hocon)
                     .map_err(|_| Error::Parse)
                     .and_then(|(remaining, parsed)| {
                         if Self::remaining_only_whitespace(remaining) {
@@ -160,100 +916,640 @@ impl HoconLoaderConfig {
                         } else {
                             parsed
                         }
-                    })?,
+                    })?
+                    .locate(&hocon, &self.source()),
             );
         };
 
+        // the values of the included documents, counted as they were parsed, are part of the
+        // values of this document
+        self.parsed_values.set(parsed_before);
+        self.count_parsed(internal.internal.len())?;
         Ok(internal)
     }
 
-    fn remaining_only_whitespace(remaining: &str) -> bool {
-        remaining
-            .chars()
-            .all(|c| c == '\n' || c == '\r' || c == '\0')
+    /// Count `values` more values parsed, for the document and all its includes
+    pub(crate) fn count_parsed(&self, values: usize) -> Result<()> {
+        let parsed = self.parsed_values.get().saturating_add(values);
+        self.parsed_values.set(parsed);
+        match self.max_parsed_values {
+            Some(max_parsed_values) if parsed > max_parsed_values => Err(Error::ResourceLimit {
+                limit: format!("more than {max_parsed_values} values parsed"),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the URLs included by a document are fetched, by the registered fetcher or by
+    /// `reqwest` or `ureq`
+    fn fetches_urls(&self) -> bool {
+        #[cfg(any(feature = "url-support", feature = "url-support-ureq"))]
+        if !self.external_url {
+            return false;
+        }
+        self.http_fetcher.is_some()
+            || cfg!(any(feature = "url-support", feature = "url-support-ureq"))
     }
 
-    pub(crate) fn read_file_to_string(path: PathBuf) -> Result<String> {
-        let mut file = File::open(path.as_os_str())?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
-        Ok(contents)
+    /// Whether the URLs included by a document are fetched together once it is parsed,
+    /// instead of one after the other while parsing it
+    fn defers_urls(&self) -> bool {
+        // when loading asynchronously, the URLs are fetched by the runtime
+        #[cfg(feature = "async")]
+        if self.async_loading {
+            return false;
+        }
+        self.fetches_urls()
     }
 
-    pub(crate) fn read_file(&self) -> Result<FileRead> {
-        let full_path = self
-            .file_meta
-            .clone()
-            .expect("missing file metadata")
-            .full_path;
-        match self.file_meta.as_ref().map(|fm| &fm.file_type) {
-            Some(FileType::All) => Ok(FileRead {
-                hocon: Self::read_file_to_string({
-                    let mut path = full_path.clone();
-                    if !path.exists() {
-                        path.set_extension("conf");
-                    }
-                    path
+    /// Configuration to parse a document, recording the URLs it includes to fetch them
+    /// once it is parsed
+    fn deferring_urls(&self) -> Self {
+        Self {
+            deferred_urls: Some(Rc::default()),
+            ..self.clone()
+        }
+    }
+
+    /// Placeholder for the document at `url`, included while parsing, that is fetched once
+    /// the document including it is parsed. The URLs that can not be fetched, like `file://`
+    /// URLs or an URL already included, are included right away
+    pub(crate) fn defer_url(&self, url: &str, required: bool) -> Option<HoconInternal> {
+        let deferred_urls = self.deferred_urls.as_ref()?;
+        if url
+            .get(..5)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file:"))
+            || !self.url_filter.allows(url)
+            || self.prefetched.borrow().contains_key(url)
+            || self.entering(String::from(url)).is_err()
+        {
+            return None;
+        }
+        let marker = uuid::Uuid::new_v4().hyphenated().to_string();
+        // the sources read for the URL are recorded in place of this one once it is fetched
+        self.include_sources.borrow_mut().push(LoadedSource {
+            source: Source::Url(marker.clone()),
+            includes: vec![],
+        });
+        deferred_urls.borrow_mut().push(DeferredUrl {
+            marker: marker.clone(),
+            url: String::from(url),
+            required,
+            config: Self {
+                deferred_urls: None,
+                ..self.clone()
+            },
+        });
+        Some(HoconInternal::deferred(marker))
+    }
+
+    /// URLs included since they were last taken, in the document or in the documents it
+    /// includes
+    fn take_deferred_urls(&self) -> Option<Vec<DeferredUrl>> {
+        Some(self.deferred_urls.as_ref()?.take()).filter(|deferred| !deferred.is_empty())
+    }
+
+    /// Include the documents fetched for `deferred`, recording them in `included` by their
+    /// marker with the sources read for their URL. The URLs they include are deferred in turn
+    fn include_deferred_urls(
+        &self,
+        deferred: Vec<DeferredUrl>,
+        included: &mut HashMap<String, IncludeCacheEntry>,
+    ) -> Result<()> {
+        for deferred in deferred {
+            if !self.prefetched.borrow().contains_key(&deferred.url) {
+                let fetched = self.fetch(&deferred.url);
+                self.prefetched
+                    .borrow_mut()
+                    .insert(deferred.url.clone(), fetched);
+            }
+            let include = Include::Url(Cow::Borrowed(&deferred.url));
+            let include = match deferred.required {
+                true => Include::Required(Box::new(include)),
+                false => include,
+            };
+            let sources = Rc::default();
+            let internal = HoconInternal::from_include(
+                include,
+                &Self {
+                    include_sources: Rc::clone(&sources),
+                    deferred_urls: self.deferred_urls.clone(),
+                    ..deferred.config
+                },
+            )?;
+            included.insert(deferred.marker, (internal, sources.take()));
+        }
+        Ok(())
+    }
+
+    /// The document parsed as `internal`, with the documents `included` for the URLs it
+    /// includes in place of their placeholders, and the sources read for them recorded
+    fn with_deferred_urls(
+        &self,
+        internal: HoconInternal,
+        included: &HashMap<String, IncludeCacheEntry>,
+        parsed_before: usize,
+    ) -> Result<HoconInternal> {
+        let internal = internal.with_deferred(included);
+        let sources = self.include_sources.take();
+        self.include_sources
+            .replace(with_fetched_sources(sources, included));
+        self.parsed_values.set(parsed_before);
+        self.count_parsed(internal.internal.len())?;
+        Ok(internal)
+    }
+
+    /// Fetch `urls` that were not fetched yet, with at most `FETCH_THREADS` of them fetched
+    /// at the same time, by the fetcher used while parsing
+    fn fetch_concurrently(&self, urls: impl IntoIterator<Item = String>) {
+        let mut urls = urls
+            .into_iter()
+            .filter(|url| !self.prefetched.borrow().contains_key(url))
+            .collect::<Vec<_>>();
+        urls.sort();
+        urls.dedup();
+        // a single URL is fetched while including it, without a thread
+        let Some(fetcher) = self.url_fetcher().filter(|_| urls.len() > 1) else {
+            return;
+        };
+        let (next, max_size) = (AtomicUsize::new(0), self.max_include_size);
+        let fetched = std::thread::scope(|scope| {
+            let workers = (0..urls.len().min(FETCH_THREADS))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut fetched = vec![];
+                        while let Some(url) = urls.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            fetched.push((url.clone(), fetch_limited(&*fetcher, url, max_size)));
+                        }
+                        fetched
+                    })
                 })
-                .ok(),
-                json: Self::read_file_to_string({
-                    let mut path = full_path.clone();
-                    path.set_extension("json");
-                    path
+                .collect::<Vec<_>>();
+            // the URLs of a worker that panicked are fetched again while including them
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect::<Vec<_>>()
+        });
+        self.prefetched.borrow_mut().extend(fetched);
+    }
+
+    /// Fetch concurrently `urls`, that were not fetched yet, with `runtime` so that the
+    /// documents including them can be parsed without blocking
+    #[cfg(feature = "async")]
+    pub(crate) async fn fetch_async(
+        &self,
+        runtime: &impl AsyncRuntime,
+        urls: impl IntoIterator<Item = String>,
+    ) {
+        if !self.fetches_urls() {
+            return;
+        }
+        let Some(fetcher) = self.url_fetcher() else {
+            return;
+        };
+        let mut urls = urls
+            .into_iter()
+            .filter(|url| {
+                self.url_filter.allows(url) && !self.prefetched.borrow().contains_key(url)
+            })
+            .collect::<Vec<_>>();
+        urls.sort();
+        urls.dedup();
+        let max_size = self.max_include_size;
+        // all the fetches are started before waiting for the first one
+        let fetching = urls
+            .into_iter()
+            .map(|url| (url.clone(), runtime.fetch(Arc::clone(&fetcher), url)))
+            .collect::<Vec<_>>();
+        for (url, fetch) in fetching {
+            let fetched = fetch
+                .await
+                .and_then(|body| within_size(&url, body, max_size));
+            self.prefetched.borrow_mut().insert(url, fetched);
+        }
+    }
+
+    /// Parse a document read with `runtime`, fetching with it the URLs included
+    #[cfg(feature = "async")]
+    pub(crate) async fn parse_async(
+        &self,
+        runtime: &impl AsyncRuntime,
+        s: FileRead,
+    ) -> Result<HoconInternal> {
+        // without a fetcher, the URLs are included as errors while parsing
+        let config = match self.fetches_urls() {
+            true => self.deferring_urls(),
+            false => self.clone(),
+        };
+        let parsed_before = self.parsed_values.get();
+        let internal = config.parse_document(s)?;
+        let mut included = HashMap::new();
+        while let Some(deferred) = config.take_deferred_urls() {
+            config
+                .fetch_async(
+                    runtime,
+                    deferred.iter().map(|deferred| deferred.url.clone()),
+                )
+                .await;
+            config.include_deferred_urls(deferred, &mut included)?;
+        }
+        config.with_deferred_urls(internal, &included, parsed_before)
+    }
+
+    fn remaining_only_whitespace(remaining: &str) -> bool {
+        remaining.chars().all(|c| c == '\n' || c == '\r')
+    }
+
+    pub(crate) fn read_file_to_string(path: PathBuf, encoding: Encoding) -> Result<String> {
+        let contents = std::fs::read(&path)?;
+        crate::encoding::decode(contents, &path.display().to_string(), encoding)
+    }
+
+    /// Read a file of the document in `format`, checking that an included file is in the
+    /// include root and within the maximum include size
+    fn read_included_file(&self, path: PathBuf, format: Format) -> Result<String> {
+        let encoding = self.encodings.of(format);
+        if let Some((archive, entry)) = self.archive_entry(&path) {
+            return self.read_archive_entry(&path, &archive, &entry, encoding);
+        }
+        if self.include_depth > 0 {
+            self.check_include_root(&path)?;
+            if let Some(max_size) = self.max_include_size {
+                let name = path.display().to_string();
+                return read_limited(File::open(path)?, max_size, &name, encoding);
+            }
+        }
+        Self::read_file_to_string(path, encoding)
+    }
+
+    /// Archive and entry of a path like `bundle.tar!defaults.conf`, if archives can be read
+    fn archive_entry(&self, path: &Path) -> Option<(PathBuf, String)> {
+        self.archive_includes
+            .then(|| crate::archive::split_archive_path(path))
+            .flatten()
+    }
+
+    /// Read the file `entry` of a tar archive, with the same checks as an included file
+    fn read_archive_entry(
+        &self,
+        path: &Path,
+        archive: &Path,
+        entry: &str,
+        encoding: Encoding,
+    ) -> Result<String> {
+        if self.include_depth > 0 {
+            self.check_include_root(archive)?;
+        }
+        let Some(content) = crate::archive::read_tar_entry(archive, entry)? else {
+            return Err(Error::File {
+                path: path.display().to_string(),
+            });
+        };
+        match self.max_include_size {
+            Some(max_size) if self.include_depth > 0 && content.len() as u64 > max_size => {
+                Err(too_large(&path.display().to_string(), max_size))
+            }
+            _ => crate::encoding::decode(content, &path.display().to_string(), encoding),
+        }
+    }
+
+    /// Check that `path` is in the include root once symbolic links and `..` are resolved. A
+    /// path that does not exist can not be read and is accepted
+    pub(crate) fn check_include_root(&self, path: &Path) -> Result<()> {
+        let Some(root) = self.include_root.as_ref() else {
+            return Ok(());
+        };
+        match std::fs::canonicalize(path) {
+            Ok(path) if std::fs::canonicalize(root).is_ok_and(|root| path.starts_with(root)) => {
+                Ok(())
+            }
+            Ok(_) => Err(Error::IncludeOutsideRoot {
+                path: path.display().to_string(),
+            }),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Parse a document read from `sources`, recording them as read by the current document,
+    /// with the documents they include
+    pub(crate) fn parse_source(&self, sources: Vec<Source>, s: FileRead) -> Result<HoconInternal> {
+        let includes = Rc::new(RefCell::new(vec![]));
+        let parsed = Self {
+            include_sources: includes.clone(),
+            ..self.clone()
+        }
+        .parse_str_to_internal(s)?;
+        // the variants of a file without extension are parsed together
+        let mut includes = Some(includes.take());
+        self.include_sources
+            .borrow_mut()
+            .extend(sources.into_iter().map(|source| LoadedSource {
+                source,
+                includes: includes.take().unwrap_or_default(),
+            }));
+        Ok(parsed)
+    }
+
+    /// Files read for the document, the variants that exist for a file without extension
+    pub(crate) fn read_sources(&self) -> Vec<Source> {
+        let Some(file_meta) = self.file_meta.as_ref() else {
+            return vec![];
+        };
+        match file_meta.file_type {
+            FileType::All if !file_meta.full_path.is_file() => [".conf", ".json", ".properties"]
+                .into_iter()
+                .map(|extension| {
+                    let mut path = file_meta.full_path.clone().into_os_string();
+                    path.push(extension);
+                    PathBuf::from(path)
                 })
-                .ok(),
-                properties: Self::read_file_to_string({
-                    let mut path = full_path;
-                    path.set_extension("properties");
-                    path
+                .filter(|path| path.is_file())
+                .map(Source::File)
+                .collect(),
+            _ => vec![Source::File(file_meta.full_path.clone())],
+        }
+    }
+
+    /// Last modification time of the file of the document, to tell whether a cached parse is
+    /// still current
+    pub(crate) fn file_modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.file_meta.as_ref()?.full_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Whether the file of the document exists
+    pub(crate) fn file_exists(&self) -> bool {
+        self.file_meta.as_ref().is_some_and(|file_meta| {
+            match self.archive_entry(&file_meta.full_path) {
+                Some((archive, entry)) => crate::archive::read_tar_entry(&archive, &entry)
+                    .is_ok_and(|read| read.is_some()),
+                None => file_meta.full_path.exists(),
+            }
+        })
+    }
+
+    pub(crate) fn read_file(&self) -> Result<FileRead> {
+        let file_meta = self.file_meta.as_ref().expect("missing file metadata");
+        let full_path = file_meta.full_path.clone();
+        match &file_meta.file_type {
+            // as in the spec, every variant that exists is read, and they are merged with
+            // `.conf` winning over `.json`, winning over `.properties`
+            FileType::All => {
+                let with_extension = |extension: &str| with_extension(&full_path, extension);
+                // a variant that can not be read is missing, unless it is out of the root, too
+                // large or badly encoded
+                let read_variant =
+                    |path: PathBuf, format| match self.read_included_file(path, format) {
+                        Err(
+                            err @ (Error::IncludeOutsideRoot { .. }
+                            | Error::ResourceLimit { .. }
+                            | Error::Encoding { .. }),
+                        ) => Err(err),
+                        read => Ok(read.ok()),
+                    };
+                Ok(FileRead {
+                    hocon: read_variant(
+                        if full_path.is_file() {
+                            full_path.clone()
+                        } else {
+                            with_extension(".conf")
+                        },
+                        Format::Hocon,
+                    )?,
+                    json: read_variant(with_extension(".json"), Format::Json)?,
+                    properties: read_variant(with_extension(".properties"), Format::Properties)?,
+                    ..Default::default()
                 })
-                .ok(),
-            }),
-            Some(ft) => Ok(FileRead::from_file_type(
+            }
+            ft => Ok(FileRead::from_file_type(
                 ft,
-                Self::read_file_to_string(full_path)?,
+                self.read_included_file(full_path, ft.format())?,
             )),
-            _ => unimplemented!(),
         }
     }
 
-    #[cfg(feature = "url-support")]
-    pub(crate) fn load_url(&self, url: &str) -> Result<HoconInternal> {
-        if let Ok(parsed_url) = reqwest::Url::parse(url) {
-            if parsed_url.scheme() == "file" {
-                if let Ok(path) = parsed_url.to_file_path() {
-                    let include_config = self.included_from().with_file(path);
-                    let s = include_config.read_file()?;
-                    Ok(include_config
-                        .parse_str_to_internal(s)
-                        .map_err(|_| Error::Include {
-                            path: String::from(url),
-                        })?)
-                } else {
-                    Err(Error::Include {
-                        path: String::from(url),
+    /// Read the file of a document as [`read_file`](#method.read_file), with `runtime` so that
+    /// it does not block unless it is in an archive
+    #[cfg(feature = "async")]
+    pub(crate) async fn read_file_async(&self, runtime: &impl AsyncRuntime) -> Result<FileRead> {
+        let file_meta = self.file_meta.as_ref().expect("missing file metadata");
+        let full_path = file_meta.full_path.clone();
+        if self.include_depth > 0 || self.archive_entry(&full_path).is_some() {
+            return self.read_file();
+        }
+        let read = |path: PathBuf, format| {
+            let reading = runtime.spawn_blocking({
+                let path = path.clone();
+                move || std::fs::read(path)
+            });
+            async move {
+                crate::encoding::decode(
+                    reading.await?,
+                    &path.display().to_string(),
+                    self.encodings.of(format),
+                )
+            }
+        };
+        // as with `read_file`, a variant that can not be read is missing unless badly encoded
+        let variant = |read: Result<String>| match read {
+            Err(err @ Error::Encoding { .. }) => Err(err),
+            read => Ok(read.ok()),
+        };
+        match &file_meta.file_type {
+            FileType::All => {
+                let is_file = runtime
+                    .spawn_blocking({
+                        let full_path = full_path.clone();
+                        move || full_path.is_file()
                     })
-                }
-            } else if self.external_url {
-                let body = reqwest::blocking::get(parsed_url)
-                    .and_then(reqwest::blocking::Response::text)
-                    .map_err(|_| Error::Include {
-                        path: String::from(url),
-                    })?;
-
-                Ok(self.parse_str_to_internal(FileRead {
-                    hocon: Some(body),
+                    .await;
+                let hocon = if is_file {
+                    full_path.clone()
+                } else {
+                    with_extension(&full_path, ".conf")
+                };
+                Ok(FileRead {
+                    hocon: variant(read(hocon, Format::Hocon).await)?,
+                    json: variant(read(with_extension(&full_path, ".json"), Format::Json).await)?,
+                    properties: variant(
+                        read(
+                            with_extension(&full_path, ".properties"),
+                            Format::Properties,
+                        )
+                        .await,
+                    )?,
                     ..Default::default()
-                })?)
-            } else {
-                Err(Error::Include {
-                    path: String::from(url),
                 })
             }
-        } else {
-            Err(Error::Include {
+            ft => Ok(FileRead::from_file_type(
+                ft,
+                read(full_path, ft.format()).await?,
+            )),
+        }
+    }
+
+    /// Document included from `url`, read from the file of a `file://` URL, or else fetched
+    pub(crate) fn load_url(&self, url: &str) -> Result<HoconInternal> {
+        if !self.url_filter.allows(url) {
+            return Err(Error::UrlNotAllowed {
+                url: String::from(url),
+            });
+        }
+        #[cfg(feature = "url-support")]
+        let include_error = || Error::Include {
+            path: String::from(url),
+        };
+        #[cfg(feature = "url-support")]
+        match reqwest::Url::parse(url) {
+            Ok(parsed_url) if parsed_url.scheme() == "file" => {
+                let path = parsed_url.to_file_path().map_err(|_| include_error())?;
+                let include_config = self.included_from().with_file(path);
+                let s = include_config.read_file()?;
+                return include_config
+                    .parse_source(include_config.read_sources(), s)
+                    .map_err(|err| match err {
+                        Error::ResourceLimit { .. } => err,
+                        _ => include_error(),
+                    });
+            }
+            Ok(_) if self.external_url => {}
+            _ => return Err(include_error()),
+        }
+        #[cfg(all(feature = "url-support-ureq", not(feature = "url-support")))]
+        if !self.external_url {
+            return Err(Error::Include {
                 path: String::from(url),
-            })
+            });
+        }
+        let body = self.fetch(url)?;
+        self.parse_source(
+            vec![Source::Url(String::from(url))],
+            FileRead {
+                hocon: Some(body),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Body of the document at `url`, if it was not fetched before parsing, fetched by the
+    /// registered fetcher, or else by `reqwest` or `ureq`
+    fn fetch(&self, url: &str) -> Result<String> {
+        if let Some(body) = self.prefetched.borrow().get(url).cloned() {
+            return body;
+        }
+        // fetching would block the asynchronous runtime
+        #[cfg(feature = "async")]
+        if self.async_loading {
+            return Err(Error::Include {
+                path: String::from(url),
+            });
+        }
+        match self.url_fetcher() {
+            Some(fetcher) => fetch_limited(&*fetcher, url, self.max_include_size),
+            None => Err(Error::DisabledExternalUrl),
         }
     }
+
+    /// Fetcher of the included URLs, the registered one, or else `reqwest` or `ureq` with the
+    /// limits of the loader
+    fn url_fetcher(&self) -> Option<Arc<dyn HttpFetcher + Send + Sync>> {
+        match self.http_fetcher.as_ref() {
+            Some(fetcher) => Some(Arc::clone(&fetcher.0)),
+            #[cfg(feature = "url-support")]
+            None => Some(Arc::new(self.reqwest_fetcher())),
+            #[cfg(all(feature = "url-support-ureq", not(feature = "url-support")))]
+            None => Some(Arc::new(UreqFetcher {
+                max_size: self.max_include_size,
+                timeout: self.url_timeout,
+                max_redirects: None,
+                filter: self.url_filter.clone(),
+            })),
+            #[cfg(not(any(feature = "url-support", feature = "url-support-ureq")))]
+            None => None,
+        }
+    }
+
+    /// Fetcher used without a registered one, with the limits of the loader
+    #[cfg(feature = "url-support")]
+    fn reqwest_fetcher(&self) -> ReqwestFetcher {
+        ReqwestFetcher {
+            max_size: self.max_include_size,
+            timeout: self.url_timeout,
+            requests: self
+                .url_requests
+                .clone()
+                .with_env_proxies(|name| self.read_env(name)),
+            filter: self.url_filter.clone(),
+            client: self.reqwest_client.clone(),
+        }
+    }
+}
+
+/// Path of the variant of a file with `extension` appended, so that `my.app` is read from
+/// `my.app.conf`
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+/// Read at most `max_size` bytes of the document `name` in `encoding`, failing if there are
+/// more
+pub(crate) fn read_limited(
+    reader: impl Read,
+    max_size: u64,
+    name: &str,
+    encoding: Encoding,
+) -> Result<String> {
+    let mut contents = vec![];
+    reader.take(max_size + 1).read_to_end(&mut contents)?;
+    if contents.len() as u64 > max_size {
+        return Err(too_large(name, max_size));
+    }
+    crate::encoding::decode(contents, name, encoding)
+}
+
+/// Body of the document at `url` fetched by `fetcher`, failing if it is larger than
+/// `max_size` bytes
+fn fetch_limited(fetcher: &dyn HttpFetcher, url: &str, max_size: Option<u64>) -> Result<String> {
+    within_size(url, fetcher.fetch(url)?, max_size)
+}
+
+/// `body` of the document at `url`, failing if it is larger than `max_size` bytes
+fn within_size(url: &str, body: String, max_size: Option<u64>) -> Result<String> {
+    match max_size {
+        Some(max_size) if body.len() as u64 > max_size => Err(too_large(url, max_size)),
+        _ => Ok(body),
+    }
+}
+
+/// `sources` with the sources read for the URLs `included` in place of their placeholders
+fn with_fetched_sources(
+    sources: Vec<LoadedSource>,
+    included: &HashMap<String, IncludeCacheEntry>,
+) -> Vec<LoadedSource> {
+    sources
+        .into_iter()
+        .flat_map(|source| {
+            let fetched = match &source.source {
+                Source::Url(marker) => included.get(marker),
+                _ => None,
+            };
+            match fetched {
+                Some((_, fetched)) => with_fetched_sources(fetched.clone(), included),
+                None => vec![LoadedSource {
+                    source: source.source,
+                    includes: with_fetched_sources(source.includes, included),
+                }],
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn too_large(name: &str, max_size: u64) -> Error {
+    Error::ResourceLimit {
+        limit: format!("'{name}' larger than {max_size} bytes"),
+    }
 }
@@ -6,11 +6,11 @@ use nom::IResult;
 use nom::Parser;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
+use nom::bytes::complete::take_till;
 use nom::bytes::complete::take_until;
 use nom::character::complete::char;
 use nom::character::complete::digit1;
 use nom::character::complete::multispace0;
-use nom::character::complete::newline;
 use nom::character::complete::none_of;
 use nom::character::complete::one_of;
 use nom::combinator::not;
@@ -25,6 +25,7 @@ use nom::multi::many1;
 use nom::multi::separated_list0;
 use nom::sequence::delimited;
 use nom::sequence::pair;
+use nom::sequence::preceded;
 
 use crate::HoconLoaderConfig;
 use crate::Result;
@@ -33,6 +34,7 @@ use crate::internals::Hash;
 use crate::internals::HoconInternal;
 use crate::internals::HoconValue;
 use crate::internals::Include;
+use crate::internals::SubstitutionLocation;
 use crate::internals::unescape;
 
 /// Root parser - the main entry point for parsing HOCON documents.
@@ -87,6 +89,15 @@ fn space(input: &str) -> IResult<&str, ()> {
     Ok((remaining, ()))
 }
 
+/// A line ending, `\n`, `\r\n` or a lone `\r`
+fn newline(input: &str) -> IResult<&str, char> {
+    alt((
+        char('\n'),
+        preceded(char('\r'), opt(char('\n'))).map(|_| '\n'),
+    ))
+    .parse(input)
+}
+
 fn sp<'a, O, F>(mut f: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
 where
     F: Parser<&'a str, Output = O, Error = NomError<&'a str>>,
@@ -119,7 +130,8 @@ fn space_then_comment(input: &str) -> IResult<&str, ()> {
 
 fn comment(input: &str) -> IResult<&str, ()> {
     let (remaining, _) = alt((tag("//"), tag("#"))).parse(input)?;
-    let (remaining, _) = take_until("\n").parse(remaining)?;
+    // a comment on the last line ends with the document
+    let (remaining, _) = take_till(|c| c == '\n' || c == '\r').parse(remaining)?;
     Ok((remaining, ()))
 }
 
@@ -193,7 +205,7 @@ where
 fn string(input: &str) -> IResult<&str, Cow<'_, str>> {
     fn escaped_char(input: &str) -> IResult<&str, &str> {
         alt((
-            recognize(none_of("\\\"\n")),
+            recognize(none_of("\\\"\n\r")),
             recognize(pair(char('\\'), one_of(r#""\/bfnrtu"#))),
             recognize((
                 tag("\\u"),
@@ -210,6 +222,15 @@ fn string(input: &str) -> IResult<&str, Cow<'_, str>> {
     Ok((remaining, unescape(content)))
 }
 
+/// Content of a multiline string, with its line endings as `\n`
+fn multiline_content(content: &str) -> Rc<str> {
+    if content.contains('\r') {
+        Rc::from(content.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Rc::from(content)
+    }
+}
+
 fn multiline_string(input: &str) -> IResult<&str, &str> {
     // Multiline strings start with """ and end with """
     // According to HOCON spec, if there are more than 3 consecutive closing quotes,
@@ -272,6 +293,7 @@ fn unquoted_string(input: &str) -> IResult<&str, &str> {
                 | '\\'
                 | '\t'
                 | '\n'
+                | '\r'
         )
     }
 
@@ -300,46 +322,197 @@ fn unquoted_string(input: &str) -> IResult<&str, &str> {
 // Substitution parsers
 // ============================================================================
 
+/// Path of a substitution, where elements of arrays can also be selected with `[index]`,
+/// like `servers[0].host`
+fn substitution_path(input: &str) -> IResult<&str, HoconValue> {
+    let (input, first) = hocon_value(input)?;
+    let (input, indexed) = many0((
+        delimited(char('['), digit1, char(']')),
+        opt(preceded(char('.'), hocon_value)),
+    ))
+    .parse(input)?;
+    if indexed.is_empty() {
+        return Ok((input, first));
+    }
+    let mut path = vec![first];
+    for (index, rest) in indexed {
+        path.push(HoconValue::String(Rc::from(index)));
+        path.extend(rest);
+    }
+    Ok((input, HoconValue::Concat(path)))
+}
+
 fn path_substitution(input: &str) -> IResult<&str, HoconValue> {
     let (input, _) = alt((tag("${?"), tag("${"))).parse(input)?;
-    let (input, val) = hocon_value(input)?;
+    let (input, val) = substitution_path(input)?;
     let (input, _) = char('}').parse(input)?;
     Ok((input, val))
 }
 
 fn optional_path_substitution(input: &str) -> IResult<&str, HoconValue> {
     let (input, _) = tag("${?").parse(input)?;
-    let (input, val) = hocon_value(input)?;
+    let (input, val) = substitution_path(input)?;
     let (input, _) = char('}').parse(input)?;
     Ok((input, val))
 }
 
+fn any_path_substitution(input: &str) -> IResult<&str, HoconValue> {
+    let location = SubstitutionLocation::parsed(input);
+    alt((
+        optional_path_substitution.map(|p| HoconValue::PathSubstitution {
+            target: Box::new(p),
+            optional: true,
+            original: None,
+            default: None,
+            location: location.clone(),
+        }),
+        path_substitution.map(|p| HoconValue::PathSubstitution {
+            target: Box::new(p),
+            optional: false,
+            original: None,
+            default: None,
+            location: location.clone(),
+        }),
+    ))
+    .parse(input)
+}
+
+/// `${path:-default}` or `${?path:-default}`, only accepted when enabled with
+/// `HoconLoader::substitution_defaults`. An empty default, as in `${?path:-}`, is an empty
+/// string
+fn substitution_with_default(input: &str) -> IResult<&str, (HoconValue, HoconValue)> {
+    let (input, _) = alt((tag("${?"), tag("${"))).parse(input)?;
+    let (input, path) = substitution_path(input)?;
+    let (input, _) = tag(":-").parse(input)?;
+    let (input, _) = space(input)?;
+    let (input, default) = opt(alt((
+        string.map(|s: Cow<str>| HoconValue::String(Rc::from(s.as_ref()))),
+        integer.map(HoconValue::Integer),
+        float.map(HoconValue::Real),
+        boolean.map(HoconValue::Boolean),
+        unquoted_string.map(|s| HoconValue::UnquotedString(Rc::from(s.trim()))),
+    )))
+    .map(|default| default.unwrap_or_else(|| HoconValue::String(Rc::from(""))))
+    .parse(input)?;
+    let (input, _) = space(input)?;
+    let (input, _) = char('}').parse(input)?;
+    Ok((input, (path, default)))
+}
+
+/// `${file:path}` or `${?file:path}`, only accepted when enabled with
+/// `HoconLoader::file_substitutions`
+fn file_substitution(input: &str) -> IResult<&str, (&str, bool)> {
+    let (input, optional) = alt((
+        nom_value(true, tag("${?file:")),
+        nom_value(false, tag("${file:")),
+    ))
+    .parse(input)?;
+    let (input, path) = take_until("}").parse(input)?;
+    let (input, _) = char('}').parse(input)?;
+    Ok((input, (path.trim(), optional)))
+}
+
 // ============================================================================
 // Value parsers
 // ============================================================================
 
 fn single_value(input: &str) -> IResult<&str, HoconValue> {
     alt((
-        multiline_string.map(|s| HoconValue::String(Rc::from(s))),
+        multiline_string.map(|s| HoconValue::String(multiline_content(s))),
         string.map(|s: Cow<str>| HoconValue::String(Rc::from(s.as_ref()))),
         integer.map(HoconValue::Integer),
         float.map(HoconValue::Real),
         boolean.map(HoconValue::Boolean),
-        optional_path_substitution.map(|p| HoconValue::PathSubstitution {
-            target: Box::new(p),
-            optional: true,
+        file_substitution.map(|(path, optional)| HoconValue::FileSubstitution {
+            path: Rc::from(path),
+            optional,
             original: None,
         }),
-        path_substitution.map(|p| HoconValue::PathSubstitution {
+        // the default is kept as the value to use if the substitution is not found
+        substitution_with_default.map(|(p, default)| HoconValue::PathSubstitution {
             target: Box::new(p),
-            optional: false,
+            optional: true,
             original: None,
+            default: Some(Box::new(default)),
+            location: SubstitutionLocation::parsed(input),
         }),
+        any_path_substitution,
         unquoted_string.map(|s| HoconValue::UnquotedString(Rc::from(s))),
     ))
     .parse(input)
 }
 
+/// Substitutions written inside quoted strings, like `"https://${host}/api"`, only
+/// interpolated when enabled with `HoconLoader::string_interpolation`. A `${` that does not
+/// start a valid substitution is kept as is. Substitutions are located at `input`, the start
+/// of the value
+fn interpolate(value: HoconValue, config: &HoconLoaderConfig, input: &str) -> HoconValue {
+    match value {
+        HoconValue::String(s) if s.contains("${") => {
+            let mut values = vec![];
+            let mut rest = s.as_ref();
+            while let Some(start) = rest.find("${") {
+                let substitution = alt((
+                    substitution_with_default.map(|(p, default)| HoconValue::PathSubstitution {
+                        target: Box::new(p),
+                        optional: true,
+                        original: None,
+                        default: Some(Box::new(default)),
+                        location: None,
+                    }),
+                    any_path_substitution,
+                ))
+                .parse(&rest[start..]);
+                match substitution {
+                    Ok((remaining, subst)) if subst.extensions_enabled(config) => {
+                        if start > 0 {
+                            values.push(HoconValue::String(Rc::from(&rest[..start])));
+                        }
+                        values.push(HoconValue::InterpolatedSubstitution(Box::new(
+                            match subst {
+                                HoconValue::PathSubstitution {
+                                    target,
+                                    optional,
+                                    original,
+                                    default,
+                                    ..
+                                } => HoconValue::PathSubstitution {
+                                    target,
+                                    optional,
+                                    original,
+                                    default,
+                                    location: SubstitutionLocation::parsed(input),
+                                },
+                                subst => subst,
+                            },
+                        )));
+                        rest = remaining;
+                    }
+                    _ => {
+                        values.push(HoconValue::String(Rc::from(&rest[..start + 2])));
+                        rest = &rest[start + 2..];
+                    }
+                }
+            }
+            if !rest.is_empty() {
+                values.push(HoconValue::String(Rc::from(rest)));
+            }
+            // kept as a concatenation, so the value is still a string
+            HoconValue::Concat(values)
+        }
+        HoconValue::Concat(values) => HoconValue::Concat(
+            values
+                .into_iter()
+                .flat_map(|value| match interpolate(value, config, input) {
+                    HoconValue::Concat(values) => values,
+                    value => vec![value],
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
 fn hocon_value(input: &str) -> IResult<&str, HoconValue> {
     let (input, _) = possible_comment(input)?;
     let (input, first_value) = single_value(input)?;
@@ -416,6 +589,26 @@ fn colon_or_equals(input: &str) -> IResult<&str, char> {
 // Include parser
 // ============================================================================
 
+/// Target of an include concatenating strings and substitutions, like
+/// `${CONF_DIR}"/extra.conf"`, only accepted when enabled with
+/// `HoconLoader::include_substitutions`
+fn substituted_include_target(url: bool, input: &str) -> IResult<&str, Include<'_>> {
+    let (remaining, parts) = many1(alt((
+        string.map(|s: Cow<str>| HoconValue::String(Rc::from(s.as_ref()))),
+        any_path_substitution,
+    )))
+    .parse(input)?;
+    let text = &input[..input.len() - remaining.len()];
+    Ok((
+        remaining,
+        Include::Substituted {
+            text: Cow::Borrowed(text),
+            parts,
+            url,
+        },
+    ))
+}
+
 fn include_parser(input: &str) -> IResult<&str, Include<'_>> {
     let (input, _) = tag("include ").parse(input)?;
     let (input, _) = ws(many0(newline)).parse(input)?;
@@ -423,6 +616,24 @@ fn include_parser(input: &str) -> IResult<&str, Include<'_>> {
     // Parse the include target with surrounding spaces
     let (input, _) = space(input)?;
     let (input, included) = alt((
+        |i| {
+            let (i, _) = tag("required(").parse(i)?;
+            let (i, _) = space(i)?;
+            let (i, included) = include_target(i)?;
+            let (i, _) = space(i)?;
+            let (i, _) = tag(")").parse(i)?;
+            Ok((i, Include::Required(Box::new(included))))
+        },
+        include_target,
+    ))
+    .parse(input)?;
+    let (input, _) = space(input)?;
+
+    Ok((input, included))
+}
+
+fn include_target(input: &str) -> IResult<&str, Include<'_>> {
+    alt((
         |i| {
             let (i, _) = tag("file(").parse(i)?;
             let (i, file_name) = string(i)?;
@@ -435,12 +646,34 @@ fn include_parser(input: &str) -> IResult<&str, Include<'_>> {
             let (i, _) = tag(")").parse(i)?;
             Ok((i, Include::Url(url)))
         },
+        |i| {
+            let (i, _) = tag("env(").parse(i)?;
+            let (i, name) = string(i)?;
+            let (i, _) = tag(")").parse(i)?;
+            Ok((i, Include::Env(name)))
+        },
+        |i| {
+            let (i, _) = tag("classpath(").parse(i)?;
+            let (i, resource) = string(i)?;
+            let (i, _) = tag(")").parse(i)?;
+            Ok((i, Include::Classpath(resource)))
+        },
         string.map(Include::File),
+        |i| {
+            let (i, _) = tag("file(").parse(i)?;
+            let (i, included) = substituted_include_target(false, i)?;
+            let (i, _) = tag(")").parse(i)?;
+            Ok((i, included))
+        },
+        |i| {
+            let (i, _) = tag("url(").parse(i)?;
+            let (i, included) = substituted_include_target(true, i)?;
+            let (i, _) = tag(")").parse(i)?;
+            Ok((i, included))
+        },
+        |i| substituted_include_target(false, i),
     ))
-    .parse(input)?;
-    let (input, _) = space(input)?;
-
-    Ok((input, included))
+    .parse(input)
 }
 
 // ============================================================================
@@ -613,9 +846,22 @@ fn hashes<'a>(
     config: &'a HoconLoaderConfig,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, Result<Hash>> {
     move |input| {
+        let location = SubstitutionLocation::parsed(input);
         let (input, maybe_substitution) = opt(path_substitution).parse(input)?;
         let (input, first_hash) = hash(config)(input)?;
-        let (input, remaining_hashes) = many0(hash(config)).parse(input)?;
+        let (input, remaining_hashes) = many0(alt((
+            hash(config),
+            // the key is only used to keep the substitution apart from the other fields
+            sp(any_path_substitution).map(|subst| {
+                Ok(vec![(
+                    vec![HoconValue::String(Rc::from(
+                        uuid::Uuid::new_v4().hyphenated().to_string(),
+                    ))],
+                    HoconValue::ConcatSubstitution(Box::new(subst)),
+                )])
+            }),
+        )))
+        .parse(input)?;
 
         let result = match (maybe_substitution, remaining_hashes.is_empty()) {
             (None, true) => first_hash,
@@ -633,6 +879,8 @@ fn hashes<'a>(
                         target: Box::new(subst),
                         optional: false,
                         original: None,
+                        default: None,
+                        location,
                     },
                 )];
                 match (first_hash, helper::extract_result(remaining_hashes)) {
@@ -690,7 +938,15 @@ fn arrays<'a>(
     move |input| {
         let (input, maybe_substitution) = opt(path_substitution).parse(input)?;
         let (input, first_array) = array(config)(input)?;
-        let (input, remaining_arrays) = many0(array(config)).parse(input)?;
+        let (input, remaining_arrays) = many0(alt((
+            array(config),
+            sp(any_path_substitution).map(|subst| {
+                Ok(vec![HoconInternal::from_value(
+                    HoconValue::ConcatSubstitution(Box::new(subst)),
+                )])
+            }),
+        )))
+        .parse(input)?;
 
         let result = match (maybe_substitution, remaining_arrays.is_empty()) {
             (None, true) => first_array,
@@ -751,6 +1007,14 @@ fn wrapper<'a>(
 
         // Try value
         let (remaining, val) = hocon_value(input)?;
+        if !val.extensions_enabled(config) {
+            return Err(NomErr::Error(NomError::new(input, ErrorKind::Verify)));
+        }
+        let val = if config.string_interpolation {
+            interpolate(val, config, input)
+        } else {
+            val
+        };
         Ok((remaining, Ok(HoconInternal::from_value(val))))
     }
 }
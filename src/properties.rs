@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+
+use std::fmt;
+use std::io;
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+use crate::render::{FmtOutput, IoOutput, Output};
+
+/// How Java properties documents are parsed, set with
+/// [`HoconLoader::properties_options`](struct.HoconLoader.html#method.properties_options).
+/// By default they follow the rules of `java.util.Properties`
+///
+/// ```rust
+/// # use hocon::{Hocon, HoconLoader, Error, PropertiesOptions};
+/// # fn main() -> Result<(), Error> {
+/// let doc = HoconLoader::new()
+///     .properties_options(PropertiesOptions::new().separators("=").no_unicode_escapes())
+///     .load_properties_str("endpoint = http://localhost:8080\npattern = \\u00e9")?
+///     .hocon()?;
+///
+/// assert_eq!(doc["endpoint"], Hocon::String(String::from("http://localhost:8080")));
+/// assert_eq!(doc["pattern"], Hocon::String(String::from("\\u00e9")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertiesOptions {
+    separators: Vec<char>,
+    whitespace_separator: bool,
+    unicode_escapes: bool,
+}
+
+impl Default for PropertiesOptions {
+    fn default() -> Self {
+        Self {
+            separators: vec!['=', ':'],
+            whitespace_separator: true,
+            unicode_escapes: true,
+        }
+    }
+}
+
+impl PropertiesOptions {
+    /// Parse as `java.util.Properties`: a key ends at the first `=`, `:` or whitespace that is
+    /// not escaped, and `\uXXXX` is the character of code point `XXXX`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// End keys at the characters of `separators` instead of `=` and `:`, for example only at
+    /// `=` for values like URLs in the keys
+    pub fn separators(self, separators: &str) -> Self {
+        Self {
+            separators: separators.chars().collect(),
+            ..self
+        }
+    }
+
+    /// Do not end keys at whitespace, so `my key = value` is the value of `my key`. The
+    /// whitespace around the separator is still skipped
+    pub fn no_whitespace_separator(self) -> Self {
+        Self {
+            whitespace_separator: false,
+            ..self
+        }
+    }
+
+    /// Keep `\uXXXX` as is in keys and values, for documents written by tools that do not
+    /// escape characters
+    pub fn no_unicode_escapes(self) -> Self {
+        Self {
+            unicode_escapes: false,
+            ..self
+        }
+    }
+
+    /// Values of the keys of properties document `text`, the last one for repeated keys
+    pub(crate) fn parse(&self, text: &str) -> Result<HashMap<String, String>> {
+        let mut values = HashMap::new();
+        let mut lines = text.lines().flat_map(|line| line.split('\r'));
+        while let Some(line) = lines.next() {
+            let line = line.trim_start_matches(is_whitespace);
+            if line.is_empty() || line.starts_with(['#', '!']) {
+                continue;
+            }
+            // a line ending with an odd number of backslashes goes on on the next line
+            let mut logical = String::from(line);
+            while ends_with_continuation(&logical) {
+                logical.pop();
+                match lines.next() {
+                    Some(next) => logical.push_str(next.trim_start_matches(is_whitespace)),
+                    None => break,
+                }
+            }
+            let (key, value) = self.parse_line(&logical)?;
+            values.insert(key, value);
+        }
+        Ok(values)
+    }
+
+    fn parse_line(&self, line: &str) -> Result<(String, String)> {
+        let mut chars = line.chars().peekable();
+        let mut key = String::new();
+        // length of the key without the whitespace before the separator
+        let mut key_len = 0;
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                self.unescape(&mut chars, &mut key)?;
+                key_len = key.len();
+            } else if self.separators.contains(&c) {
+                break;
+            } else if is_whitespace(c) {
+                if self.whitespace_separator {
+                    // whitespace can be followed by a separator
+                    while chars.next_if(|c| is_whitespace(*c)).is_some() {}
+                    chars.next_if(|c| self.separators.contains(c));
+                    break;
+                }
+                key.push(c);
+            } else {
+                key.push(c);
+                key_len = key.len();
+            }
+        }
+        key.truncate(key_len);
+
+        while chars.next_if(|c| is_whitespace(*c)).is_some() {}
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                self.unescape(&mut chars, &mut value)?;
+            } else {
+                value.push(c);
+            }
+        }
+        Ok((key, value))
+    }
+
+    /// Push the character escaped after a backslash
+    fn unescape(&self, chars: &mut impl Iterator<Item = char>, to: &mut String) -> Result<()> {
+        match chars.next() {
+            Some('t') => to.push('\t'),
+            Some('n') => to.push('\n'),
+            Some('r') => to.push('\r'),
+            Some('f') => to.push('\u{c}'),
+            Some('u') if self.unicode_escapes => {
+                let first = unicode_unit(chars)?;
+                let units = if (0xd800..0xdc00).contains(&first) {
+                    // characters out of the basic plane are escaped as a surrogate pair
+                    if chars.next() != Some('\\') || chars.next() != Some('u') {
+                        return Err(Error::Parse);
+                    }
+                    vec![first, unicode_unit(chars)?]
+                } else {
+                    vec![first]
+                };
+                for c in char::decode_utf16(units) {
+                    to.push(c.map_err(|_| Error::Parse)?);
+                }
+            }
+            Some('u') => to.push_str("\\u"),
+            Some(c) => to.push(c),
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl Hocon {
+    /// Convert to a Java properties document, with a line for each value at its path: the keys
+    /// of objects are joined with `.`, and the elements of arrays are at their index, like
+    /// `hosts.0`.
+    ///
+    /// * keys and values are escaped, with the characters that are not ASCII written as
+    ///   `\uXXXX`, so the document can be read as ISO-8859-1 or UTF-8
+    /// * properties have no null or empty values:
+    ///   [`Hocon::Null`](enum.Hocon.html#variant.Null),
+    ///   [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue), empty objects and empty arrays
+    ///   are left out
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ db { url: "jdbc:h2:mem", hosts: [a, b] }, city: Zürich }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(
+    ///     doc.to_properties_string(),
+    ///     "db.url=jdbc\\:h2\\:mem\ndb.hosts.0=a\ndb.hosts.1=b\ncity=Z\\u00FCrich\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_properties_string(&self) -> String {
+        let mut properties = String::new();
+        self.write_properties_fmt(&mut properties)
+            .expect("properties can be written to a string");
+        properties
+    }
+
+    /// Write as a Java properties document to `writer`, as with
+    /// [`to_properties_string`](#method.to_properties_string), a line at a time
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`](enum.Error.html#variant.Io) if writing to `writer` fails
+    pub fn write_properties(&self, writer: &mut impl io::Write) -> Result<()> {
+        self.properties_lines(&mut vec![], &mut IoOutput(writer))
+    }
+
+    /// Write as a Java properties document to `writer`, as with
+    /// [`write_properties`](#method.write_properties), for writers of text like a `String`
+    /// or a `Formatter`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`write_properties`](#method.write_properties)
+    pub fn write_properties_fmt(&self, writer: &mut impl fmt::Write) -> Result<()> {
+        self.properties_lines(&mut vec![], &mut FmtOutput(writer))
+    }
+
+    fn properties_lines(&self, path: &mut Vec<String>, output: &mut dyn Output) -> Result<()> {
+        let mut write_child = |key: String, value: &Hocon, output: &mut dyn Output| {
+            path.push(key);
+            let written = value.properties_lines(path, output);
+            path.pop();
+            written
+        };
+        match self {
+            Hocon::Hash(hash) => {
+                for (key, value) in hash {
+                    write_child(key.clone(), value, output)?;
+                }
+            }
+            Hocon::Array(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    write_child(index.to_string(), value, output)?;
+                }
+            }
+            value => {
+                if let (false, Some(value)) = (path.is_empty(), value.as_string()) {
+                    let mut line = String::new();
+                    escape_property(&path.join("."), true, &mut line);
+                    line.push('=');
+                    escape_property(&value, false, &mut line);
+                    line.push('\n');
+                    output.write(&line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `text` escaped as `java.util.Properties` does, with all its spaces escaped in a key
+/// and only its leading ones in a value
+fn escape_property(text: &str, key: bool, to: &mut String) {
+    for (index, c) in text.chars().enumerate() {
+        match c {
+            ' ' if key || index == 0 => to.push_str("\\ "),
+            '\\' | '=' | ':' | '#' | '!' => {
+                to.push('\\');
+                to.push(c);
+            }
+            '\t' => to.push_str("\\t"),
+            '\n' => to.push_str("\\n"),
+            '\r' => to.push_str("\\r"),
+            '\u{c}' => to.push_str("\\f"),
+            ' '..='~' => to.push(c),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    to.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+        }
+    }
+}
+
+/// UTF-16 code unit of the 4 hexadecimal digits of a `\uXXXX` escape
+fn unicode_unit(chars: &mut impl Iterator<Item = char>) -> Result<u16> {
+    let digits: String = chars.take(4).collect();
+    u16::from_str_radix(&digits, 16)
+        .ok()
+        .filter(|_| digits.len() == 4 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or(Error::Parse)
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\u{c}')
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    let backslashes = line.chars().rev().take_while(|c| *c == '\\').count();
+    !backslashes.is_multiple_of(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(options: &PropertiesOptions, text: &str) -> Vec<(String, String)> {
+        let mut values: Vec<_> = options
+            .parse(text)
+            .expect("during test")
+            .into_iter()
+            .collect();
+        values.sort();
+        values
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (String::from(*key), String::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn can_parse_as_java() {
+        assert_eq!(
+            parse(
+                &PropertiesOptions::new(),
+                "# comment\n! comment\na=1\n  b : 2\r\nc 3\rd\ne = \\\n    multi\\\\\nf\\ g\\=h = \\u00e9\\t\n"
+            ),
+            pairs(&[
+                ("a", "1"),
+                ("b", "2"),
+                ("c", "3"),
+                ("d", ""),
+                ("e", "multi\\"),
+                ("f g=h", "é\t"),
+            ])
+        );
+    }
+
+    #[test]
+    fn can_parse_with_options() {
+        assert_eq!(
+            parse(
+                &PropertiesOptions::new().separators("="),
+                "url:port=http://localhost:8080\nkey value=1"
+            ),
+            pairs(&[("key", "value=1"), ("url:port", "http://localhost:8080")])
+        );
+        assert_eq!(
+            parse(
+                &PropertiesOptions::new().no_whitespace_separator(),
+                "my key = my value\nother:1"
+            ),
+            pairs(&[("my key", "my value"), ("other", "1")])
+        );
+        assert_eq!(
+            parse(
+                &PropertiesOptions::new().no_unicode_escapes(),
+                "a=\\u00e9\\t"
+            ),
+            pairs(&[("a", "\\u00e9\t")])
+        );
+    }
+
+    #[test]
+    fn can_write_properties() {
+        let mut hocon = crate::hocon!({
+            a { b = 1, c = [true, 2.5], d = null, e = {}, f = [] },
+            "key with spaces" = " leading and trailing ",
+            g = "tab\tnew\nline#!",
+            h = "é𝄞",
+        });
+        hocon
+            .set("i", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        let properties = hocon.to_properties_string();
+        assert_eq!(
+            properties,
+            "a.b=1\na.c.0=true\na.c.1=2.5\nkey\\ with\\ spaces=\\ leading and trailing \n\
+             g=tab\\tnew\\nline\\#\\!\nh=\\u00E9\\uD834\\uDD1E\n"
+        );
+        // and it is read back
+        assert_eq!(
+            parse(&PropertiesOptions::new(), &properties),
+            pairs(&[
+                ("a.b", "1"),
+                ("a.c.0", "true"),
+                ("a.c.1", "2.5"),
+                ("g", "tab\tnew\nline#!"),
+                ("h", "é𝄞"),
+                ("key with spaces", " leading and trailing "),
+            ])
+        );
+        assert_eq!(Hocon::Integer(1).to_properties_string(), "");
+    }
+
+    #[test]
+    fn can_not_parse_invalid_unicode_escapes() {
+        assert_eq!(
+            PropertiesOptions::new().parse("a=\\u00g9"),
+            Err(Error::Parse)
+        );
+        assert_eq!(PropertiesOptions::new().parse("a=\\u00"), Err(Error::Parse));
+        assert_eq!(
+            PropertiesOptions::new().parse("a=\\uD834"),
+            Err(Error::Parse)
+        );
+    }
+}
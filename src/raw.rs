@@ -0,0 +1,314 @@
+//! Parse-only access to HOCON documents
+//!
+//! [`parse`](fn.parse.html) returns the document as it was written, before objects are
+//! merged and substitutions are resolved. Each value is listed with the path it is
+//! assigned to, in document order, so tools can inspect substitutions, includes and
+//! concatenations, and implement their own resolution strategy.
+//!
+//! ```rust
+//! use hocon::raw::{self, RawValue};
+//! # use hocon::Error;
+//!
+//! # fn main() -> Result<(), Error> {
+//! let doc = raw::parse(r#"{ a: 5, b: ${a} }"#)?;
+//!
+//! let substitutions = doc.substitutions();
+//! assert_eq!(substitutions[0].path, vec![String::from("b")]);
+//! assert_eq!(
+//!     substitutions[0].value,
+//!     RawValue::Substitution { path: String::from("a"), optional: false }
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use crate::Error;
+use crate::Hocon;
+use crate::HoconLoader;
+use crate::Result;
+use crate::internals::HoconValue;
+
+/// Parse a string containing an HOCON document without resolving it
+///
+/// # Errors
+///
+/// * [`Error::Parse`](../enum.Error.html#variant.Parse) if the document is invalid
+pub fn parse(s: &str) -> Result<RawDocument> {
+    Ok(RawDocument {
+        loader: HoconLoader::new().load_str(s)?,
+    })
+}
+
+/// Parse an HOCON file without resolving it. Included files are read and their values
+/// are marked as [`RawValue::Included`](enum.RawValue.html#variant.Included)
+///
+/// # Errors
+///
+/// * [`Error::File`](../enum.Error.html#variant.File) if there was an error reading the
+///   file content
+/// * [`Error::Parse`](../enum.Error.html#variant.Parse) if the document is invalid
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<RawDocument> {
+    Ok(RawDocument {
+        loader: HoconLoader::new().load_file(path)?,
+    })
+}
+
+/// An HOCON document that has been parsed but not resolved
+#[derive(Debug, Clone)]
+pub struct RawDocument {
+    loader: HoconLoader,
+}
+
+/// A value of a [`RawDocument`](struct.RawDocument.html), with the path it is assigned to
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawEntry {
+    /// Keys leading to the value. Array elements are identified by their index
+    pub path: Vec<String>,
+    /// The value as written in the document
+    pub value: RawValue,
+}
+
+/// An unresolved value
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawValue {
+    /// A floating value
+    Real(f64),
+    /// An integer value
+    Integer(i64),
+    /// A quoted string
+    String(String),
+    /// An unquoted string. Whitespace is kept as it can be significant in a concatenation
+    Unquoted(String),
+    /// A boolean
+    Boolean(bool),
+    /// A null value
+    Null,
+    /// An empty object
+    EmptyObject,
+    /// An empty array
+    EmptyArray,
+    /// A substitution like `${path}` or `${?path}`
+    Substitution {
+        /// Path of the value to substitute
+        path: String,
+        /// `true` for an optional substitution `${?path}`
+        optional: bool,
+    },
+    /// A substitution with the content of a file, like `${file:/run/secrets/password}`
+    FileSubstitution {
+        /// Path of the file
+        path: String,
+        /// `true` for an optional substitution `${?file:/run/secrets/password}`
+        optional: bool,
+    },
+    /// Values to concatenate
+    Concatenation(Vec<RawValue>),
+    /// A value appended to an array with `+=`
+    AppendToArray(Box<RawValue>),
+    /// A value that was read from an included document
+    Included(Box<RawValue>),
+    /// A value that could not be parsed, or an include that could not be loaded
+    Bad(Error),
+}
+
+impl RawDocument {
+    /// All values of the document, in document order
+    pub fn entries(&self) -> Vec<RawEntry> {
+        self.loader
+            .layers
+            .iter()
+            .flat_map(|layer| layer.internal.internal.iter())
+            .filter(|(path, _)| !path.is_empty())
+            .map(|(path, value)| {
+                let mut path = path
+                    .iter()
+                    .flat_map(HoconValue::to_path)
+                    .map(HoconValue::key_string)
+                    .collect::<Vec<_>>();
+                if let HoconValue::PathSubstitutionInParent(_) = value {
+                    // the substitution applies to the array itself, not to its first element
+                    path.pop();
+                }
+                RawEntry {
+                    path,
+                    value: RawValue::from(value),
+                }
+            })
+            .collect()
+    }
+
+    /// Values that contain a substitution
+    pub fn substitutions(&self) -> Vec<RawEntry> {
+        self.entries_matching(RawValue::has_substitution)
+    }
+
+    /// Values coming from included documents, and includes that could not be loaded
+    pub fn includes(&self) -> Vec<RawEntry> {
+        self.entries_matching(|value| {
+            matches!(
+                value,
+                RawValue::Included(_)
+                    | RawValue::Bad(Error::Include { .. })
+                    | RawValue::Bad(Error::IncludeNotAllowedFromStr)
+                    | RawValue::Bad(Error::TooManyIncludes)
+                    | RawValue::Bad(Error::DisabledExternalUrl)
+            )
+        })
+    }
+
+    /// Values that are a concatenation of several values
+    pub fn concatenations(&self) -> Vec<RawEntry> {
+        self.entries_matching(RawValue::has_concatenation)
+    }
+
+    /// Resolve the document with the default [`HoconLoader`](../struct.HoconLoader.html)
+    /// configuration
+    pub fn resolve(self) -> Result<Hocon> {
+        self.loader.hocon()
+    }
+
+    fn entries_matching(&self, predicate: impl Fn(&RawValue) -> bool) -> Vec<RawEntry> {
+        self.entries()
+            .into_iter()
+            .filter(|entry| predicate(&entry.value))
+            .collect()
+    }
+}
+
+impl RawValue {
+    fn has_substitution(&self) -> bool {
+        match self {
+            RawValue::Substitution { .. } => true,
+            RawValue::Concatenation(values) => values.iter().any(RawValue::has_substitution),
+            RawValue::AppendToArray(value) | RawValue::Included(value) => value.has_substitution(),
+            _ => false,
+        }
+    }
+
+    fn has_concatenation(&self) -> bool {
+        match self {
+            RawValue::Concatenation(_) => true,
+            RawValue::AppendToArray(value) | RawValue::Included(value) => value.has_concatenation(),
+            _ => false,
+        }
+    }
+}
+
+impl From<&HoconValue> for RawValue {
+    fn from(value: &HoconValue) -> Self {
+        match value {
+            HoconValue::Real(f) => RawValue::Real(*f),
+            HoconValue::Integer(i) => RawValue::Integer(*i),
+            HoconValue::String(s) => RawValue::String(s.to_string()),
+            HoconValue::UnquotedString(s) if s.trim() == "null" => RawValue::Null,
+            HoconValue::UnquotedString(s) => RawValue::Unquoted(s.to_string()),
+            HoconValue::Boolean(b) => RawValue::Boolean(*b),
+            HoconValue::Null(_) => RawValue::Null,
+            HoconValue::EmptyObject => RawValue::EmptyObject,
+            HoconValue::EmptyArray => RawValue::EmptyArray,
+            HoconValue::Concat(values) => {
+                RawValue::Concatenation(values.iter().map(RawValue::from).collect())
+            }
+            HoconValue::PathSubstitution {
+                target, optional, ..
+            } => RawValue::Substitution {
+                path: target.path_string(),
+                optional: *optional,
+            },
+            HoconValue::PathSubstitutionInParent(target) => RawValue::Substitution {
+                path: target.path_string(),
+                optional: false,
+            },
+            HoconValue::ConcatSubstitution(subst) | HoconValue::InterpolatedSubstitution(subst) => {
+                RawValue::from(subst.as_ref())
+            }
+            HoconValue::FileSubstitution { path, optional, .. } => RawValue::FileSubstitution {
+                path: path.to_string(),
+                optional: *optional,
+            },
+            HoconValue::ToConcatToArray { value, .. } => {
+                RawValue::AppendToArray(Box::new(RawValue::from(value.as_ref())))
+            }
+            HoconValue::Included { value, .. } => {
+                RawValue::Included(Box::new(RawValue::from(value.as_ref())))
+            }
+            HoconValue::BadValue(err) => RawValue::Bad(err.clone()),
+            // Placeholders only exist while merging a document
+            HoconValue::Temp => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_inspect_raw_document() {
+        let doc = parse(r#"{ a.b: 5, c: foo ${a.b}, d: ${?x} [1], e += 3, e += 4 }"#)
+            .expect("during test");
+
+        assert_eq!(
+            doc.entries()[0],
+            RawEntry {
+                path: vec![String::from("a"), String::from("b")],
+                value: RawValue::Integer(5)
+            }
+        );
+        assert_eq!(
+            doc.concatenations(),
+            vec![RawEntry {
+                path: vec![String::from("c")],
+                value: RawValue::Concatenation(vec![
+                    RawValue::Unquoted(String::from("foo ")),
+                    RawValue::Substitution {
+                        path: String::from("a.b"),
+                        optional: false
+                    }
+                ])
+            }]
+        );
+        assert_eq!(doc.substitutions().len(), 2);
+        assert_eq!(doc.substitutions()[1].path, vec![String::from("d")]);
+        assert_eq!(
+            doc.entries()
+                .into_iter()
+                .filter(|entry| entry.path == vec![String::from("e")])
+                .map(|entry| entry.value)
+                .collect::<Vec<_>>(),
+            vec![
+                RawValue::AppendToArray(Box::new(RawValue::Integer(3))),
+                RawValue::AppendToArray(Box::new(RawValue::Integer(4)))
+            ]
+        );
+
+        assert_eq!(
+            doc.resolve().expect("during test")["c"].as_string(),
+            Some(String::from("foo 5"))
+        );
+    }
+
+    #[test]
+    fn can_inspect_includes() {
+        let doc = parse_file("tests/data/include_file.conf").expect("during test");
+
+        assert_eq!(
+            doc.includes()[0],
+            RawEntry {
+                path: vec![String::from("a")],
+                value: RawValue::Included(Box::new(RawValue::Integer(5)))
+            }
+        );
+
+        let doc = parse(r#"include "other.conf""#).expect("during test");
+        assert_eq!(
+            doc.includes(),
+            vec![RawEntry {
+                path: vec![String::from("other.conf")],
+                value: RawValue::Bad(Error::IncludeNotAllowedFromStr)
+            }]
+        );
+    }
+}
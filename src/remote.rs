@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "consul")]
+use crate::Error;
+use crate::Result;
+
+/// Key-value store holding part of a configuration, like Consul or etcd, loaded with
+/// [`HoconLoader::load_remote`](struct.HoconLoader.html#method.load_remote) as a document
+/// merged with the others.
+///
+/// The values are strings, converted when they are read as the values of a properties file,
+/// so `"8080"` can be read as an integer.
+pub trait RemoteSource {
+    /// Values of the keys under `prefix`, by their path relative to `prefix` with `.` between
+    /// keys, like `db.url`
+    ///
+    /// # Errors
+    ///
+    /// An error fails the load of the source
+    fn fetch(&self, prefix: &str) -> Result<HashMap<String, String>>;
+}
+
+impl<F> RemoteSource for F
+where
+    F: Fn(&str) -> Result<HashMap<String, String>>,
+{
+    fn fetch(&self, prefix: &str) -> Result<HashMap<String, String>> {
+        self(prefix)
+    }
+}
+
+/// The key-value store of [Consul](https://developer.hashicorp.com/consul/api-docs/kv),
+/// read with its HTTP API. The `/` in the keys under the prefix separate the keys of the
+/// document, so `app/db/url` under prefix `app` is `db.url`
+///
+/// ```rust,no_run
+/// # use hocon::{ConsulSource, HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// let consul = ConsulSource::new("http://127.0.0.1:8500").token("my-acl-token");
+/// let doc = HoconLoader::new()
+///     .load_file("application.conf")?
+///     .load_remote(&consul, "config/myapp")?
+///     .hocon()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Feature
+///
+/// This struct depends on feature `consul`
+#[cfg(feature = "consul")]
+#[derive(Clone)]
+pub struct ConsulSource {
+    address: String,
+    token: Option<String>,
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "consul")]
+impl std::fmt::Debug for ConsulSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsulSource")
+            .field("address", &self.address)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[cfg(feature = "consul")]
+impl ConsulSource {
+    /// Read the store of the Consul agent at `address`, like `http://127.0.0.1:8500`
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: String::from(address.trim_end_matches('/')),
+            token: None,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Send the ACL token `token` with the requests
+    pub fn token(self, token: &str) -> Self {
+        Self {
+            token: Some(String::from(token)),
+            ..self
+        }
+    }
+
+    /// Make the requests with `client`, to set its timeouts or certificates
+    pub fn client(self, client: reqwest::blocking::Client) -> Self {
+        Self { client, ..self }
+    }
+}
+
+#[cfg(feature = "consul")]
+impl RemoteSource for ConsulSource {
+    fn fetch(&self, prefix: &str) -> Result<HashMap<String, String>> {
+        let prefix = prefix.trim_matches('/');
+        let url = format!("{}/v1/kv/{prefix}?recurse", self.address);
+        let failed = |message: String| Error::Io {
+            message: format!("could not read '{url}': {message}"),
+        };
+        let mut request = self.client.get(&url);
+        if let Some(token) = self.token.as_ref() {
+            request = request.header("X-Consul-Token", token);
+        }
+        let response = request.send().map_err(|err| failed(err.to_string()))?;
+        // there is no key under the prefix
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(HashMap::new());
+        }
+        let body = response
+            .error_for_status()
+            .and_then(|response| response.text())
+            .map_err(|err| failed(err.to_string()))?;
+        let entries = match serde_json::from_str(&body) {
+            Ok(serde_json::Value::Array(entries)) => entries,
+            _ => return Err(failed(String::from("invalid response"))),
+        };
+
+        let mut values = HashMap::new();
+        for entry in entries {
+            let (Some(key), Some(value)) = (entry["Key"].as_str(), entry["Value"].as_str()) else {
+                // folders have no value
+                continue;
+            };
+            let path = key
+                .strip_prefix(prefix)
+                .unwrap_or(key)
+                .trim_matches('/')
+                .replace('/', ".");
+            let value = decode_base64(value)
+                .and_then(|value| String::from_utf8(value).ok())
+                .ok_or_else(|| failed(format!("invalid value of key '{key}'")))?;
+            if !path.is_empty() {
+                values.insert(path, value);
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// Bytes encoded in `text` with the standard base64 alphabet, as Consul sends its values
+#[cfg(feature = "consul")]
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let digit = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for c in text {
+        buffer = (buffer << 6) | u32::from(digit(*c)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(all(test, feature = "consul"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_base64() {
+        assert_eq!(decode_base64("aG9jb24="), Some(b"hocon".to_vec()));
+        assert_eq!(decode_base64("OjgwODA"), Some(b":8080".to_vec()));
+        assert_eq!(decode_base64(""), Some(vec![]));
+        assert_eq!(decode_base64("a*=="), None);
+    }
+}
@@ -0,0 +1,1181 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+
+use linked_hash_map::LinkedHashMap;
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+use crate::document::{Comments, HoconDocument};
+
+/// What to do with a [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) when writing a
+/// document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadValues {
+    /// Leave it out of its object or array, and write `null` when it is not inside one
+    Skip,
+    /// Write `null`
+    Null,
+    /// Fail with the error of the bad value
+    Error,
+}
+
+/// How [`Hocon::Real`](enum.Hocon.html#variant.Real) are written. Whatever the format, a
+/// real is written so that it is read back as the same real
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// With the fewest digits that are read back as the same value, like `0.1`, `2.0` or
+    /// `1e-7`
+    Shortest,
+    /// With this number of decimals, like `0.50` for 2. A value that would not be read back
+    /// the same, like `0.125` with 2 decimals, is written as with
+    /// [`Shortest`](#variant.Shortest)
+    Fixed(usize),
+    /// In scientific notation when its decimal exponent is at least this number, or at most
+    /// its opposite, like `1.5e21`, and with all its digits otherwise, like `1500000.0`
+    Scientific(u16),
+}
+
+impl FloatFormat {
+    /// `f`, that must be finite, as written in a document
+    pub(crate) fn format(self, f: f64) -> String {
+        let formatted = match self {
+            FloatFormat::Shortest => format!("{f:?}"),
+            FloatFormat::Fixed(decimals) => format!("{f:.decimals$}"),
+            FloatFormat::Scientific(threshold) => {
+                let exponent = if f == 0.0 {
+                    0
+                } else {
+                    f.abs().log10().floor() as i32
+                };
+                if exponent.abs() >= i32::from(threshold) {
+                    format!("{f:e}")
+                } else {
+                    format!("{f}")
+                }
+            }
+        };
+        // not to be read back as an integer
+        let formatted = if formatted.contains(['.', 'e']) {
+            formatted
+        } else {
+            format!("{formatted}.0")
+        };
+        if formatted.parse::<f64>() == Ok(f) {
+            formatted
+        } else {
+            format!("{f:?}")
+        }
+    }
+}
+
+/// Options to write JSON with [`Hocon::write_json`](enum.Hocon.html#method.write_json)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JsonOptions {
+    /// Write each value on its own line, indented by this number of spaces for each level.
+    /// By default `None`, writing the document on one line
+    pub indent: Option<usize>,
+    /// What to do with bad values, by default [`BadValues::Skip`](enum.BadValues.html#variant.Skip)
+    pub bad_values: BadValues,
+    /// Write [`Hocon::Real`](enum.Hocon.html#variant.Real) that are whole numbers as integers,
+    /// like `5` instead of `5.0`, as HOCON documents can't tell them apart once read from a
+    /// JSON document. By default `true`
+    pub whole_reals_as_integers: bool,
+    /// How the other reals are written, by default
+    /// [`FloatFormat::Shortest`](enum.FloatFormat.html#variant.Shortest)
+    pub float_format: FloatFormat,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self {
+            indent: None,
+            bad_values: BadValues::Skip,
+            whole_reals_as_integers: true,
+            float_format: FloatFormat::Shortest,
+        }
+    }
+}
+
+impl JsonOptions {
+    /// Write the document on one line
+    pub fn compact() -> Self {
+        Self::default()
+    }
+
+    /// Write each value on its own line, indented by two spaces for each level
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some(2),
+            ..Self::default()
+        }
+    }
+}
+
+impl Hocon {
+    /// Write as JSON to `writer`, without building an intermediate value, for documents too
+    /// large to be copied. The document is written as it is converted, so writing to a
+    /// [`BufWriter`](https://doc.rust-lang.org/std/io/struct.BufWriter.html) is faster.
+    /// Infinite and NaN values, that JSON can't represent, are written as `null`
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error, JsonOptions};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ name: app, ports: [80, 443], ratio: 2.0 }"#)?
+    ///     .hocon()?;
+    ///
+    /// let mut json = vec![];
+    /// doc.write_json(&mut json, JsonOptions::compact())?;
+    /// assert_eq!(json, br#"{"name":"app","ports":[80,443],"ratio":2}"#);
+    ///
+    /// let mut json = vec![];
+    /// doc.write_json(&mut json, JsonOptions::pretty())?;
+    /// assert_eq!(
+    ///     String::from_utf8_lossy(&json),
+    ///     "{\n  \"name\": \"app\",\n  \"ports\": [\n    80,\n    443\n  ],\n  \"ratio\": 2\n}"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`](enum.Error.html#variant.Io) if writing to `writer` fails
+    /// * the error of a bad value, with [`BadValues::Error`](enum.BadValues.html#variant.Error)
+    pub fn write_json(&self, writer: &mut impl io::Write, options: JsonOptions) -> Result<()> {
+        self.render_json(&mut IoOutput(writer), options)
+    }
+
+    /// Write as JSON to `writer`, as with [`write_json`](#method.write_json), for writers of
+    /// text like a `String` or a `Formatter`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`write_json`](#method.write_json)
+    pub fn write_json_fmt(&self, writer: &mut impl fmt::Write, options: JsonOptions) -> Result<()> {
+        self.render_json(&mut FmtOutput(writer), options)
+    }
+
+    fn render_json(&self, output: &mut dyn Output, options: JsonOptions) -> Result<()> {
+        let mut json = JsonWriter {
+            output,
+            options,
+            level: 0,
+        };
+        match self {
+            Hocon::BadValue(_) if options.bad_values == BadValues::Skip => json.write("null"),
+            value => json.value(value),
+        }
+    }
+}
+
+/// Where a document is written while it is rendered, an `io::Write` or a `fmt::Write`
+pub(crate) trait Output {
+    fn write(&mut self, text: &str) -> Result<()>;
+}
+
+pub(crate) struct IoOutput<'a, W>(pub(crate) &'a mut W);
+
+impl<W: io::Write> Output for IoOutput<'_, W> {
+    fn write(&mut self, text: &str) -> Result<()> {
+        self.0.write_all(text.as_bytes()).map_err(Error::from)
+    }
+}
+
+pub(crate) struct FmtOutput<'a, W>(pub(crate) &'a mut W);
+
+impl<W: fmt::Write> Output for FmtOutput<'_, W> {
+    fn write(&mut self, text: &str) -> Result<()> {
+        self.0.write_str(text).map_err(|err| Error::Io {
+            message: err.to_string(),
+        })
+    }
+}
+
+struct JsonWriter<'a> {
+    output: &'a mut dyn Output,
+    options: JsonOptions,
+    level: usize,
+}
+
+impl JsonWriter<'_> {
+    fn write(&mut self, text: &str) -> Result<()> {
+        self.output.write(text)
+    }
+
+    /// Start the line of a value in a pretty document
+    fn new_line(&mut self) -> Result<()> {
+        if let Some(indent) = self.options.indent {
+            let line = format!("\n{:width$}", "", width = indent * self.level);
+            self.write(&line)?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, value: &Hocon) -> Result<()> {
+        match value {
+            Hocon::Boolean(b) => self.write(if *b { "true" } else { "false" }),
+            Hocon::Integer(i) => self.write(&i.to_string()),
+            Hocon::Real(f) if !f.is_finite() => self.write("null"),
+            Hocon::Real(f)
+                if self.options.whole_reals_as_integers
+                    && f.fract() == 0.0
+                    && *f >= i64::MIN as f64
+                    && *f <= i64::MAX as f64 =>
+            {
+                self.write(&(*f as i64).to_string())
+            }
+            Hocon::Real(f) => self.write(&self.options.float_format.format(*f)),
+            Hocon::String(s) => self.string(s),
+            Hocon::Array(values) => {
+                self.container(('[', ']'), values.iter().map(|value| (None, value)))
+            }
+            Hocon::Hash(hash) => self.container(
+                ('{', '}'),
+                hash.iter().map(|(key, value)| (Some(key.as_str()), value)),
+            ),
+            Hocon::Null => self.write("null"),
+            Hocon::BadValue(err) => match self.options.bad_values {
+                BadValues::Error => Err(err.clone()),
+                BadValues::Skip | BadValues::Null => self.write("null"),
+            },
+        }
+    }
+
+    fn container<'v>(
+        &mut self,
+        (open, close): (char, char),
+        items: impl Iterator<Item = (Option<&'v str>, &'v Hocon)>,
+    ) -> Result<()> {
+        self.write(open.encode_utf8(&mut [0; 4]))?;
+        self.level += 1;
+        let mut empty = true;
+        for (key, value) in items {
+            if matches!(value, Hocon::BadValue(_)) && self.options.bad_values == BadValues::Skip {
+                continue;
+            }
+            if !empty {
+                self.write(",")?;
+            }
+            empty = false;
+            self.new_line()?;
+            if let Some(key) = key {
+                self.string(key)?;
+                self.write(if self.options.indent.is_some() {
+                    ": "
+                } else {
+                    ":"
+                })?;
+            }
+            self.value(value)?;
+        }
+        self.level -= 1;
+        if !empty {
+            self.new_line()?;
+        }
+        self.write(close.encode_utf8(&mut [0; 4]))
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        let mut quoted = String::with_capacity(s.len() + 2);
+        push_quoted(s, &mut quoted);
+        self.write(&quoted)
+    }
+}
+
+/// Escape `s` to write it between the double quotes of an HOCON or JSON string, so that it is
+/// read back as `s`. Quotes, backslashes and control characters are escaped, and so are the
+/// characters that are not shown, like the line separator or `U+FEFF`, as `\uXXXX`, with a
+/// surrogate pair outside of the basic multilingual plane. `s` is borrowed when nothing needs to
+/// be escaped
+///
+/// ```rust
+/// assert_eq!(hocon::escape("a \"b\"\n"), r#"a \"b\"\n"#);
+/// assert_eq!(hocon::escape("\u{2028}\u{10FFFF}"), r"\u2028\udbff\udfff");
+/// assert_eq!(hocon::escape("héllo"), "héllo");
+/// ```
+pub fn escape(s: &str) -> Cow<'_, str> {
+    let Some(first) = s.find(|c| escaped(c).is_some()) else {
+        return Cow::Borrowed(s);
+    };
+    let mut escaped_s = String::with_capacity(s.len() + 8);
+    escaped_s.push_str(&s[..first]);
+    for c in s[first..].chars() {
+        match escaped(c) {
+            Some(Escaped::Short(short)) => escaped_s.push_str(short),
+            Some(Escaped::Unicode) => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    escaped_s.push_str(&format!("\\u{unit:04x}"));
+                }
+            }
+            None => escaped_s.push(c),
+        }
+    }
+    Cow::Owned(escaped_s)
+}
+
+/// How a character is escaped in a quoted string
+enum Escaped {
+    Short(&'static str),
+    Unicode,
+}
+
+fn escaped(c: char) -> Option<Escaped> {
+    match c {
+        '"' => Some(Escaped::Short("\\\"")),
+        '\\' => Some(Escaped::Short("\\\\")),
+        '\n' => Some(Escaped::Short("\\n")),
+        '\r' => Some(Escaped::Short("\\r")),
+        '\t' => Some(Escaped::Short("\\t")),
+        '\u{8}' => Some(Escaped::Short("\\b")),
+        '\u{c}' => Some(Escaped::Short("\\f")),
+        // controls, line and paragraph separators, byte order mark and noncharacters
+        c if c.is_control()
+            || c == '\u{2028}'
+            || c == '\u{2029}'
+            || c == '\u{feff}'
+            || ('\u{fdd0}'..='\u{fdef}').contains(&c)
+            || (c as u32) & 0xfffe == 0xfffe =>
+        {
+            Some(Escaped::Unicode)
+        }
+        _ => None,
+    }
+}
+
+/// Push `s` as a quoted string, valid in both JSON and HOCON
+fn push_quoted(s: &str, to: &mut String) {
+    to.push('"');
+    to.push_str(&escape(s));
+    to.push('"');
+}
+
+/// When to quote keys and strings with [`Hocon::to_hocon_string`](enum.Hocon.html#method.to_hocon_string)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quoting {
+    /// Quote every key and string, as in JSON
+    Always,
+    /// Quote only the keys and strings that would not be read back the same unquoted, like
+    /// `"a.b"` or `"8080"`
+    WhenNeeded,
+}
+
+/// How keys are separated from their values with
+/// [`Hocon::to_hocon_string`](enum.Hocon.html#method.to_hocon_string)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// `key = value`, and `key { ... }` for objects
+    Equals,
+    /// `key: value`, and `key: { ... }` for objects, as in JSON
+    Colon,
+}
+
+/// Options to write HOCON with [`Hocon::to_hocon_string`](enum.Hocon.html#method.to_hocon_string),
+/// like the `ConfigRenderOptions` of the Java library
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// Number of spaces to indent each level of objects and arrays, by default 2
+    pub indent: usize,
+    /// When to quote keys and strings, by default
+    /// [`Quoting::WhenNeeded`](enum.Quoting.html#variant.WhenNeeded)
+    pub quoting: Quoting,
+    /// How keys are separated from their values, by default
+    /// [`Separator::Equals`](enum.Separator.html#variant.Equals)
+    pub separator: Separator,
+    /// Write the braces of the root object. By default `false`, as in most HOCON files
+    pub root_braces: bool,
+    /// What to do with bad values, by default [`BadValues::Skip`](enum.BadValues.html#variant.Skip)
+    pub bad_values: BadValues,
+    /// Write a comment before each value that is not an object, with the source it comes from
+    /// and the sources it overrides, like `# from file 'prod.conf', overrides file
+    /// 'base.conf'`. By default `false`. This needs a document loaded with tracing,
+    /// written with [`HoconDocument::to_hocon_string`](document/struct.HoconDocument.html#method.to_hocon_string)
+    pub origin_comments: bool,
+    /// Write the fields of objects sorted by key, so that documents built in different
+    /// orders are written the same, for example to compare them in tests. By default
+    /// `false`, keeping the order the fields were set in
+    pub sort_keys: bool,
+    /// Write the comments kept with
+    /// [`HoconLoader::keep_comments`](struct.HoconLoader.html#method.keep_comments), written
+    /// with [`HoconDocument::to_hocon_string`](document/struct.HoconDocument.html#method.to_hocon_string).
+    /// By default `true`
+    pub comments: bool,
+    /// How reals are written, by default
+    /// [`FloatFormat::Shortest`](enum.FloatFormat.html#variant.Shortest)
+    pub float_format: FloatFormat,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            quoting: Quoting::WhenNeeded,
+            separator: Separator::Equals,
+            root_braces: false,
+            bad_values: BadValues::Skip,
+            origin_comments: false,
+            sort_keys: false,
+            comments: true,
+            float_format: FloatFormat::Shortest,
+        }
+    }
+}
+
+impl Hocon {
+    /// Write as an HOCON document, that is read back as the same document. Arrays of
+    /// scalars are written on one line, other arrays and objects with a line for each of
+    /// their values. As HOCON has no literal for them, infinite and NaN values are written
+    /// as `null`
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error, RenderOptions, Separator};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ "server": { "host": "0.0.0.0", "port": 8080, "tags": ["a", "b"] } }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(
+    ///     doc.to_hocon_string(RenderOptions::default())?,
+    ///     "server {\n  host = \"0.0.0.0\"\n  port = 8080\n  tags = [a, b]\n}\n"
+    /// );
+    /// assert_eq!(
+    ///     doc.to_hocon_string(RenderOptions {
+    ///         separator: Separator::Colon,
+    ///         root_braces: true,
+    ///         ..RenderOptions::default()
+    ///     })?,
+    ///     "{\n  server: {\n    host: \"0.0.0.0\"\n    port: 8080\n    tags: [a, b]\n  }\n}\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * the error of a bad value, with [`BadValues::Error`](enum.BadValues.html#variant.Error)
+    pub fn to_hocon_string(&self, options: RenderOptions) -> Result<String> {
+        let mut hocon = String::new();
+        self.write_hocon_fmt(&mut hocon, options)?;
+        Ok(hocon)
+    }
+
+    /// Write as an HOCON document to `writer`, as with
+    /// [`to_hocon_string`](#method.to_hocon_string), a line at a time, without building the
+    /// whole document in memory
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`](enum.Error.html#variant.Io) if writing to `writer` fails
+    /// * the error of a bad value, with [`BadValues::Error`](enum.BadValues.html#variant.Error)
+    pub fn write_hocon(&self, writer: &mut impl io::Write, options: RenderOptions) -> Result<()> {
+        HoconRenderer::render(self, &mut IoOutput(writer), options, None)
+    }
+
+    /// Write as an HOCON document to `writer`, as with [`write_hocon`](#method.write_hocon),
+    /// for writers of text like a `String` or a `Formatter`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`write_hocon`](#method.write_hocon)
+    pub fn write_hocon_fmt(
+        &self,
+        writer: &mut impl fmt::Write,
+        options: RenderOptions,
+    ) -> Result<()> {
+        HoconRenderer::render(self, &mut FmtOutput(writer), options, None)
+    }
+}
+
+impl HoconDocument {
+    /// Write as an HOCON document, as with
+    /// [`Hocon::to_hocon_string`](../enum.Hocon.html#method.to_hocon_string). With
+    /// [`RenderOptions::origin_comments`](../struct.RenderOptions.html#structfield.origin_comments)
+    /// and tracing enabled with [`HoconLoader::trace`](../struct.HoconLoader.html#method.trace),
+    /// each value is written after a comment saying where it comes from. The comments kept
+    /// with [`HoconLoader::keep_comments`](../struct.HoconLoader.html#method.keep_comments)
+    /// are written around their fields
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error, RenderOptions};
+    /// # fn main() -> Result<(), Error> {
+    /// let document = HoconLoader::new()
+    ///     .trace()
+    ///     .load_str(r#"{ server.port: 8080 }"#)?
+    ///     .load_args(vec!["server.port=9000"])?
+    ///     .document()?;
+    ///
+    /// assert_eq!(
+    ///     document.to_hocon_string(RenderOptions {
+    ///         origin_comments: true,
+    ///         ..RenderOptions::default()
+    ///     })?,
+    ///     "server {\n  # from arguments, overrides string\n  port = 9000\n}\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * the error of a bad value, with [`BadValues::Error`](../enum.BadValues.html#variant.Error)
+    pub fn to_hocon_string(&self, options: RenderOptions) -> Result<String> {
+        let mut hocon = String::new();
+        self.write_hocon_fmt(&mut hocon, options)?;
+        Ok(hocon)
+    }
+
+    /// Write as an HOCON document to `writer`, as with
+    /// [`to_hocon_string`](#method.to_hocon_string), a line at a time
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Io`](../enum.Error.html#variant.Io) if writing to `writer` fails
+    /// * the error of a bad value, with [`BadValues::Error`](../enum.BadValues.html#variant.Error)
+    pub fn write_hocon(&self, writer: &mut impl io::Write, options: RenderOptions) -> Result<()> {
+        HoconRenderer::render(&self.hocon, &mut IoOutput(writer), options, Some(self))
+    }
+
+    /// Write as an HOCON document to `writer`, as with [`write_hocon`](#method.write_hocon),
+    /// for writers of text like a `String` or a `Formatter`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`write_hocon`](#method.write_hocon)
+    pub fn write_hocon_fmt(
+        &self,
+        writer: &mut impl fmt::Write,
+        options: RenderOptions,
+    ) -> Result<()> {
+        HoconRenderer::render(&self.hocon, &mut FmtOutput(writer), options, Some(self))
+    }
+}
+
+struct HoconRenderer<'a, 'o> {
+    output: &'o mut dyn Output,
+    /// The line being written
+    out: String,
+    options: RenderOptions,
+    level: usize,
+    /// Document to read the origins of the values from, for origin comments
+    document: Option<&'a HoconDocument>,
+    /// Keys of the field being written
+    path: Vec<&'a str>,
+}
+
+impl<'a, 'o> HoconRenderer<'a, 'o> {
+    fn render(
+        hocon: &'a Hocon,
+        output: &'o mut dyn Output,
+        options: RenderOptions,
+        document: Option<&'a HoconDocument>,
+    ) -> Result<()> {
+        let mut renderer = HoconRenderer {
+            output,
+            out: String::new(),
+            options,
+            level: 0,
+            document,
+            path: vec![],
+        };
+        match hocon {
+            Hocon::Hash(hash) if !options.root_braces => {
+                renderer.fields(hash)?;
+            }
+            Hocon::BadValue(_) if options.bad_values == BadValues::Skip => {
+                renderer.out.push_str("null\n");
+            }
+            value => {
+                renderer.value(value)?;
+                renderer.out.push('\n');
+            }
+        }
+        renderer.flush()
+    }
+
+    /// Write the lines rendered
+    fn flush(&mut self) -> Result<()> {
+        self.output.write(&self.out)?;
+        self.out.clear();
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        let width = self.options.indent * self.level;
+        self.out.extend(std::iter::repeat_n(' ', width));
+    }
+
+    fn skipped(&self, value: &Hocon) -> bool {
+        matches!(value, Hocon::BadValue(_)) && self.options.bad_values == BadValues::Skip
+    }
+
+    fn fields(&mut self, hash: &'a LinkedHashMap<String, Hocon>) -> Result<()> {
+        let mut fields: Vec<_> = hash.iter().collect();
+        if self.options.sort_keys {
+            fields.sort_by_key(|(key, _)| *key);
+        }
+        for (key, value) in fields {
+            self.field(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Write a field of an object on its own line
+    fn field(&mut self, key: &'a str, value: &'a Hocon) -> Result<()> {
+        if self.skipped(value) {
+            return Ok(());
+        }
+        self.path.push(key);
+        let comments = self.kept_comments();
+        for comment in comments.iter().flat_map(|comments| &comments.leading) {
+            self.indent();
+            self.out.push('#');
+            self.out.push_str(comment);
+            self.out.push('\n');
+        }
+        if self.options.origin_comments && !matches!(value, Hocon::Hash(_)) {
+            self.origin_comment();
+        }
+        self.indent();
+        if self.options.quoting == Quoting::WhenNeeded && is_unquoted_key(key) {
+            self.out.push_str(key);
+        } else {
+            push_quoted(key, &mut self.out);
+        }
+        self.out.push_str(match (self.options.separator, value) {
+            (Separator::Equals, Hocon::Hash(hash)) if !hash.is_empty() => " ",
+            (Separator::Equals, _) => " = ",
+            (Separator::Colon, _) => ": ",
+        });
+        self.value(value)?;
+        if let Some(trailing) = comments.and_then(|comments| comments.trailing.as_ref()) {
+            self.out.push_str(" #");
+            self.out.push_str(trailing);
+        }
+        self.out.push('\n');
+        self.path.pop();
+        self.flush()
+    }
+
+    /// Comments kept with the field at the current path
+    fn kept_comments(&self) -> Option<&'a Comments> {
+        self.document
+            .filter(|_| self.options.comments)
+            .and_then(|document| document.comments(&self.path.join(".")))
+    }
+
+    /// Comment with the source of the value at the current path, and the sources it overrides
+    fn origin_comment(&mut self) {
+        let Some(document) = self.document else {
+            return;
+        };
+        let path = self.path.join(".");
+        let Some(origin) = document.origin(&path) else {
+            return;
+        };
+        let mut overridden = document.override_chain(&path);
+        overridden.pop();
+        overridden.dedup();
+        self.indent();
+        self.out.push_str(&format!("# {origin}"));
+        for (index, source) in overridden.iter().rev().enumerate() {
+            self.out.push_str(&format!(
+                "{}{source}",
+                if index == 0 { ", overrides " } else { ", " }
+            ));
+        }
+        self.out.push('\n');
+    }
+
+    fn value(&mut self, value: &'a Hocon) -> Result<()> {
+        match value {
+            Hocon::Boolean(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            Hocon::Integer(i) => self.out.push_str(&i.to_string()),
+            Hocon::Real(f) if !f.is_finite() => self.out.push_str("null"),
+            Hocon::Real(f) => {
+                let real = self.options.float_format.format(*f);
+                self.out.push_str(&real);
+            }
+            Hocon::String(s) => {
+                if self.options.quoting == Quoting::WhenNeeded && is_unquoted_string(s) {
+                    self.out.push_str(s);
+                } else {
+                    push_quoted(s, &mut self.out);
+                }
+            }
+            Hocon::Hash(hash) if hash.is_empty() => self.out.push_str("{}"),
+            Hocon::Hash(hash) => {
+                self.out.push_str("{\n");
+                self.level += 1;
+                self.fields(hash)?;
+                self.level -= 1;
+                self.indent();
+                self.out.push('}');
+            }
+            Hocon::Array(values) => {
+                // the origins of the values in arrays are those of their arrays
+                let document = self.document.take();
+                let values: Vec<_> = values.iter().filter(|value| !self.skipped(value)).collect();
+                if values
+                    .iter()
+                    .all(|value| !matches!(value, Hocon::Hash(_) | Hocon::Array(_)))
+                {
+                    self.out.push('[');
+                    for (index, value) in values.into_iter().enumerate() {
+                        if index > 0 {
+                            self.out.push_str(", ");
+                        }
+                        self.value(value)?;
+                    }
+                    self.out.push(']');
+                } else {
+                    self.out.push_str("[\n");
+                    self.level += 1;
+                    for value in values {
+                        self.indent();
+                        self.value(value)?;
+                        self.out.push('\n');
+                        self.flush()?;
+                    }
+                    self.level -= 1;
+                    self.indent();
+                    self.out.push(']');
+                }
+                self.document = document;
+            }
+            Hocon::Null => self.out.push_str("null"),
+            Hocon::BadValue(err) => match self.options.bad_values {
+                BadValues::Error => return Err(err.clone()),
+                BadValues::Skip | BadValues::Null => self.out.push_str("null"),
+            },
+        }
+        Ok(())
+    }
+}
+
+/// A compact rendering on a single line, like `{a: 1, b: [1, 2], c: "a b"}`, to log values or
+/// show them in messages. Strings are quoted when needed, and bad values are left out of their
+/// objects and arrays as in [`Hocon::to_hocon_string`](enum.Hocon.html#method.to_hocon_string)
+///
+/// ```rust
+/// # use hocon::hocon;
+/// let doc = hocon!({ server { host = "0.0.0.0", ports = [80, 443] }, ratio = 0.5 });
+///
+/// assert_eq!(
+///     doc.to_string(),
+///     r#"{server: {host: "0.0.0.0", ports: [80, 443]}, ratio: 0.5}"#
+/// );
+/// assert_eq!(doc["server"]["ports"][0].to_string(), "80");
+/// ```
+impl fmt::Display for Hocon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = |value: &&Hocon| !matches!(value, Hocon::BadValue(_));
+        match self {
+            Hocon::Boolean(b) => write!(f, "{b}"),
+            Hocon::Integer(i) => write!(f, "{i}"),
+            Hocon::Real(r) if !r.is_finite() => f.write_str("null"),
+            Hocon::Real(r) => f.write_str(&FloatFormat::Shortest.format(*r)),
+            Hocon::String(s) if is_unquoted_string(s) => f.write_str(s),
+            Hocon::String(s) => {
+                let mut quoted = String::with_capacity(s.len() + 2);
+                push_quoted(s, &mut quoted);
+                f.write_str(&quoted)
+            }
+            Hocon::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().filter(shown).enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Hocon::Hash(hash) => {
+                f.write_str("{")?;
+                let fields = hash.iter().filter(|(_, value)| shown(value));
+                for (index, (key, value)) in fields.enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {value}", key_string(key))?;
+                }
+                f.write_str("}")
+            }
+            Hocon::Null | Hocon::BadValue(_) => f.write_str("null"),
+        }
+    }
+}
+
+/// Key as written in a path, quoted when needed
+pub(crate) fn key_string(key: &str) -> String {
+    if is_unquoted_key(key) {
+        String::from(key)
+    } else {
+        let mut quoted = String::with_capacity(key.len() + 2);
+        push_quoted(key, &mut quoted);
+        quoted
+    }
+}
+
+/// Whether `key` is read back as the same key without quotes
+fn is_unquoted_key(key: &str) -> bool {
+    !key.is_empty()
+        && key != "include"
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Whether `s` is read back as the same string without quotes, and not as another type
+fn is_unquoted_string(s: &str) -> bool {
+    !s.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.')
+        && !["true", "false", "null", "include"]
+            .iter()
+            .any(|keyword| s.starts_with(keyword))
+        && s.split('.').all(is_unquoted_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(hocon: &Hocon, options: JsonOptions) -> Result<String> {
+        let mut json = vec![];
+        hocon.write_json(&mut json, options)?;
+        Ok(String::from_utf8(json).expect("during test"))
+    }
+
+    #[test]
+    fn can_write_json() {
+        let mut hocon = crate::hocon!({
+            a = [1, 2.0, 2.5, f64::NAN],
+            b { c = null, d = "quote \" and\ttab\u{1}" },
+            e = {},
+            f = [],
+        });
+        hocon
+            .set("b.g", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        assert_eq!(
+            json(&hocon, JsonOptions::compact()),
+            Ok(String::from(
+                r#"{"a":[1,2,2.5,null],"b":{"c":null,"d":"quote \" and\ttab\u0001"},"e":{},"f":[]}"#
+            ))
+        );
+        assert_eq!(
+            json(
+                &hocon,
+                JsonOptions {
+                    bad_values: BadValues::Null,
+                    whole_reals_as_integers: false,
+                    ..JsonOptions::default()
+                }
+            ),
+            Ok(String::from(
+                r#"{"a":[1,2.0,2.5,null],"b":{"c":null,"d":"quote \" and\ttab\u0001","g":null},"e":{},"f":[]}"#
+            ))
+        );
+        assert_eq!(
+            json(
+                &hocon,
+                JsonOptions {
+                    bad_values: BadValues::Error,
+                    ..JsonOptions::default()
+                }
+            ),
+            Err(Error::MissingKey)
+        );
+        assert_eq!(
+            json(&Hocon::BadValue(Error::MissingKey), JsonOptions::default()),
+            Ok(String::from("null"))
+        );
+    }
+
+    #[test]
+    fn can_write_pretty_json() {
+        let hocon = crate::hocon!({ a { b = [1, {}] }, c = [] });
+
+        assert_eq!(
+            json(
+                &hocon,
+                JsonOptions {
+                    indent: Some(4),
+                    ..JsonOptions::default()
+                }
+            ),
+            Ok(String::from(
+                "{\n    \"a\": {\n        \"b\": [\n            1,\n            {}\n        ]\n    },\n    \"c\": []\n}"
+            ))
+        );
+    }
+
+    #[test]
+    fn can_write_to_any_writer() {
+        let hocon = crate::hocon!({ a { b = [1, { c = "d" }] } });
+
+        let mut json = String::new();
+        hocon
+            .write_json_fmt(&mut json, JsonOptions::compact())
+            .expect("during test");
+        assert_eq!(json, r#"{"a":{"b":[1,{"c":"d"}]}}"#);
+
+        let mut rendered = vec![];
+        hocon
+            .write_hocon(&mut rendered, RenderOptions::default())
+            .expect("during test");
+        assert_eq!(
+            String::from_utf8(rendered).ok(),
+            hocon.to_hocon_string(RenderOptions::default()).ok()
+        );
+
+        let mut properties = vec![];
+        hocon
+            .write_properties(&mut properties)
+            .expect("during test");
+        assert_eq!(properties, b"a.b.0=1\na.b.1.c=d\n");
+    }
+
+    #[test]
+    fn can_not_write_to_a_failing_writer() {
+        struct Failing;
+        impl io::Write for Failing {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        assert!(matches!(
+            Hocon::Integer(1).write_json(&mut Failing, JsonOptions::default()),
+            Err(Error::Io { message }) if message == "disk full"
+        ));
+        assert!(matches!(
+            crate::hocon!({ a = 1 }).write_hocon(&mut Failing, RenderOptions::default()),
+            Err(Error::Io { message }) if message == "disk full"
+        ));
+        assert!(matches!(
+            crate::hocon!({ a = 1 }).write_properties(&mut Failing),
+            Err(Error::Io { message }) if message == "disk full"
+        ));
+    }
+
+    #[test]
+    fn can_write_hocon() {
+        let mut hocon = crate::hocon!({
+            "a.b" = [1, 2.0, "true", "x y"],
+            c { d = "8080", e = "local.host", f = {}, g = [] },
+            include = [{ h = null }, [f64::NAN]],
+            "" = "",
+        });
+        hocon
+            .set("c.i", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        let rendered = hocon
+            .to_hocon_string(RenderOptions::default())
+            .expect("during test");
+        assert_eq!(
+            rendered,
+            r#""a.b" = [1, 2.0, "true", "x y"]
+c {
+  d = "8080"
+  e = local.host
+  f = {}
+  g = []
+}
+"include" = [
+  {
+    h = null
+  }
+  [null]
+]
+"" = ""
+"#
+        );
+        // read back without the bad value, and with null for NaN
+        hocon.remove("c.i");
+        hocon.set("include.1.0", Hocon::Null).expect("during test");
+        let read = crate::HoconLoader::new()
+            .load_str(&rendered)
+            .and_then(crate::HoconLoader::hocon)
+            .expect("during test");
+        assert_eq!(read, hocon);
+
+        assert_eq!(
+            crate::hocon!({ a { b = "c" } }).to_hocon_string(RenderOptions {
+                indent: 4,
+                quoting: Quoting::Always,
+                separator: Separator::Colon,
+                root_braces: true,
+                bad_values: BadValues::Skip,
+                origin_comments: false,
+                sort_keys: false,
+                comments: false,
+                float_format: FloatFormat::Shortest,
+            }),
+            Ok(String::from(
+                "{\n    \"a\": {\n        \"b\": \"c\"\n    }\n}\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn can_write_hocon_bad_values() {
+        let hocon = crate::hocon!({ a = [1, 2] });
+        let mut bad = hocon.clone();
+        bad.set("a.1", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        assert_eq!(
+            bad.to_hocon_string(RenderOptions::default()),
+            Ok(String::from("a = [1]\n"))
+        );
+        assert_eq!(
+            bad.to_hocon_string(RenderOptions {
+                bad_values: BadValues::Null,
+                ..RenderOptions::default()
+            }),
+            Ok(String::from("a = [1, null]\n"))
+        );
+        assert_eq!(
+            bad.to_hocon_string(RenderOptions {
+                bad_values: BadValues::Error,
+                ..RenderOptions::default()
+            }),
+            Err(Error::MissingKey)
+        );
+        assert_eq!(
+            Hocon::BadValue(Error::MissingKey).to_hocon_string(RenderOptions::default()),
+            Ok(String::from("null\n"))
+        );
+    }
+
+    #[test]
+    fn can_write_hocon_sorted() {
+        let options = RenderOptions {
+            sort_keys: true,
+            ..RenderOptions::default()
+        };
+        let hocon = crate::hocon!({ b { d = 1, c = [{ f = 1, e = 2 }] }, a = 0, B = 2 });
+        let reordered = crate::hocon!({ B = 2, a = 0, b { c = [{ e = 2, f = 1 }], d = 1 } });
+
+        let rendered = hocon.to_hocon_string(options).expect("during test");
+        assert_eq!(
+            rendered,
+            "B = 2\na = 0\nb {\n  c = [\n    {\n      e = 2\n      f = 1\n    }\n  ]\n  d = 1\n}\n"
+        );
+        assert_eq!(reordered.to_hocon_string(options), Ok(rendered));
+    }
+
+    #[test]
+    fn can_format_reals() {
+        let reals = [0.1, 2.0, -0.5, 0.125, 1.5e21, 1e-7, 123456.789, 0.0];
+        let formatted =
+            |format: FloatFormat| reals.iter().map(|f| format.format(*f)).collect::<Vec<_>>();
+        assert_eq!(
+            formatted(FloatFormat::Shortest),
+            [
+                "0.1",
+                "2.0",
+                "-0.5",
+                "0.125",
+                "1.5e21",
+                "1e-7",
+                "123456.789",
+                "0.0"
+            ]
+        );
+        assert_eq!(
+            formatted(FloatFormat::Fixed(2)),
+            [
+                "0.10",
+                "2.00",
+                "-0.50",
+                "0.125",
+                "1500000000000000000000.00",
+                "1e-7",
+                "123456.789",
+                "0.00"
+            ]
+        );
+        assert_eq!(
+            formatted(FloatFormat::Scientific(3)),
+            [
+                "0.1",
+                "2.0",
+                "-0.5",
+                "0.125",
+                "1.5e21",
+                "1e-7",
+                "1.23456789e5",
+                "0.0"
+            ]
+        );
+
+        // and they are read back the same
+        for format in [
+            FloatFormat::Shortest,
+            FloatFormat::Fixed(0),
+            FloatFormat::Fixed(3),
+            FloatFormat::Scientific(0),
+            FloatFormat::Scientific(30),
+        ] {
+            let hocon = Hocon::Hash(
+                reals
+                    .iter()
+                    .enumerate()
+                    .map(|(index, f)| (format!("r{index}"), Hocon::Real(*f)))
+                    .collect(),
+            );
+            let rendered = hocon
+                .to_hocon_string(RenderOptions {
+                    float_format: format,
+                    ..RenderOptions::default()
+                })
+                .expect("during test");
+            let read = crate::HoconLoader::new()
+                .load_str(&rendered)
+                .and_then(crate::HoconLoader::hocon)
+                .expect("during test");
+            assert_eq!(read, hocon, "{rendered}");
+        }
+    }
+
+    #[test]
+    fn can_display_values() {
+        let mut hocon = crate::hocon!({
+            a = 1,
+            b = [1, 2.0, "x y", null],
+            "c.d" { e = true, f = {}, g = [] },
+            h = f64::NAN,
+        });
+        hocon
+            .set("b.4", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+        assert_eq!(
+            hocon.to_string(),
+            r#"{a: 1, b: [1, 2.0, "x y", null], "c.d": {e: true, f: {}, g: []}, h: null}"#
+        );
+        assert_eq!(Hocon::String(String::from("true")).to_string(), r#""true""#);
+        assert_eq!(Hocon::BadValue(Error::MissingKey).to_string(), "null");
+    }
+
+    #[test]
+    fn can_escape_strings() {
+        assert!(matches!(escape("plain é"), Cow::Borrowed("plain é")));
+        for s in [
+            "a \"b\" \\ c",
+            "\n\r\t\u{8}\u{c}\u{0}\u{1f}\u{7f}\u{85}",
+            "\u{2028}\u{2029}\u{feff}\u{fdd0}\u{fffe}",
+            "😀 \u{1fffe}\u{10ffff}",
+        ] {
+            assert_eq!(crate::internals::unescape(&escape(s)), s);
+        }
+        assert_eq!(escape("\u{1}\u{1fffe}😀"), r"\u0001\ud83f\udffe😀");
+    }
+}
@@ -0,0 +1,21 @@
+/// Options to resolve the substitutions of loaded documents with
+/// [`HoconLoader::resolve_partially`](struct.HoconLoader.html#method.resolve_partially)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolveOptions {
+    /// Keep the substitutions that can not be resolved yet, to resolve them once more
+    /// documents are loaded. By default `false`, such substitutions are handled as when
+    /// loading the document
+    pub allow_unresolved: bool,
+    /// Look for substitutions that are not in the documents in environment variables. By
+    /// default `true`
+    pub use_env: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            allow_unresolved: false,
+            use_env: true,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::HttpFetcher;
+use crate::Result;
+
+/// Asynchronous runtime running the IO of
+/// [`HoconLoader::load_file_async_with`](struct.HoconLoader.html#method.load_file_async_with)
+/// and [`HoconLoader::load_url_async_with`](struct.HoconLoader.html#method.load_url_async_with),
+/// so that loading does not depend on a given runtime. It is implemented for tokio by
+/// `TokioRuntime` with feature `tokio`, and for smol and async-std by `SmolRuntime` with feature
+/// `smol`.
+///
+/// The futures returned must start their work when they are created, without waiting to be
+/// polled, so that the URLs included by a document are fetched concurrently. For example, with
+/// a thread for each blocking call:
+///
+/// ```rust
+/// # use std::future::Future;
+/// use hocon::AsyncRuntime;
+///
+/// struct Threads;
+///
+/// impl AsyncRuntime for Threads {
+///     fn spawn_blocking<F, T>(&self, f: F) -> impl Future<Output = T>
+///     where
+///         F: FnOnce() -> T + Send + 'static,
+///         T: Send + 'static,
+///     {
+///         let thread = std::thread::spawn(f);
+///         // joining blocks the executor, a real runtime would wake the task instead
+///         async move { thread.join().unwrap() }
+///     }
+/// }
+/// ```
+///
+/// # Feature
+///
+/// This trait depends on feature `async`
+pub trait AsyncRuntime {
+    /// Run `f`, that blocks, without blocking the runtime, like on a thread pool
+    fn spawn_blocking<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static;
+
+    /// Body of the document at `url`, fetched by `fetcher`, the registered
+    /// [`HttpFetcher`](trait.HttpFetcher.html) or else the one of `reqwest` or `ureq`. By
+    /// default, it is run with [`spawn_blocking`](#tymethod.spawn_blocking)
+    fn fetch(
+        &self,
+        fetcher: Arc<dyn HttpFetcher + Send + Sync>,
+        url: String,
+    ) -> impl Future<Output = Result<String>> {
+        self.spawn_blocking(move || fetcher.fetch(&url))
+    }
+}
+
+/// The [tokio](https://tokio.rs) runtime, running blocking calls, and the fetches of URLs, on
+/// its blocking thread pool. Loading with it must be run within a tokio runtime
+///
+/// # Feature
+///
+/// This struct depends on feature `tokio`
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl AsyncRuntime for TokioRuntime {
+    fn spawn_blocking<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let task = tokio::task::spawn_blocking(f);
+        async move {
+            task.await
+                .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+        }
+    }
+}
+
+/// A runtime for [smol](https://github.com/smol-rs/smol) and
+/// [async-std](https://github.com/async-rs/async-std), running blocking calls on the thread
+/// pool of the [`blocking`](https://docs.rs/blocking) crate they use. It does not depend on
+/// the executor, and can be used with any of them
+///
+/// ```rust
+/// # use hocon::{Hocon, HoconLoader, SmolRuntime, Error};
+/// # fn main() -> Result<(), Error> {
+/// # futures_lite::future::block_on(async {
+/// let doc = HoconLoader::new()
+///     .load_file_async_with(&SmolRuntime, "tests/data/layers")
+///     .await?
+///     .hocon()?;
+///
+/// assert_eq!(doc["http"]["port"], Hocon::Integer(8080));
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+///
+/// # Feature
+///
+/// This struct depends on feature `smol`
+#[cfg(feature = "smol")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmolRuntime;
+
+#[cfg(feature = "smol")]
+impl AsyncRuntime for SmolRuntime {
+    fn spawn_blocking<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        blocking::unblock(f)
+    }
+}
@@ -767,6 +767,115 @@ where
     })
 }
 
+/// Deserialize any self-describing value as an `Hocon` document, for example to keep a
+/// free-form section of a typed configuration.
+///
+/// Unsigned integers that don't fit in an `i64` are kept as
+/// [`Hocon::Real`](../enum.Hocon.html#variant.Real).
+///
+/// # Example
+///
+/// ```rust
+/// # use serde::Deserialize;
+/// # use hocon::{Hocon, HoconLoader, Error};
+/// #[derive(Deserialize)]
+/// struct Configuration {
+///     name: String,
+///     extra: Hocon,
+/// }
+///
+/// # fn main() -> Result<(), Error> {
+/// let conf: Configuration = HoconLoader::new()
+///     .load_str(r#"{ name: app, extra { retries: 3, hosts: [a, b] } }"#)?
+///     .resolve()?;
+///
+/// assert_eq!(conf.extra["retries"], Hocon::Integer(3));
+/// assert_eq!(conf.extra["hosts"][1].as_string(), Some(String::from("b")));
+/// # Ok(())
+/// # }
+/// ```
+impl<'de> serde::de::Deserialize<'de> for Hocon {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(HoconVisitor)
+    }
+}
+
+struct HoconVisitor;
+
+impl<'de> serde::de::Visitor<'de> for HoconVisitor {
+    type Value = Hocon;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("any valid HOCON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::Boolean(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::Integer(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> std::result::Result<Hocon, E> {
+        Ok(i64::try_from(value)
+            .map(Hocon::Integer)
+            .unwrap_or(Hocon::Real(value as f64)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::Real(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::String(String::from(value)))
+    }
+
+    fn visit_string<E>(self, value: String) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::String(value))
+    }
+
+    fn visit_none<E>(self) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::Null)
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<Hocon, E> {
+        Ok(Hocon::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> std::result::Result<Hocon, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        serde::de::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Hocon, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Hocon::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Hocon, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut hash = linked_hash_map::LinkedHashMap::new();
+        while let Some((key, value)) = map.next_entry::<String, Hocon>()? {
+            hash.insert(key, value);
+        }
+        Ok(Hocon::Hash(hash))
+    }
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 mod tests {
@@ -790,6 +899,38 @@ mod tests {
         string: String,
     }
 
+    #[derive(Deserialize, Debug)]
+    struct WithHocon {
+        int: i64,
+        extra: Hocon,
+        others: Vec<Hocon>,
+    }
+
+    #[test]
+    fn can_deserialize_to_hocon() {
+        let doc = crate::hocon!({
+            int = 1,
+            extra { a = [1, 2.5, "b"], c { d = null, e = true } },
+            others = [{ f = 1 }, "g"],
+        });
+
+        let with_hocon: WithHocon = super::from_hocon(doc.clone()).expect("during test");
+        assert_eq!(with_hocon.int, 1);
+        assert_eq!(with_hocon.extra, doc["extra"]);
+        assert_eq!(
+            with_hocon.others,
+            vec![doc["others"][0].clone(), doc["others"][1].clone()]
+        );
+
+        let from_json: Hocon = serde_json::from_str(
+            r#"{ "a": [1, 2.5, "b"], "c": { "d": null, "e": true }, "u": 18446744073709551615 }"#,
+        )
+        .expect("during test");
+        assert_eq!(from_json["a"], doc["extra"]["a"]);
+        assert_eq!(from_json["c"], doc["extra"]["c"]);
+        assert_eq!(from_json["u"], Hocon::Real(u64::MAX as f64));
+    }
+
     #[test]
     fn can_deserialize_struct() {
         let mut hm = LinkedHashMap::new();
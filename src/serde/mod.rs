@@ -1,4 +1,5 @@
 pub mod de;
+mod ser;
 
 pub mod wrappers;
 
@@ -0,0 +1,97 @@
+use serde::ser::Error as _;
+use serde::ser::SerializeMap;
+use serde::ser::SerializeSeq;
+use serde::{Serialize, Serializer};
+
+use crate::Hocon;
+
+/// Serialize an `Hocon` document, for example to embed it in a larger serializable structure
+/// or write it to another format.
+///
+/// [`Hocon::Null`](enum.Hocon.html#variant.Null) is serialized as a unit, and numbers keep
+/// their type. Serializing a [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) fails with
+/// the error it contains.
+///
+/// # Example
+///
+/// ```rust
+/// # use hocon::{HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// let doc = HoconLoader::new().load_str(r#"{ a: 1, b: [true, null] }"#)?.hocon()?;
+///
+/// assert_eq!(
+///     serde_json::to_string(&doc).expect("during doctest"),
+///     r#"{"a":1,"b":[true,null]}"#
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Feature
+///
+/// This implementation depends on feature `serde-support`
+impl Serialize for Hocon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Hocon::Real(f) => serializer.serialize_f64(*f),
+            Hocon::Integer(i) => serializer.serialize_i64(*i),
+            Hocon::String(s) => serializer.serialize_str(s),
+            Hocon::Boolean(b) => serializer.serialize_bool(*b),
+            Hocon::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Hocon::Hash(hash) => {
+                let mut map = serializer.serialize_map(Some(hash.len()))?;
+                for (key, value) in hash {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Hocon::Null => serializer.serialize_unit(),
+            Hocon::BadValue(err) => Err(S::Error::custom(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct WithExtra {
+        name: &'static str,
+        extra: Hocon,
+    }
+
+    #[test]
+    fn can_serialize() {
+        let hocon = crate::hocon!({ a = [1, 2.5, "c"], d { e = null, f = false } });
+
+        assert_eq!(
+            serde_json::to_value(WithExtra {
+                name: "n",
+                extra: hocon
+            })
+            .expect("during test"),
+            serde_json::json!({
+                "name": "n",
+                "extra": { "a": [1, 2.5, "c"], "d": { "e": null, "f": false } }
+            })
+        );
+    }
+
+    #[test]
+    fn cant_serialize_bad_value() {
+        let mut hocon = crate::hocon!({ a = 1 });
+        hocon
+            .set("b", Hocon::BadValue(crate::Error::MissingKey))
+            .expect("during test");
+
+        let error = serde_json::to_string(&hocon).expect_err("during test");
+        assert_eq!(error.to_string(), crate::Error::MissingKey.to_string());
+    }
+}
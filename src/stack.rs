@@ -0,0 +1,168 @@
+use crate::HoconLoader;
+use crate::Result;
+use crate::document::{HoconDocument, Layer, Priority, Source};
+use crate::{FileRead, Hocon};
+
+/// A stack of overlays on top of documents loaded once, for example to apply per-request
+/// overrides to a base configuration.
+///
+/// Documents of the base [`HoconLoader`](struct.HoconLoader.html) are parsed only once.
+/// Overlays are merged after them, as if they had been loaded last, so they win over the
+/// base documents, but not over overrides like
+/// [`load_args`](struct.HoconLoader.html#method.load_args). Each resolution merges the
+/// base documents and the current overlays again.
+///
+/// # Usage
+///
+/// ```rust
+/// # use hocon::{hocon, ConfigStack, Hocon, HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// let mut stack = ConfigStack::new(
+///     HoconLoader::new().load_str(r#"{ tenant: default, limits.requests: 100 }"#)?,
+/// );
+///
+/// stack.push(hocon!({ tenant = "acme", limits.requests = 1000 }));
+/// assert_eq!(stack.hocon()?["limits"]["requests"], Hocon::Integer(1000));
+///
+/// stack.pop();
+/// assert_eq!(stack.hocon()?["tenant"].as_string(), Some(String::from("default")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigStack {
+    loader: HoconLoader,
+    base_layers: usize,
+}
+
+impl ConfigStack {
+    /// New `ConfigStack` with the documents of `loader` as its base
+    pub fn new(loader: HoconLoader) -> Self {
+        Self {
+            base_layers: loader.layers.len(),
+            loader,
+        }
+    }
+
+    /// Push an overlay built from code
+    pub fn push(&mut self, overlay: Hocon) {
+        self.push_layer(
+            Source::Hocon,
+            crate::internals::HoconInternal::from_hocon(overlay),
+        );
+    }
+
+    /// Push an overlay from a string containing an `Hocon` document
+    ///
+    /// # Errors
+    ///
+    /// Same as [`HoconLoader::load_str`](struct.HoconLoader.html#method.load_str). The
+    /// stack is unchanged on error
+    pub fn push_str(&mut self, s: &str) -> Result<()> {
+        let internal = self
+            .loader
+            .parsing_config()
+            .parse_str_to_internal(FileRead {
+                hocon: Some(String::from(s)),
+                ..Default::default()
+            })?;
+        self.push_layer(Source::Str, internal);
+        Ok(())
+    }
+
+    fn push_layer(&mut self, source: Source, internal: crate::internals::HoconInternal) {
+        self.loader.layers.push(Layer {
+            source,
+            priority: Priority::Document,
+            internal,
+            includes: vec![],
+            comments: vec![],
+        });
+    }
+
+    /// Remove the last overlay pushed. Returns `false` if there was no overlay left, the
+    /// base documents are never removed
+    pub fn pop(&mut self) -> bool {
+        if self.loader.layers.len() > self.base_layers {
+            self.loader.layers.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Number of overlays currently pushed
+    pub fn depth(&self) -> usize {
+        self.loader.layers.len() - self.base_layers
+    }
+
+    /// Resolve the base documents with the current overlays
+    ///
+    /// # Errors
+    ///
+    /// Same as [`HoconLoader::hocon`](struct.HoconLoader.html#method.hocon)
+    pub fn hocon(&self) -> Result<Hocon> {
+        self.loader.clone().hocon()
+    }
+
+    /// Resolve the base documents with the current overlays, keeping the steps recorded if
+    /// tracing was enabled on the base loader
+    ///
+    /// # Errors
+    ///
+    /// Same as [`HoconLoader::hocon`](struct.HoconLoader.html#method.hocon)
+    pub fn document(&self) -> Result<HoconDocument> {
+        self.loader.clone().document()
+    }
+
+    /// Deserialize the base documents with the current overlays to the target type
+    ///
+    /// # Errors
+    ///
+    /// Same as [`HoconLoader::resolve`](struct.HoconLoader.html#method.resolve)
+    #[cfg(feature = "serde-support")]
+    pub fn resolve<'de, T>(&self) -> Result<T>
+    where
+        T: ::serde::Deserialize<'de>,
+    {
+        self.loader.clone().resolve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_push_and_pop_overlays() {
+        let mut stack = ConfigStack::new(
+            HoconLoader::new()
+                .no_system()
+                .load_str(r#"{ a: 1, b { c: 2 }, d: ${b.c} }"#)
+                .expect("during test")
+                .load_args(vec!["e=5"])
+                .expect("during test"),
+        );
+        assert_eq!(stack.depth(), 0);
+
+        stack.push(crate::hocon!({ b.c = 3, e = 6 }));
+        stack.push_str(r#"{ a: 4, f: ${a} }"#).expect("during test");
+        assert_eq!(stack.depth(), 2);
+        let doc = stack.hocon().expect("during test");
+        assert_eq!(doc["a"], Hocon::Integer(4));
+        assert_eq!(doc["b"]["c"], Hocon::Integer(3));
+        assert_eq!(doc["e"], Hocon::Integer(5));
+        assert_eq!(doc["f"], Hocon::Integer(4));
+
+        assert!(stack.push_str("{ invalid").is_err());
+        assert_eq!(stack.depth(), 2);
+
+        assert!(stack.pop());
+        assert!(stack.pop());
+        assert!(!stack.pop());
+        assert_eq!(
+            stack.hocon().expect("during test"),
+            crate::hocon!({ a = 1, b { c = 2 }, d = 2, e = 5 })
+        );
+    }
+}
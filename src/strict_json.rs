@@ -0,0 +1,318 @@
+use crate::Error;
+use crate::Result;
+
+/// Check that `text` is a JSON document as in RFC 8259, without the syntax HOCON adds to JSON
+pub(crate) fn validate(text: &str) -> Result<()> {
+    let mut validator = Validator {
+        text: text.as_bytes(),
+        position: 0,
+    };
+    validator.skip_whitespace();
+    validator.value()?;
+    validator.skip_whitespace();
+    if validator.position < text.len() {
+        return Err(validator.invalid("content after the document"));
+    }
+    Ok(())
+}
+
+struct Validator<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl Validator<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    fn invalid(&self, message: &str) -> Error {
+        let before = &self.text[..self.position.min(self.text.len())];
+        let line_start = before
+            .iter()
+            .rposition(|c| *c == b'\n')
+            .map_or(0, |newline| newline + 1);
+        Error::InvalidJson {
+            line: before.iter().filter(|c| **c == b'\n').count() + 1,
+            column: String::from_utf8_lossy(&before[line_start..])
+                .chars()
+                .count()
+                + 1,
+            message: String::from(message),
+        }
+    }
+
+    /// Error for the character at the current position, naming the HOCON syntax it starts
+    fn unexpected(&self) -> Error {
+        let rest = &self.text[self.position..];
+        self.invalid(match rest.first() {
+            None => "unexpected end of document",
+            Some(b'#') => "comments are not JSON",
+            Some(b'/') if rest.starts_with(b"//") || rest.starts_with(b"/*") => {
+                "comments are not JSON"
+            }
+            Some(b'$') if rest.starts_with(b"${") => "substitutions are not JSON",
+            Some(b'=') => "`=` is not JSON, separate keys and values with `:`",
+            Some(b'\'') => "strings are quoted with `\"` in JSON",
+            _ => "unexpected character",
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn value(&mut self) -> Result<()> {
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true"),
+            Some(b'f') => self.literal("false"),
+            Some(b'n') => self.literal("null"),
+            Some(c) if c.is_ascii_alphabetic() => {
+                Err(self.invalid("unquoted strings are not JSON"))
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn object(&mut self) -> Result<()> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            match self.peek() {
+                Some(b'"') => self.string()?,
+                Some(c) if c.is_ascii_alphanumeric() || c == b'_' => {
+                    return Err(self.invalid("unquoted keys are not JSON"));
+                }
+                _ => return Err(self.unexpected()),
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b':') => self.position += 1,
+                Some(b'{') => return Err(self.invalid("keys need a `:` before their object")),
+                _ => return Err(self.unexpected()),
+            }
+            self.skip_whitespace();
+            self.value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        return Err(self.invalid("trailing commas are not JSON"));
+                    }
+                }
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'"') => return Err(self.invalid("fields are separated with `,`")),
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<()> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            self.value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        return Err(self.invalid("trailing commas are not JSON"));
+                    }
+                }
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<()> {
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.position += 1;
+                        }
+                        Some(b'u') => {
+                            self.position += 1;
+                            for _ in 0..4 {
+                                match self.peek() {
+                                    Some(c) if c.is_ascii_hexdigit() => self.position += 1,
+                                    _ => return Err(self.invalid("invalid unicode escape")),
+                                }
+                            }
+                        }
+                        _ => return Err(self.invalid("invalid escape")),
+                    }
+                }
+                Some(c) if c < 0x20 => {
+                    return Err(self.invalid("control characters must be escaped in strings"));
+                }
+                Some(_) => self.position += 1,
+                None => return Err(self.invalid("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<()> {
+        let digits = |validator: &mut Self| {
+            let start = validator.position;
+            while let Some(b'0'..=b'9') = validator.peek() {
+                validator.position += 1;
+            }
+            if validator.position == start {
+                Err(validator.invalid("invalid number"))
+            } else {
+                Ok(start)
+            }
+        };
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        let start = digits(self)?;
+        if self.text[start] == b'0' && self.position - start > 1 {
+            self.position = start;
+            return Err(self.invalid("numbers do not start with `0` in JSON"));
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            digits(self)?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.position += 1;
+            }
+            digits(self)?;
+        }
+        match self.peek() {
+            Some(c) if c.is_ascii_alphanumeric() || c == b'.' => {
+                Err(self.invalid("invalid number"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<()> {
+        let end = self.position + literal.len();
+        if self.text.get(self.position..end) == Some(literal.as_bytes())
+            && !self.text.get(end).is_some_and(u8::is_ascii_alphanumeric)
+        {
+            self.position = end;
+            Ok(())
+        } else {
+            Err(self.invalid("unquoted strings are not JSON"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(text: &str) -> Option<(usize, usize, String)> {
+        match validate(text) {
+            Err(Error::InvalidJson {
+                line,
+                column,
+                message,
+            }) => Some((line, column, message)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn can_validate_json() {
+        assert_eq!(
+            validate(
+                r#" { "a": [1, -2.5e3, 0, true, false, null], "b": { "c": "é\n\"" }, "": {} }"#
+            ),
+            Ok(())
+        );
+        assert_eq!(validate("[]"), Ok(()));
+        assert_eq!(validate("\"value\""), Ok(()));
+    }
+
+    #[test]
+    fn can_not_validate_hocon() {
+        assert_eq!(
+            message("{\n  a: 1\n}"),
+            Some((2, 3, String::from("unquoted keys are not JSON")))
+        );
+        assert_eq!(
+            message(r#"{ "a" = 1 }"#),
+            Some((
+                1,
+                7,
+                String::from("`=` is not JSON, separate keys and values with `:`")
+            ))
+        );
+        assert_eq!(
+            message("{\n  # comment\n  \"a\": 1\n}"),
+            Some((2, 3, String::from("comments are not JSON")))
+        );
+        assert_eq!(
+            message(r#"{ "a": ${b} }"#),
+            Some((1, 8, String::from("substitutions are not JSON")))
+        );
+        assert_eq!(
+            message(r#"{ "a": 1, }"#),
+            Some((1, 11, String::from("trailing commas are not JSON")))
+        );
+        assert_eq!(
+            message(r#"{ "a": localhost }"#),
+            Some((1, 8, String::from("unquoted strings are not JSON")))
+        );
+        assert_eq!(
+            message(r#"{ "a": 1 } { "b": 2 }"#),
+            Some((1, 12, String::from("content after the document")))
+        );
+        assert_eq!(
+            message(""),
+            Some((1, 1, String::from("unexpected end of document")))
+        );
+        assert_eq!(
+            message("[01]"),
+            Some((1, 2, String::from("numbers do not start with `0` in JSON")))
+        );
+    }
+}
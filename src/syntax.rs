@@ -0,0 +1,378 @@
+use std::ops::Range;
+
+use crate::document::Comments;
+
+/// A field of an HOCON document, as written
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Field {
+    /// Keys of the field, with those of the objects it is in
+    pub(crate) path: Vec<String>,
+    /// Comments written around the field
+    pub(crate) comments: Comments,
+    /// Where its value is written, with the braces or brackets of objects and arrays
+    pub(crate) value: Range<usize>,
+}
+
+/// Fields of HOCON document `text`, in the order they are written, not including those of
+/// objects in arrays. The braces around the root object, when there are some, are a field
+/// with an empty path
+pub(crate) fn fields(text: &str) -> Vec<Field> {
+    let mut scanner = Scanner {
+        text,
+        position: 0,
+        contexts: vec![Context::Object {
+            path: Some(vec![]),
+            field: None,
+        }],
+        fields: vec![],
+        pending: vec![],
+        last_field: None,
+        same_line: false,
+    };
+    scanner.scan();
+    scanner.fields
+}
+
+/// Comments of HOCON document `text`, with the path of the field each is attached to: the
+/// comments on the lines before a field are its leading comments, and a comment after its
+/// value on the same line is its trailing comment. Comments inside arrays, and after the
+/// last field of an object, are attached to no field
+pub(crate) fn comments(text: &str) -> Vec<(String, Comments)> {
+    let mut comments: Vec<(String, Comments)> = vec![];
+    for field in fields(text) {
+        if field.comments.leading.is_empty() && field.comments.trailing.is_none() {
+            continue;
+        }
+        let path = field.path.join(".");
+        // the comments of a field set several times are those of its last occurrence
+        comments.retain(|(existing, _)| *existing != path);
+        comments.push((path, field.comments));
+    }
+    comments
+}
+
+enum Context {
+    /// An object, with its path when it is not inside an array, and its field
+    Object {
+        path: Option<Vec<String>>,
+        field: Option<usize>,
+    },
+    /// An array, with the field it is the value of
+    Array { field: Option<usize> },
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+    contexts: Vec<Context>,
+    fields: Vec<Field>,
+    /// Comments read since the last field, for the next one
+    pending: Vec<String>,
+    /// Field a comment on the same line would be the trailing comment of
+    last_field: Option<usize>,
+    /// Whether something was read since the last new line
+    same_line: bool,
+}
+
+impl Scanner<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.position = (self.position + len).min(self.text.len());
+    }
+
+    fn scan(&mut self) {
+        while let Some(c) = self.peek() {
+            let rest = self.rest();
+            if c == '\n' {
+                self.same_line = false;
+                self.advance(1);
+            } else if c.is_whitespace() || c == ',' {
+                self.advance(c.len_utf8());
+            } else if c == '#' || rest.starts_with("//") {
+                self.comment();
+            } else if c == '}' || c == ']' {
+                self.close();
+            } else {
+                match self.contexts.last() {
+                    Some(Context::Object { path, .. }) => {
+                        let path = path.clone();
+                        self.field(path);
+                    }
+                    _ => {
+                        self.pending.clear();
+                        self.value(None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn comment(&mut self) {
+        let rest = self.rest();
+        let marker = if rest.starts_with('#') { 1 } else { 2 };
+        let end = rest.find('\n').unwrap_or(rest.len());
+        let comment = String::from(rest[marker..end].trim_end());
+        self.advance(end);
+        if let Some(Context::Object { path: Some(_), .. }) = self.contexts.last() {
+            match self.last_field {
+                Some(field) if self.same_line => {
+                    self.fields[field].comments.trailing = Some(comment);
+                }
+                _ => self.pending.push(comment),
+            }
+        }
+        self.same_line = false;
+    }
+
+    fn close(&mut self) {
+        self.advance(1);
+        self.pending.clear();
+        // the root object is never closed
+        if self.contexts.len() > 1
+            && let Some(Context::Object { field, .. } | Context::Array { field }) =
+                self.contexts.pop()
+        {
+            if let Some(field) = field {
+                self.fields[field].value.end = self.position;
+            }
+            self.last_field = field;
+        }
+        self.same_line = true;
+    }
+
+    /// A field of an object, or an include, at path `path` if it is not in an array
+    fn field(&mut self, path: Option<Vec<String>>) {
+        if self.peek() == Some('{') {
+            // the braces of the root object
+            let field = path.as_ref().filter(|path| path.is_empty()).map(|path| {
+                self.fields.push(Field {
+                    path: path.clone(),
+                    comments: Comments::default(),
+                    value: self.position..self.text.len(),
+                });
+                self.fields.len() - 1
+            });
+            self.advance(1);
+            self.contexts.push(Context::Object { path, field });
+            return;
+        }
+        if self.rest().starts_with("include")
+            && self.rest()["include".len()..].starts_with([' ', '\t'])
+        {
+            self.pending.clear();
+            let end = self.rest().find('\n').unwrap_or(self.rest().len());
+            self.advance(end);
+            return;
+        }
+
+        let mut keys = vec![];
+        loop {
+            keys.push(self.key());
+            if self.peek() == Some('.') {
+                self.advance(1);
+            } else {
+                break;
+            }
+        }
+        let field = path.map(|mut path| {
+            path.extend(keys);
+            let comments = Comments {
+                leading: std::mem::take(&mut self.pending),
+                trailing: None,
+            };
+            self.fields.push(Field {
+                path,
+                comments,
+                value: 0..0,
+            });
+            self.fields.len() - 1
+        });
+        self.last_field = field;
+        self.same_line = true;
+
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.advance(1);
+        }
+        let rest = self.rest();
+        if rest.starts_with("+=") {
+            self.advance(2);
+        } else if rest.starts_with([':', '=']) {
+            self.advance(1);
+        }
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.advance(1);
+        }
+        let start = self.position;
+        let opened = self.contexts.len();
+        self.value(field);
+        if let Some(field) = field {
+            let end = if self.contexts.len() > opened {
+                // until the closing brace or bracket
+                self.text.len()
+            } else {
+                start + self.text[start..self.position].trim_end().len()
+            };
+            self.fields[field].value = start..end;
+        }
+    }
+
+    /// A key of a path, quoted or not
+    fn key(&mut self) -> String {
+        if self.peek() == Some('"') {
+            let start = self.position;
+            self.quoted();
+            let quoted = &self.text[start..self.position];
+            let unquoted = quoted.strip_prefix('"').unwrap_or(quoted);
+            let unquoted = unquoted.strip_suffix('"').unwrap_or(unquoted);
+            return crate::internals::unescape(unquoted).into_owned();
+        }
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| {
+                c.is_whitespace() || matches!(c, '.' | ':' | '=' | '{' | '+' | '#' | ',' | '}')
+            })
+            .unwrap_or(rest.len());
+        let key = String::from(&rest[..end]);
+        // an unexpected character is skipped, not to scan it again
+        let skipped = rest.chars().next().map_or(0, char::len_utf8);
+        self.advance(if end == 0 { skipped } else { end });
+        key
+    }
+
+    /// Skip a quoted string, or a multi-line string
+    fn quoted(&mut self) {
+        let rest = self.rest();
+        if let Some(content) = rest.strip_prefix("\"\"\"") {
+            let end = content.find("\"\"\"").map_or(content.len(), |end| {
+                // the quotes closing a multi-line string can be preceded by more quotes
+                let quotes = content[end..]
+                    .find(|c| c != '"')
+                    .unwrap_or(content.len() - end);
+                end + quotes
+            });
+            self.advance(3 + end);
+            return;
+        }
+        let mut escaped = false;
+        for (index, c) in rest.char_indices().skip(1) {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    self.advance(index + 1);
+                    return;
+                }
+                '\n' => {
+                    self.advance(index);
+                    return;
+                }
+                _ => escaped = false,
+            }
+        }
+        self.advance(rest.len());
+    }
+
+    /// Skip a value, up to the end of its line, entering its objects and arrays
+    fn value(&mut self, field: Option<usize>) {
+        while let Some(c) = self.peek() {
+            let rest = self.rest();
+            match c {
+                '\n' | ',' | '}' | ']' | '#' => return,
+                '/' if rest.starts_with("//") => return,
+                '"' => self.quoted(),
+                '$' if rest.starts_with("${") => {
+                    let end = rest.find('}').map_or(rest.len(), |end| end + 1);
+                    self.advance(end);
+                }
+                '{' => {
+                    self.advance(1);
+                    // objects in arrays have no path
+                    let path = field.map(|field| self.fields[field].path.clone());
+                    self.contexts.push(Context::Object { path, field });
+                    self.same_line = true;
+                    return;
+                }
+                '[' => {
+                    self.advance(1);
+                    self.contexts.push(Context::Array { field });
+                    return;
+                }
+                c => self.advance(c.len_utf8()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comments(leading: &[&str], trailing: Option<&str>) -> Comments {
+        Comments {
+            leading: leading
+                .iter()
+                .map(|comment| String::from(*comment))
+                .collect(),
+            trailing: trailing.map(String::from),
+        }
+    }
+
+    #[test]
+    fn can_scan_comments() {
+        let scanned = super::comments(
+            r#"# the server
+// on two lines
+server {
+  host = "localhost # not a comment" # trailing
+  "port.number": 8080
+  # before the array
+  tags = [
+    # in the array
+    a, { b = 1 # in an object of the array
+    }
+  ] // after the array
+} # after the object
+include "other.conf" # after an include
+# before a dotted key
+a.b.c = ${x} """multi
+# line""" #after
+empty = {}
+#
+a.b.c = 1 # set again
+# at the end
+"#,
+        );
+        assert_eq!(
+            scanned,
+            vec![
+                (
+                    String::from("server"),
+                    comments(&[" the server", " on two lines"], Some(" after the object"))
+                ),
+                (
+                    String::from("server.host"),
+                    comments(&[], Some(" trailing"))
+                ),
+                (
+                    String::from("server.tags"),
+                    comments(&[" before the array"], Some(" after the array"))
+                ),
+                (String::from("a.b.c"), comments(&[""], Some(" set again"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_scan_comments_of_json() {
+        assert_eq!(
+            super::comments("{\n  // the port\n  \"port\": 8080\n}\n"),
+            vec![(String::from("port"), comments(&[" the port"], None))]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use ::toml::Value;
+use ::toml::value::Table;
+
+use crate::Hocon;
+
+/// Convert to a TOML value.
+///
+/// TOML has no null value: [`Hocon::Null`](enum.Hocon.html#variant.Null) and
+/// [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) are left out of tables and arrays,
+/// and convert to an empty table when they are not inside one. HOCON has no datetime type:
+/// strings are kept as [`Value::String`](https://docs.rs/toml/latest/toml/enum.Value.html),
+/// even when they contain a date, and can be parsed with
+/// `str::parse::<toml::value::Datetime>()` when a datetime is expected.
+///
+/// # Example
+///
+/// ```rust
+/// # use hocon::{HoconLoader, Error};
+/// # fn main() -> Result<(), Error> {
+/// let doc = HoconLoader::new()
+///     .load_str(r#"{ unset: null, package { name: app, version: "1.0" }, features: [a, null, b] }"#)?
+///     .hocon()?;
+///
+/// let toml = toml::Value::from(doc);
+/// assert_eq!(toml["package"]["name"].as_str(), Some("app"));
+/// assert_eq!(toml["features"].as_array().map(Vec::len), Some(2));
+/// assert_eq!(toml.get("unset"), None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Feature
+///
+/// This conversion depends on feature `toml-support`
+impl From<Hocon> for Value {
+    fn from(hocon: Hocon) -> Self {
+        to_toml(hocon).unwrap_or_else(|| Value::Table(Table::new()))
+    }
+}
+
+/// Convert from a TOML value.
+///
+/// HOCON has no datetime type: datetimes are converted to strings written as in TOML, like
+/// `1979-05-27T07:32:00Z`. This is how `.toml` files are loaded, with feature
+/// `toml-support`.
+///
+/// # Example
+///
+/// ```rust
+/// # use hocon::Hocon;
+/// let toml: toml::Value = toml::from_str(
+///     r#"
+/// [package]
+/// name = "app"
+/// released = 1979-05-27
+/// "#,
+/// )
+/// .expect("valid TOML");
+///
+/// let doc = Hocon::from(toml);
+/// assert_eq!(doc["package"]["name"].as_string(), Some(String::from("app")));
+/// assert_eq!(doc["package"]["released"].as_string(), Some(String::from("1979-05-27")));
+/// ```
+///
+/// # Feature
+///
+/// This conversion depends on feature `toml-support`
+impl From<Value> for Hocon {
+    fn from(toml: Value) -> Self {
+        match toml {
+            Value::String(s) => Hocon::String(s),
+            Value::Integer(i) => Hocon::Integer(i),
+            Value::Float(f) => Hocon::Real(f),
+            Value::Boolean(b) => Hocon::Boolean(b),
+            Value::Datetime(datetime) => Hocon::String(datetime.to_string()),
+            Value::Array(values) => Hocon::Array(values.into_iter().map(Hocon::from).collect()),
+            Value::Table(table) => Hocon::Hash(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (key, Hocon::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Document of TOML file content `text`
+pub(crate) fn parse(text: &str) -> crate::Result<Hocon> {
+    let table = text.parse::<Table>().map_err(|_| crate::Error::Parse)?;
+    Ok(Hocon::from(Value::Table(table)))
+}
+
+fn to_toml(hocon: Hocon) -> Option<Value> {
+    match hocon {
+        Hocon::Real(f) => Some(Value::Float(f)),
+        Hocon::Integer(i) => Some(Value::Integer(i)),
+        Hocon::String(s) => Some(Value::String(s)),
+        Hocon::Boolean(b) => Some(Value::Boolean(b)),
+        Hocon::Array(values) => Some(Value::Array(
+            values.into_iter().filter_map(to_toml).collect(),
+        )),
+        Hocon::Hash(hash) => Some(Value::Table(
+            hash.into_iter()
+                .filter_map(|(key, value)| to_toml(value).map(|value| (key, value)))
+                .collect(),
+        )),
+        Hocon::Null | Hocon::BadValue(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_to_toml() {
+        let hocon = crate::hocon!({
+            a = [1, null, 2.5],
+            b { c = "2024-01-01", d = true, e = null },
+            f = {},
+        });
+
+        let toml = Value::from(hocon);
+
+        assert_eq!(
+            toml["a"],
+            Value::Array(vec![Value::Integer(1), Value::Float(2.5)])
+        );
+        assert_eq!(toml["b"]["c"], Value::String(String::from("2024-01-01")));
+        assert_eq!(toml["b"]["d"], Value::Boolean(true));
+        assert_eq!(toml["b"].get("e"), None);
+        assert_eq!(toml["f"], Value::Table(Table::new()));
+        assert_eq!(
+            toml.as_table()
+                .expect("during test")
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "f"]
+        );
+        assert_eq!(Value::from(Hocon::Null), Value::Table(Table::new()));
+    }
+
+    #[test]
+    fn can_convert_from_toml() {
+        let doc = parse(
+            r#"
+title = "app"
+ratio = 0.5
+
+[server]
+port = 8080
+started = 1979-05-27T07:32:00Z
+hosts = ["a", "b"]
+
+[[backends]]
+name = "first"
+
+[[backends]]
+name = "second"
+"#,
+        )
+        .expect("during test");
+
+        assert_eq!(doc["title"], Hocon::String(String::from("app")));
+        assert_eq!(doc["ratio"], Hocon::Real(0.5));
+        assert_eq!(doc["server"]["port"], Hocon::Integer(8080));
+        assert_eq!(
+            doc["server"]["started"],
+            Hocon::String(String::from("1979-05-27T07:32:00Z"))
+        );
+        assert_eq!(doc["server"]["hosts"][1], Hocon::String(String::from("b")));
+        assert_eq!(
+            doc["backends"][1]["name"],
+            Hocon::String(String::from("second"))
+        );
+        assert_eq!(parse("a = ").err(), Some(crate::Error::Parse));
+    }
+}
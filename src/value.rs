@@ -107,7 +107,7 @@ impl Index<usize> for Hocon {
                     .keys()
                     .filter_map(|k| k.parse::<usize>().ok().map(|v| (k, v)))
                     .collect::<Vec<_>>();
-                keys_as_usize.sort_by(|(_, v0), (_, v1)| v0.cmp(v1));
+                keys_as_usize.sort_by_key(|(_, v)| *v);
                 keys_as_usize
                     .get(idx)
                     .and_then(|(k, _)| hash.get(*k))
@@ -118,6 +118,52 @@ impl Index<usize> for Hocon {
     }
 }
 
+impl From<bool> for Hocon {
+    fn from(b: bool) -> Self {
+        Hocon::Boolean(b)
+    }
+}
+impl From<i32> for Hocon {
+    fn from(i: i32) -> Self {
+        Hocon::Integer(i64::from(i))
+    }
+}
+impl From<i64> for Hocon {
+    fn from(i: i64) -> Self {
+        Hocon::Integer(i)
+    }
+}
+impl From<f64> for Hocon {
+    fn from(f: f64) -> Self {
+        Hocon::Real(f)
+    }
+}
+impl From<&str> for Hocon {
+    fn from(s: &str) -> Self {
+        Hocon::String(String::from(s))
+    }
+}
+impl From<String> for Hocon {
+    fn from(s: String) -> Self {
+        Hocon::String(s)
+    }
+}
+impl<T: Into<Hocon>> From<Vec<T>> for Hocon {
+    fn from(values: Vec<T>) -> Self {
+        Hocon::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+impl<T: Into<Hocon>> From<Option<T>> for Hocon {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Hocon::Null)
+    }
+}
+impl From<LinkedHashMap<String, Hocon>> for Hocon {
+    fn from(hash: LinkedHashMap<String, Hocon>) -> Self {
+        Hocon::Hash(hash)
+    }
+}
+
 impl Hocon {
     /// Try to cast a value as a `f64` value
     pub fn as_f64(&self) -> Option<f64> {
@@ -173,6 +219,325 @@ impl Hocon {
     }
 }
 
+impl Hocon {
+    /// Looks up a value by a [JSON Pointer](https://tools.ietf.org/html/rfc6901), as
+    /// `serde_json::Value::pointer` does.
+    ///
+    /// A JSON Pointer is a string of reference tokens each prefixed by `/`, with `~1`
+    /// escaping `/` and `~0` escaping `~`. The empty string points to the whole document.
+    /// Array elements are referenced by their index, following the same
+    /// [numerically-indexed objects](https://github.com/lightbend/config/blob/master/HOCON.md#conversion-of-numerically-indexed-objects-to-arrays)
+    /// rules as indexing with a `usize`.
+    ///
+    /// Returns `None` if the pointer is invalid or if the value is not found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ a: { b: [ { c: 7 } ] }, "d/e": true }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(doc.pointer("/a/b/0/c"), Some(&Hocon::Integer(7)));
+    /// assert_eq!(doc.pointer("/d~1e"), Some(&Hocon::Boolean(true)));
+    /// assert_eq!(doc.pointer("/a/missing"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Hocon> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .try_fold(self, |target, token| {
+                let found = match target {
+                    Hocon::Hash(hash) => match hash.get(&token) {
+                        Some(value) => value,
+                        None => &target[parse_index(&token)?],
+                    },
+                    Hocon::Array(_) => &target[parse_index(&token)?],
+                    _ => return None,
+                };
+                match found {
+                    Hocon::BadValue(_) => None,
+                    value => Some(value),
+                }
+            })
+    }
+}
+
+impl Hocon {
+    /// Extracts the object at `path` as its own document, like Typesafe Config's `getConfig`.
+    ///
+    /// The path is a HOCON path expression (`akka.http`, `a."b.c"`). Substitutions have
+    /// already been resolved against the whole document when it was loaded, so the
+    /// extracted object is self-contained and can be handed to code that only knows about
+    /// its own section of the configuration.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::MissingKey`](enum.Error.html#variant.MissingKey) if there is no value at
+    ///   this path
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if the value at this path
+    ///   is not an object
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ port: 8080, akka.http { port: ${port} } }"#)?
+    ///     .hocon()?;
+    ///
+    /// let http = doc.get_config("akka.http")?;
+    /// assert_eq!(http["port"], Hocon::Integer(8080));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_config(&self, path: &str) -> Result<Hocon, crate::Error> {
+        match self.at_path(&crate::helper::split_path(path)) {
+            Hocon::Hash(hash) => Ok(Hocon::Hash(hash.clone())),
+            Hocon::BadValue(crate::Error::MissingKey) => Err(crate::Error::MissingKey),
+            _ => Err(crate::Error::InvalidKey),
+        }
+    }
+
+    /// Sets `value` at `path`, creating intermediate objects as needed.
+    ///
+    /// The path is a HOCON path expression. As when a key is duplicated in a document, an
+    /// intermediate value that is not an object is replaced by an object. Numeric keys
+    /// address existing elements of an array, or append to it when equal to its length.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if a numeric key is past
+    ///   the end of an array
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut doc = HoconLoader::new().load_str(r#"{ a: { b: 1 } }"#)?.hocon()?;
+    ///
+    /// doc.set("a.b", Hocon::Integer(2))?;
+    /// doc.set("x.y.z", Hocon::Boolean(true))?;
+    /// assert_eq!(doc["a"]["b"], Hocon::Integer(2));
+    /// assert_eq!(doc["x"]["y"]["z"], Hocon::Boolean(true));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set(&mut self, path: &str, value: Hocon) -> Result<(), crate::Error> {
+        self.set_at(&crate::helper::split_path(path), value)
+    }
+
+    pub(crate) fn set_at<S: AsRef<str>>(
+        &mut self,
+        path: &[S],
+        value: Hocon,
+    ) -> Result<(), crate::Error> {
+        *self.entry_at(path)? = value;
+        Ok(())
+    }
+
+    /// Removes the value at `path` and returns it. Removing an element of an array shifts
+    /// the elements after it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut doc = HoconLoader::new().load_str(r#"{ a: { b: 1, c: 2 }, l: [1, 2, 3] }"#)?.hocon()?;
+    ///
+    /// assert_eq!(doc.remove("a.b"), Some(Hocon::Integer(1)));
+    /// assert_eq!(doc.remove("l.0"), Some(Hocon::Integer(1)));
+    /// assert_eq!(doc.remove("x"), None);
+    /// assert_eq!(doc["a"]["b"], Hocon::BadValue(Error::MissingKey));
+    /// assert_eq!(doc["l"][0], Hocon::Integer(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&mut self, path: &str) -> Option<Hocon> {
+        self.remove_at(&crate::helper::split_path(path))
+    }
+
+    pub(crate) fn remove_at<S: AsRef<str>>(&mut self, path: &[S]) -> Option<Hocon> {
+        let (key, parents) = path.split_last()?;
+        let parent = parents
+            .iter()
+            .try_fold(self, |target, key| target.child_mut(key.as_ref()))?;
+        match parent {
+            Hocon::Hash(hash) => hash.remove(key.as_ref()),
+            Hocon::Array(vec) => match key.as_ref().parse::<usize>() {
+                Ok(idx) if idx < vec.len() => Some(vec.remove(idx)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Merges `other` into the value at `path`, creating intermediate objects as needed.
+    ///
+    /// Objects are merged as when the same key is set several times in a document: keys of
+    /// `other` are merged recursively into the existing object, and their values win when
+    /// they are not both objects. Any other value is replaced by `other`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidKey`](enum.Error.html#variant.InvalidKey) if a numeric key is past
+    ///   the end of an array
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut doc = HoconLoader::new().load_str(r#"{ db { host: localhost, port: 5432 } }"#)?.hocon()?;
+    /// let overrides = HoconLoader::new().load_str(r#"{ port: 6543, user: admin }"#)?.hocon()?;
+    ///
+    /// doc.merge_at("db", overrides)?;
+    /// assert_eq!(doc["db"]["host"].as_string(), Some(String::from("localhost")));
+    /// assert_eq!(doc["db"]["port"], Hocon::Integer(6543));
+    /// assert_eq!(doc["db"]["user"].as_string(), Some(String::from("admin")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_at(&mut self, path: &str, other: Hocon) -> Result<(), crate::Error> {
+        self.entry_at(&crate::helper::split_path(path))?
+            .deep_merge(other);
+        Ok(())
+    }
+
+    /// Merges this document on top of `fallback`: objects present in both are merged
+    /// recursively, and any other value of this document wins over the one of `fallback`.
+    /// Merged objects keep the order of the keys of this document, followed by the keys only
+    /// in `fallback`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{Hocon, HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let defaults = HoconLoader::new()
+    ///     .load_str(r#"{ http { host: localhost, port: 80 }, workers: 4 }"#)?
+    ///     .hocon()?;
+    /// let overrides = HoconLoader::new().load_str(r#"{ http.port: 8080 }"#)?.hocon()?;
+    ///
+    /// let conf = overrides.with_fallback(defaults);
+    /// assert_eq!(conf["http"]["host"].as_string(), Some(String::from("localhost")));
+    /// assert_eq!(conf["http"]["port"], Hocon::Integer(8080));
+    /// assert_eq!(conf["workers"], Hocon::Integer(4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_fallback(self, fallback: Hocon) -> Hocon {
+        match (self, fallback) {
+            (Hocon::Hash(mut hash), Hocon::Hash(fallback)) => {
+                for (key, fallback_value) in fallback {
+                    match hash.get_mut(&key) {
+                        Some(value) => {
+                            *value =
+                                std::mem::replace(value, Hocon::Null).with_fallback(fallback_value);
+                        }
+                        None => {
+                            hash.insert(key, fallback_value);
+                        }
+                    }
+                }
+                Hocon::Hash(hash)
+            }
+            (value, _) => value,
+        }
+    }
+
+    /// Merge `other` into `self`, `other` winning for keys present in both
+    pub(crate) fn deep_merge(&mut self, other: Hocon) {
+        match (self, other) {
+            (Hocon::Hash(hash), Hocon::Hash(other)) => {
+                for (key, value) in other {
+                    match hash.get_mut(&key) {
+                        Some(existing) => existing.deep_merge(value),
+                        None => {
+                            hash.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (target, other) => *target = other,
+        }
+    }
+
+    /// Follow a list of keys, creating intermediate objects as needed, and return the value
+    /// at the end of the path. A new value is `Hocon::Null`
+    fn entry_at<S: AsRef<str>>(&mut self, path: &[S]) -> Result<&mut Hocon, crate::Error> {
+        let (key, rest) = match path.split_first() {
+            None => return Ok(self),
+            Some(split) => split,
+        };
+        let idx = key
+            .as_ref()
+            .parse::<usize>()
+            .ok()
+            .filter(|_| matches!(self, Hocon::Array(_)));
+        match (self, idx) {
+            (Hocon::Array(vec), Some(idx)) => match idx.cmp(&vec.len()) {
+                std::cmp::Ordering::Less => vec[idx].entry_at(rest),
+                std::cmp::Ordering::Equal => {
+                    vec.push(Hocon::Null);
+                    vec[idx].entry_at(rest)
+                }
+                std::cmp::Ordering::Greater => Err(crate::Error::InvalidKey),
+            },
+            (Hocon::Hash(hash), _) => hash
+                .entry(key.as_ref().to_string())
+                .or_insert(Hocon::Null)
+                .entry_at(rest),
+            (target, _) => {
+                *target = Hocon::Hash(LinkedHashMap::new());
+                target.entry_at(path)
+            }
+        }
+    }
+
+    fn child_mut(&mut self, key: &str) -> Option<&mut Hocon> {
+        match self {
+            Hocon::Hash(hash) => hash.get_mut(key),
+            Hocon::Array(vec) => key.parse::<usize>().ok().and_then(|idx| vec.get_mut(idx)),
+            _ => None,
+        }
+    }
+
+    /// Follow a list of keys, indexing arrays with numeric keys
+    pub(crate) fn at_path<S: AsRef<str>>(&self, path: &[S]) -> &Hocon {
+        path.iter().fold(self, |target, key| match target {
+            Hocon::Array(_) => match key.as_ref().parse::<usize>() {
+                Ok(idx) => &target[idx],
+                Err(_) => &INVALID_KEY,
+            },
+            Hocon::BadValue(_) => target,
+            _ => &target[key.as_ref()],
+        })
+    }
+}
+
+/// Parses an array index from a JSON Pointer token, rejecting leading zeros and signs
+fn parse_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() != 1) {
+        return None;
+    }
+    token.parse().ok()
+}
+
 mod unit_format {
     use nom::IResult;
     use nom::Parser;
@@ -741,6 +1106,165 @@ mod tests {
         assert_eq!(val["2"], Hocon::Integer(7));
     }
 
+    #[test]
+    fn access_with_pointer() {
+        let mut inner = LinkedHashMap::new();
+        inner.insert(String::from("c"), Hocon::Integer(5));
+        let mut hm = LinkedHashMap::new();
+        hm.insert(
+            String::from("a"),
+            Hocon::Array(vec![Hocon::Null, Hocon::Hash(inner)]),
+        );
+        hm.insert(String::from("b/c"), Hocon::Integer(6));
+        hm.insert(String::from("d~e"), Hocon::Integer(7));
+        hm.insert(String::from(""), Hocon::Integer(8));
+        let val = Hocon::Hash(hm);
+
+        assert_eq!(val.pointer(""), Some(&val));
+        assert_eq!(val.pointer("/a/0"), Some(&Hocon::Null));
+        assert_eq!(val.pointer("/a/1/c"), Some(&Hocon::Integer(5)));
+        assert_eq!(val.pointer("/b~1c"), Some(&Hocon::Integer(6)));
+        assert_eq!(val.pointer("/d~0e"), Some(&Hocon::Integer(7)));
+        assert_eq!(val.pointer("/"), Some(&Hocon::Integer(8)));
+        assert_eq!(val.pointer("a"), None);
+        assert_eq!(val.pointer("/a/01"), None);
+        assert_eq!(val.pointer("/a/2"), None);
+        assert_eq!(val.pointer("/a/1/c/d"), None);
+        assert_eq!(val.pointer("/missing"), None);
+    }
+
+    #[test]
+    fn get_config_at_path() {
+        let mut inner = LinkedHashMap::new();
+        inner.insert(String::from("c"), Hocon::Integer(5));
+        let mut hm = LinkedHashMap::new();
+        hm.insert(String::from("a"), Hocon::Hash(inner.clone()));
+        hm.insert(
+            String::from("b.c"),
+            Hocon::Array(vec![Hocon::Hash(inner.clone())]),
+        );
+        hm.insert(String::from("d"), Hocon::Integer(6));
+        let val = Hocon::Hash(hm);
+
+        assert_eq!(val.get_config("a"), Ok(Hocon::Hash(inner.clone())));
+        assert_eq!(val.get_config(r#""b.c".0"#), Ok(Hocon::Hash(inner)));
+        assert_eq!(val.get_config("d"), Err(crate::Error::InvalidKey));
+        assert_eq!(val.get_config("a.c.e"), Err(crate::Error::InvalidKey));
+        assert_eq!(val.get_config("e"), Err(crate::Error::MissingKey));
+        assert_eq!(val.get_config("e.f"), Err(crate::Error::MissingKey));
+    }
+
+    #[test]
+    fn set_at_path() {
+        let mut val = Hocon::Hash(LinkedHashMap::new());
+
+        assert_eq!(val.set("a.b", Hocon::Integer(5)), Ok(()));
+        assert_eq!(val["a"]["b"], Hocon::Integer(5));
+
+        assert_eq!(val.set("a.b.c", Hocon::Integer(6)), Ok(()));
+        assert_eq!(val["a"]["b"]["c"], Hocon::Integer(6));
+
+        assert_eq!(val.set("l", Hocon::Array(vec![Hocon::Integer(1)])), Ok(()));
+        assert_eq!(val.set("l.0", Hocon::Integer(2)), Ok(()));
+        assert_eq!(val.set("l.1.m", Hocon::Integer(3)), Ok(()));
+        assert_eq!(
+            val.set("l.3", Hocon::Integer(4)),
+            Err(crate::Error::InvalidKey)
+        );
+        assert_eq!(val["l"][0], Hocon::Integer(2));
+        assert_eq!(val["l"][1]["m"], Hocon::Integer(3));
+
+        assert_eq!(val.set(r#""x.y""#, Hocon::Null), Ok(()));
+        assert_eq!(val["x.y"], Hocon::Null);
+        match val {
+            Hocon::Hash(hash) => {
+                assert_eq!(hash.keys().collect::<Vec<_>>(), vec!["a", "l", "x.y"])
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn remove_at_path() {
+        let mut val = Hocon::Hash(LinkedHashMap::new());
+        assert_eq!(val.set("a.b", Hocon::Integer(5)), Ok(()));
+        assert_eq!(val.set("a.c", Hocon::Integer(6)), Ok(()));
+        assert_eq!(
+            val.set(
+                "l",
+                Hocon::Array(vec![Hocon::Integer(1), Hocon::Integer(2)])
+            ),
+            Ok(())
+        );
+
+        assert_eq!(val.remove("a.b"), Some(Hocon::Integer(5)));
+        assert_eq!(val.remove("a.b"), None);
+        assert_eq!(val.remove("a.c.d"), None);
+        assert_eq!(val.remove("l.2"), None);
+        assert_eq!(val.remove("l.x"), None);
+        assert_eq!(val.remove("l.0"), Some(Hocon::Integer(1)));
+        assert_eq!(val["l"], Hocon::Array(vec![Hocon::Integer(2)]));
+        assert_eq!(val["a"]["c"], Hocon::Integer(6));
+        assert!(val.remove("a").is_some());
+        assert_eq!(val["a"], Hocon::BadValue(crate::Error::MissingKey));
+    }
+
+    #[test]
+    fn merge_at_path() {
+        let mut val = Hocon::Hash(LinkedHashMap::new());
+        assert_eq!(val.set("a.b", Hocon::Integer(5)), Ok(()));
+        assert_eq!(val.set("a.c.d", Hocon::Integer(6)), Ok(()));
+
+        let mut other = Hocon::Hash(LinkedHashMap::new());
+        assert_eq!(other.set("c.e", Hocon::Integer(7)), Ok(()));
+        assert_eq!(other.set("b", Hocon::Integer(8)), Ok(()));
+        assert_eq!(other.set("f", Hocon::Integer(9)), Ok(()));
+
+        assert_eq!(val.merge_at("a", other.clone()), Ok(()));
+        assert_eq!(val["a"]["b"], Hocon::Integer(8));
+        assert_eq!(val["a"]["c"]["d"], Hocon::Integer(6));
+        assert_eq!(val["a"]["c"]["e"], Hocon::Integer(7));
+        assert_eq!(val["a"]["f"], Hocon::Integer(9));
+        match &val["a"] {
+            Hocon::Hash(hash) => assert_eq!(hash.keys().collect::<Vec<_>>(), vec!["b", "c", "f"]),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(val.merge_at("a.b", other.clone()), Ok(()));
+        assert_eq!(val["a"]["b"], other);
+        assert_eq!(val.merge_at("x.y", Hocon::Integer(1)), Ok(()));
+        assert_eq!(val["x"]["y"], Hocon::Integer(1));
+        assert_eq!(val.merge_at("a.c.d", Hocon::Integer(10)), Ok(()));
+        assert_eq!(val["a"]["c"]["d"], Hocon::Integer(10));
+    }
+
+    #[test]
+    fn merge_with_fallback() {
+        let overrides = crate::hocon!({ a { b = 1 }, c = [1], d = "e", f = { g = 2 } });
+        let fallback = crate::hocon!({ a { b = 2, h = 3 }, c = [2, 3], d { i = 4 }, f = 5, j = 6 });
+
+        assert_eq!(
+            overrides.with_fallback(fallback),
+            crate::hocon!({ a { b = 1, h = 3 }, c = [1], d = "e", f = { g = 2 }, j = 6 })
+        );
+        assert_eq!(
+            Hocon::Integer(1).with_fallback(crate::hocon!({ a = 1 })),
+            Hocon::Integer(1)
+        );
+
+        let merged = crate::hocon!({ b = 1, a { d = 2, c = 3 } })
+            .with_fallback(crate::hocon!({ a { c = 4, e = 5 }, f = 6, b = 7 }));
+        assert_eq!(
+            merged,
+            crate::hocon!({ b = 1, a { d = 2, c = 3, e = 5 }, f = 6 })
+        );
+        let keys = match &merged {
+            Hocon::Hash(hash) => hash.keys().map(String::as_str).collect(),
+            _ => vec![],
+        };
+        assert_eq!(keys, vec!["b", "a", "f"]);
+    }
+
     #[test]
     fn access_on_bytes() {
         let val = Hocon::Array(vec![
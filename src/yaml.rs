@@ -0,0 +1,222 @@
+use linked_hash_map::LinkedHashMap;
+use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
+
+use crate::Error;
+use crate::Hocon;
+use crate::Result;
+use crate::internals::HoconInternal;
+
+/// Document of YAML stream `text`, each of its documents merged over the previous ones as
+/// HOCON documents are. Anchors and aliases are resolved, and an empty document is an empty
+/// object
+pub(crate) fn parse(text: &str) -> Result<HoconInternal> {
+    let documents = YamlLoader::load_from_str(text).map_err(|_| Error::Parse)?;
+    documents
+        .into_iter()
+        .try_fold(HoconInternal::empty(), |merged, document| {
+            match to_hocon(document)? {
+                hash @ Hocon::Hash(_) => Ok(merged.add(HoconInternal::from_hocon(hash))),
+                Hocon::Null => Ok(merged),
+                // as for JSON, the root of a document is an object
+                _ => Err(Error::Parse),
+            }
+        })
+}
+
+fn to_hocon(yaml: Yaml) -> Result<Hocon> {
+    Ok(match yaml {
+        Yaml::Real(_) => Hocon::Real(yaml.as_f64().ok_or(Error::Parse)?),
+        Yaml::Integer(i) => Hocon::Integer(i),
+        Yaml::String(s) => Hocon::String(s),
+        Yaml::Boolean(b) => Hocon::Boolean(b),
+        Yaml::Array(values) => Hocon::Array(
+            values
+                .into_iter()
+                .map(to_hocon)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Yaml::Hash(hash) => {
+            let mut converted = LinkedHashMap::new();
+            for (key, value) in hash {
+                converted.insert(key_of(key)?, to_hocon(value)?);
+            }
+            Hocon::Hash(converted)
+        }
+        Yaml::Null => Hocon::Null,
+        Yaml::Alias(_) | Yaml::BadValue => return Err(Error::Parse),
+    })
+}
+
+impl Hocon {
+    /// Convert to a YAML document, like the manifests of Kubernetes.
+    ///
+    /// * [`Hocon::BadValue`](enum.Hocon.html#variant.BadValue) are left out of mappings and
+    ///   sequences, and convert to `null` when they are not inside one
+    /// * strings that would be read as another type, like `"true"` or `"8080"`, are quoted
+    /// * strings on several lines are written as literal blocks
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hocon::{HoconLoader, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let doc = HoconLoader::new()
+    ///     .load_str(r#"{ metadata { name: app, labels { tier: "1" } }, spec.replicas: 3 }"#)?
+    ///     .hocon()?;
+    ///
+    /// assert_eq!(
+    ///     doc.to_yaml_string(),
+    ///     "metadata:\n  name: app\n  labels:\n    tier: \"1\"\nspec:\n  replicas: 3\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Feature
+    ///
+    /// This method depends on feature `yaml-support`
+    pub fn to_yaml_string(&self) -> String {
+        let mut yaml = String::new();
+        let mut emitter = YamlEmitter::new(&mut yaml);
+        emitter.multiline_strings(true);
+        emitter
+            .dump(&self.to_yaml().unwrap_or(Yaml::Null))
+            .expect("converted values can be written");
+        // a single document does not need its start marker
+        let mut yaml = match yaml.strip_prefix("---\n") {
+            Some(document) => String::from(document),
+            None => yaml,
+        };
+        yaml.push('\n');
+        yaml
+    }
+
+    fn to_yaml(&self) -> Option<Yaml> {
+        match self {
+            Hocon::Boolean(b) => Some(Yaml::Boolean(*b)),
+            Hocon::Integer(i) => Some(Yaml::Integer(*i)),
+            Hocon::Real(f) => Some(Yaml::Real(match *f {
+                f if f.is_nan() => String::from(".nan"),
+                f64::INFINITY => String::from(".inf"),
+                f64::NEG_INFINITY => String::from("-.inf"),
+                // with a `.0` when it is whole, to be read back as a float
+                f => format!("{f:?}"),
+            })),
+            Hocon::String(s) => Some(Yaml::String(s.clone())),
+            Hocon::Array(values) => Some(Yaml::Array(
+                values.iter().filter_map(Hocon::to_yaml).collect(),
+            )),
+            Hocon::Hash(hash) => Some(Yaml::Hash(
+                hash.iter()
+                    .filter_map(|(key, value)| {
+                        value
+                            .to_yaml()
+                            .map(|value| (Yaml::String(key.clone()), value))
+                    })
+                    .collect(),
+            )),
+            Hocon::Null => Some(Yaml::Null),
+            Hocon::BadValue(_) => None,
+        }
+    }
+}
+
+/// Key of a mapping, that HOCON only has as strings
+fn key_of(key: Yaml) -> Result<String> {
+    match key {
+        Yaml::String(s) | Yaml::Real(s) => Ok(s),
+        Yaml::Integer(i) => Ok(i.to_string()),
+        Yaml::Boolean(b) => Ok(b.to_string()),
+        Yaml::Null => Ok(String::from("null")),
+        _ => Err(Error::Parse),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_to_hocon(text: &str) -> Hocon {
+        crate::HoconLoader::new()
+            .load_bytes_as(text.as_bytes(), crate::Format::Yaml)
+            .and_then(crate::HoconLoader::hocon)
+            .expect("during test")
+    }
+
+    #[test]
+    fn can_parse_yaml() {
+        let doc = parse_to_hocon(
+            r#"
+defaults: &defaults
+  timeout: 30
+  retries: 3
+service:
+  limits: *defaults
+  name: billing
+  ratio: 0.75
+  hosts:
+    - a.local
+    - b.local
+  ports: [8080, 8443]
+  description: |
+    multi
+    line
+  unset: ~
+  200: ok
+"#,
+        );
+        assert_eq!(doc["service"]["limits"]["timeout"], Hocon::Integer(30));
+        assert_eq!(
+            doc["service"]["name"],
+            Hocon::String(String::from("billing"))
+        );
+        assert_eq!(doc["service"]["ratio"], Hocon::Real(0.75));
+        assert_eq!(
+            doc["service"]["hosts"][1],
+            Hocon::String(String::from("b.local"))
+        );
+        assert_eq!(doc["service"]["ports"][0], Hocon::Integer(8080));
+        assert_eq!(
+            doc["service"]["description"],
+            Hocon::String(String::from("multi\nline\n"))
+        );
+        assert_eq!(doc["service"]["unset"], Hocon::Null);
+        assert_eq!(doc["service"]["200"], Hocon::String(String::from("ok")));
+    }
+
+    #[test]
+    fn can_merge_yaml_documents() {
+        let doc = parse_to_hocon("a: { b: 1, c: 2 }\n---\n---\na: { b: 3 }\n");
+        assert_eq!(doc["a"]["b"], Hocon::Integer(3));
+        assert_eq!(doc["a"]["c"], Hocon::Integer(2));
+    }
+
+    #[test]
+    fn can_convert_to_yaml() {
+        let mut hocon = crate::hocon!({
+            a = [1, 2.0, 2.5, f64::INFINITY],
+            b { c = null, d = "true", e = "multi\nline", f = "" },
+            g = {},
+        });
+        hocon
+            .set("b.h", Hocon::BadValue(Error::MissingKey))
+            .expect("during test");
+
+        let yaml = hocon.to_yaml_string();
+        assert_eq!(
+            yaml,
+            "a:\n  - 1\n  - 2.0\n  - 2.5\n  - .inf\nb:\n  c: ~\n  d: \"true\"\n  e: |-\n    multi\n    line\n  f: \"\"\ng: {}\n"
+        );
+        // read back without the bad value
+        hocon.remove("b.h");
+        assert_eq!(parse_to_hocon(&yaml), hocon);
+        assert_eq!(Hocon::BadValue(Error::MissingKey).to_yaml_string(), "~\n");
+    }
+
+    #[test]
+    fn can_not_parse_invalid_yaml() {
+        assert_eq!(parse("a: [1, 2").err(), Some(Error::Parse));
+        assert_eq!(parse("- 1\n- 2\n").err(), Some(Error::Parse));
+        assert_eq!(parse("? [a, b]\n: 1\n").err(), Some(Error::Parse));
+    }
+}
@@ -24,3 +24,3164 @@ fn missing_file() {
 
     assert!(dbg!(doc).is_err());
 }
+
+#[test]
+fn trace_merge_steps() {
+    use hocon::document::{MergeAction, Resolution, Source};
+
+    let doc = hocon::HoconLoader::new()
+        .trace()
+        .load_file("tests/data/basic.conf")
+        .expect("during test")
+        .load_str(r#"{ a: { b: ${z} }, d: ${?missing}, u: ${missing} }"#)
+        .expect("during test")
+        .load_str(r#"{ z: 5 }"#)
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    let first = dbg!(&doc.trace()[0]);
+    assert_eq!(first.action, MergeAction::Set);
+    assert!(matches!(
+        &first.source,
+        Some(Source::File(path)) if path.ends_with("tests/data/basic.conf")
+    ));
+
+    assert_eq!(
+        doc.trace_for("a.b")
+            .into_iter()
+            .map(|step| step.action.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            MergeAction::Set,
+            MergeAction::Resolve {
+                substitution: String::from("z"),
+                resolution: Resolution::Document
+            }
+        ]
+    );
+    assert_eq!(doc.trace_for("d")[1].action, MergeAction::Override);
+    assert_eq!(
+        doc.trace_for("d")[2].action,
+        MergeAction::Resolve {
+            substitution: String::from("missing"),
+            resolution: Resolution::PreviousValue
+        }
+    );
+    assert_eq!(
+        doc.trace_for("u")[1].action,
+        MergeAction::Resolve {
+            substitution: String::from("missing"),
+            resolution: Resolution::Unresolved
+        }
+    );
+    assert_eq!(doc.hocon()["a"]["b"], hocon::Hocon::Integer(5));
+    assert_eq!(doc.hocon()["d"], hocon::Hocon::Boolean(true));
+
+    let doc = hocon::HoconLoader::new()
+        .load_str("{ a: 1 }")
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert!(doc.trace().is_empty());
+}
+
+#[test]
+fn load_hocon_round_trip() {
+    for file_name in [
+        "tests/data/basic.conf",
+        "tests/data/test01.conf",
+        "tests/data/file_with_different_types.conf",
+    ] {
+        let doc = hocon::HoconLoader::new()
+            .no_system()
+            .load_file(file_name)
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+
+        let reloaded = hocon::HoconLoader::new()
+            .load_hocon(doc.clone())
+            .hocon()
+            .expect("during test");
+
+        assert_eq!(dbg!(reloaded), doc);
+    }
+
+    let doc = hocon::HoconLoader::new()
+        .load_str(r#"{ a: null, b: [], c: {}, d: [[1], { e: [] }], "f.g": "null" }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    let reloaded = hocon::HoconLoader::new()
+        .load_hocon(doc.clone())
+        .hocon()
+        .expect("during test");
+    assert_eq!(dbg!(reloaded), doc);
+}
+
+#[test]
+fn reference_layering() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_defaults_from("tests/data/layers")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(8080));
+    assert_eq!(
+        doc["http"]["url"].as_string(),
+        Some(String::from("http://example.com:8080"))
+    );
+    assert_eq!(doc["workers"].as_string(), Some(String::from("4 x")));
+
+    // defaults are merged first, even when loaded last
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(r#"{ workers: 8 }"#)
+        .expect("during test")
+        .with_reference("tests/data/layers/reference.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["workers"], hocon::Hocon::Integer(8));
+    assert_eq!(
+        doc["http"]["url"].as_string(),
+        Some(String::from("http://localhost:80"))
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_defaults_from("tests/data")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::Hocon::Hash(Default::default()));
+}
+
+#[test]
+fn load_standard_from_env() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_standard("tests/data/layers/application")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(8080));
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .with_env_source([("HOCON_CONFIG_FILE", "tests/data/basic.conf")])
+        .load_standard("tests/data/layers/application")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"], hocon::Hocon::Integer(5));
+    assert_eq!(
+        doc["http"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+
+    #[cfg(feature = "url-support")]
+    {
+        let url = format!(
+            "file://{}/tests/data/basic.conf",
+            std::env::current_dir().expect("during test").display()
+        );
+        let loaded = hocon::HoconLoader::new()
+            .no_system()
+            .with_env_source([("HOCON_CONFIG_URL", url)])
+            .load_standard("tests/data/layers/application");
+        assert_eq!(
+            loaded.expect("during test").hocon().expect("during test")["a"],
+            hocon::Hocon::Integer(5)
+        );
+    }
+}
+
+#[test]
+fn profile_overlays() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .profile("prod")
+        .load_defaults_from("tests/data/layers")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(443));
+    assert_eq!(
+        doc["http"]["url"].as_string(),
+        Some(String::from("http://example.com:443"))
+    );
+    assert_eq!(doc["workers"].as_string(), Some(String::from("16")));
+
+    let env = [("HOCON_TEST_PROFILE", "dev")];
+    let doc = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .no_system()
+        .profile("prod")
+        .profile_from_env("HOCON_TEST_PROFILE")
+        .load_file("tests/data/layers/application.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(8080));
+}
+
+#[test]
+fn args_override_documents() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .trace()
+        .load_args(vec!["http.port=9000", r#""a.b"="8""#, "workers=null"])
+        .expect("during test")
+        .load_defaults_from("tests/data/layers")
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    assert_eq!(doc.hocon()["http"]["port"], hocon::Hocon::Integer(9000));
+    assert_eq!(
+        doc.hocon()["http"]["url"].as_string(),
+        Some(String::from("http://example.com:9000"))
+    );
+    assert_eq!(doc.hocon()["a.b"], hocon::Hocon::String(String::from("8")));
+    assert_eq!(doc.hocon()["workers"], hocon::Hocon::Null);
+    assert_eq!(
+        doc.trace_for("http.port")
+            .last()
+            .and_then(|step| step.source.clone()),
+        Some(hocon::document::Source::Args)
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new().load_args(vec!["novalue"]).err(),
+        Some(hocon::Error::Parse)
+    );
+    assert_eq!(
+        hocon::HoconLoader::new().load_args(vec!["=x"]).err(),
+        Some(hocon::Error::Parse)
+    );
+    assert_eq!(
+        hocon::HoconLoader::new().load_args(vec![" =x"]).err(),
+        Some(hocon::Error::Parse)
+    );
+
+    // values are trimmed, like the numbers they are read as
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_args(vec!["name= example ", "port= 80 "])
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["name"].as_string(), Some(String::from("example")));
+    assert_eq!(doc["port"], hocon::Hocon::Integer(80));
+}
+
+#[test]
+fn env_overrides_documents() {
+    let env = [
+        ("HOCON_TEST_OVERRIDES_HTTP__PORT", "9090"),
+        ("HOCON_TEST_OVERRIDES_HTTP__HOST", "example.org"),
+    ];
+    let doc = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .no_system()
+        .env_overrides("HOCON_TEST_OVERRIDES")
+        .load_args(vec!["http.port=9000"])
+        .expect("during test")
+        .load_defaults_from("tests/data/layers")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    // later overrides win over earlier ones
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(9000));
+    assert_eq!(
+        doc["http"]["url"].as_string(),
+        Some(String::from("http://example.org:9000"))
+    );
+}
+
+#[test]
+fn overrides_resolve_substitutions() {
+    let mut overrides = std::collections::HashMap::new();
+    overrides.insert("db.port", hocon::Hocon::Integer(6543));
+    overrides.insert("SECRET", hocon::Hocon::String(String::from("xyz")));
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(
+            r#"{
+                db { host: localhost, port: 5432 }
+                url: "pg://"${db.host}":"${db.port}
+                copy: ${db}
+                secret: ${?SECRET}
+                path: [a]
+                path: ${path} [b]
+            }"#,
+        )
+        .expect("during test")
+        .with_overrides(overrides)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(
+        doc["url"].as_string(),
+        Some(String::from("pg://localhost:6543"))
+    );
+    assert_eq!(doc["copy"]["port"], hocon::Hocon::Integer(6543));
+    assert_eq!(doc["secret"].as_string(), Some(String::from("xyz")));
+    assert_eq!(doc["path"][1].as_string(), Some(String::from("b")));
+}
+
+#[test]
+fn defaults_from_code() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_file("tests/data/layers/application.conf")
+        .expect("during test")
+        .with_defaults(hocon::hocon!({ http { host = "0.0.0.0", port = 1 }, workers = 2 }))
+        .with_reference("tests/data/layers/reference.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    // defaults are merged in the order they were loaded, before the application
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(8080));
+    assert_eq!(
+        doc["http"]["url"].as_string(),
+        Some(String::from("http://localhost:8080"))
+    );
+    assert_eq!(doc["workers"].as_string(), Some(String::from("4 x")));
+}
+
+#[test]
+fn first_wins_ordering() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .first_wins()
+        .profile("prod")
+        .load_args(vec!["workers=1"])
+        .expect("during test")
+        .load_args(vec!["workers=2"])
+        .expect("during test")
+        .load_str(r#"{ http.port: 1, extra: true }"#)
+        .expect("during test")
+        .load_defaults_from("tests/data/layers")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(1));
+    assert_eq!(
+        doc["http"]["host"].as_string(),
+        Some(String::from("example.com"))
+    );
+    assert_eq!(doc["extra"], hocon::Hocon::Boolean(true));
+    assert_eq!(doc["workers"], hocon::Hocon::Integer(1));
+
+    // the profile overlay still wins over its file
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .first_wins()
+        .profile("prod")
+        .load_file("tests/data/layers/application.conf")
+        .expect("during test")
+        .load_str(r#"{ http.port: 1 }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(443));
+}
+
+#[test]
+fn override_chain_of_path() {
+    let document = hocon::HoconLoader::new()
+        .no_system()
+        .trace()
+        .load_str(r#"{ a { b: 1 }, c: 1 }"#)
+        .expect("during test")
+        .load_hocon(hocon::hocon!({ a = 2 }))
+        .with_defaults(hocon::hocon!({ c = 0 }))
+        .document()
+        .expect("during test");
+
+    assert_eq!(
+        document.override_chain("a.b"),
+        vec![hocon::document::Source::Str, hocon::document::Source::Hocon]
+    );
+    assert_eq!(
+        document.override_chain("c"),
+        vec![hocon::document::Source::Hocon, hocon::document::Source::Str]
+    );
+    assert!(document.override_chain("missing").is_empty());
+}
+
+#[test]
+fn filter_env_substitutions() {
+    let env = [
+        ("HOCON_TEST_ALLOWED", "1"),
+        ("HOCON_TEST_DENIED", "2"),
+        ("PATH", "/bin"),
+    ];
+    let doc = r#"{ a: ${?HOCON_TEST_ALLOWED}, b: ${?HOCON_TEST_DENIED}, c: ${?PATH} }"#;
+
+    let all = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(all["b"].as_i64(), Some(2));
+
+    let filtered = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .allow_env(&["HOCON_TEST_DENIED"])
+        .allow_env(&["HOCON_TEST_ALLOWED"])
+        .deny_env(&["HOCON_TEST_DENIED"])
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(filtered["a"].as_i64(), Some(1));
+    assert!(matches!(filtered["b"], hocon::Hocon::BadValue(_)));
+    assert!(matches!(filtered["c"], hocon::Hocon::BadValue(_)));
+}
+
+#[test]
+fn case_insensitive_env_substitutions() {
+    let env = [
+        ("HOCON_TEST_CASE_Region", "eu"),
+        ("HOCON_TEST_CASE_SECRET", "s3cr3t"),
+    ];
+    let doc = r#"{ a: ${?HOCON_TEST_CASE_REGION}, b: ${?hocon_test_case_secret} }"#;
+
+    let sensitive = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert!(matches!(sensitive["a"], hocon::Hocon::BadValue(_)));
+
+    let insensitive = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .env_case_insensitive()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(insensitive["a"].as_string(), Some(String::from("eu")));
+    assert_eq!(insensitive["b"].as_string(), Some(String::from("s3cr3t")));
+
+    let denied = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .env_case_insensitive()
+        .deny_env(&["HOCON_TEST_CASE_SECRET"])
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(denied["a"].as_string(), Some(String::from("eu")));
+    assert!(matches!(denied["b"], hocon::Hocon::BadValue(_)));
+}
+
+#[test]
+fn quoted_string_interpolation() {
+    let env = [("HOCON_TEST_INTERPOLATED_HOST", "example.com")];
+    let doc = r#"{
+        port: 8080
+        url: "https://${HOCON_TEST_INTERPOLATED_HOST}:${port}/api"
+        hosts: ["${HOCON_TEST_INTERPOLATED_HOST}", "${?missing}"]
+        concat: "a-${port}" "-b" ${port}
+        default: "${?missing:-none}"
+        text: "${ unclosed and $${port}"
+    }"#;
+
+    let interpolated = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .string_interpolation()
+        .substitution_defaults()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        interpolated["url"].as_string(),
+        Some(String::from("https://example.com:8080/api"))
+    );
+    assert_eq!(
+        interpolated["hosts"][0].as_string(),
+        Some(String::from("example.com"))
+    );
+    assert_eq!(interpolated["hosts"][1].as_string(), Some(String::new()));
+    assert_eq!(
+        interpolated["concat"].as_string(),
+        Some(String::from("a-8080 -b 8080"))
+    );
+    assert_eq!(
+        interpolated["default"].as_string(),
+        Some(String::from("none"))
+    );
+    assert_eq!(
+        interpolated["text"].as_string(),
+        Some(String::from("${ unclosed and $8080"))
+    );
+
+    let literal = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .substitution_defaults()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        literal["url"].as_string(),
+        Some(String::from(
+            "https://${HOCON_TEST_INTERPOLATED_HOST}:${port}/api"
+        ))
+    );
+
+    // only values that can be written as text are interpolated
+    let doc = r#"{ list: [1], in_text: "a${list}", missing: "${not_set}" }"#;
+    let interpolated = hocon::HoconLoader::new()
+        .no_system()
+        .string_interpolation()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        interpolated["in_text"],
+        hocon::Hocon::BadValue(hocon::Error::InvalidConcatenation {
+            path: String::from("list")
+        })
+    );
+    assert_eq!(
+        interpolated["missing"],
+        hocon::Hocon::BadValue(hocon::Error::KeyNotFound {
+            key: String::from("not_set")
+        })
+    );
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .no_system()
+            .string_interpolation()
+            .strict()
+            .load_str(doc)
+            .and_then(|loader| loader.hocon()),
+        Err(hocon::Error::InvalidConcatenation { .. })
+    ));
+}
+
+#[test]
+fn include_target_substitutions() {
+    let env = [("HOCON_TEST_INCLUDED_FILE", "extra.conf")];
+    let doc = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .include_substitutions()
+        .load_file("tests/data/include_substitution/env.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["extra"].as_bool(), Some(true));
+    assert_eq!(
+        doc["missing.conf"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .with_env_source(env)
+            .include_substitutions()
+            .load_file("tests/data/include_substitution/main.conf")
+            .expect("during test")
+            .hocon()
+            .expect("during test")["${variant}\".conf\""],
+        hocon::Hocon::BadValue(hocon::Error::Include {
+            path: String::from(r#"${variant}".conf""#)
+        })
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .with_env_source(env)
+            .load_file("tests/data/include_substitution/env.conf")
+            .err(),
+        Some(hocon::Error::Parse)
+    );
+}
+
+#[test]
+fn substitution_expansion_limits() {
+    let doubling = (0..64).fold(String::from("a: lol\n"), |doc, _| doc + "a: ${a}${a}\n");
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .no_system()
+            .load_str(&doubling)
+            .expect("during test")
+            .hocon(),
+        Err(hocon::Error::ResourceLimit { .. })
+    ));
+
+    // values referenced before they are defined are only expanded when resolving the document
+    let forward = (1..40)
+        .map(|i| format!("a{}: ${{a{}}}${{a{}}}\n", i, i - 1, i - 1))
+        .rev()
+        .chain(std::iter::once(String::from("a0: lol\n")))
+        .collect::<String>();
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .no_system()
+            .max_substitutions(1_000)
+            .load_str(&forward)
+            .expect("during test")
+            .hocon(),
+        Err(hocon::Error::ResourceLimit { .. })
+    ));
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .max_substitutions(10)
+        .max_value_size(16)
+        .load_str(r#"{ a: lol, b: ${a}${a}, c: { d: ${b} }, e: ${c} }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["e"]["d"].as_string(), Some(String::from("lollol")));
+}
+
+#[test]
+fn resolution_passes_limit() {
+    // each value refers to the next one, defined after it
+    let chain = |length: usize| {
+        (0..length)
+            .map(|i| format!("a{}: ${{a{}}}\n", i, i + 1))
+            .chain(std::iter::once(format!("a{}: end\n", length)))
+            .collect::<String>()
+    };
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(&chain(50))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a0"].as_string(), Some(String::from("end")));
+
+    match hocon::HoconLoader::new()
+        .no_system()
+        .load_str(&chain(150))
+        .expect("during test")
+        .hocon()
+    {
+        Err(hocon::Error::TooManyResolutionPasses { passes, keys }) => {
+            assert_eq!(passes, 100);
+            assert_eq!(keys.len(), 101);
+            assert_eq!(keys[0], "a1");
+            assert_eq!(keys[100], "a101");
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .max_resolution_passes(3)
+        .load_str(r#"{ a: ${b}, b: ${c}, c: ${d}, d: 1, e: ${d}, f: ${e} }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"], hocon::Hocon::Integer(1));
+    assert_eq!(doc["f"], hocon::Hocon::Integer(1));
+}
+
+#[test]
+fn system_properties_and_env_substitutions() {
+    let env = [
+        ("HOCON_TEST_PROPERTY_BOTH", "from-env"),
+        ("HOCON_TEST_PROPERTY_ENV", "from-env"),
+    ];
+    let doc = r#"{
+        both: ${?HOCON_TEST_PROPERTY_BOTH}
+        env: ${?HOCON_TEST_PROPERTY_ENV}
+        property: ${?app.name}
+    }"#;
+    let loader = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .system_properties(vec![("HOCON_TEST_PROPERTY_BOTH", "from-property")])
+        .system_properties(vec![("app.name", "demo")]);
+
+    let all = loader
+        .trace()
+        .load_str(doc)
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert_eq!(
+        all.hocon()["both"].as_string(),
+        Some(String::from("from-property"))
+    );
+    assert_eq!(
+        all.hocon()["env"].as_string(),
+        Some(String::from("from-env"))
+    );
+    assert_eq!(
+        all.hocon()["property"].as_string(),
+        Some(String::from("demo"))
+    );
+    assert!(all.trace_for("property").iter().any(|step| step.action
+        == hocon::document::MergeAction::Resolve {
+            substitution: String::from("app.name"),
+            resolution: hocon::document::Resolution::SystemProperty,
+        }));
+
+    let no_env = loader
+        .no_env()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        no_env["both"].as_string(),
+        Some(String::from("from-property"))
+    );
+    assert!(matches!(no_env["env"], hocon::Hocon::BadValue(_)));
+
+    let no_properties = loader
+        .no_system_properties()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        no_properties["both"].as_string(),
+        Some(String::from("from-env"))
+    );
+    assert!(matches!(
+        no_properties["property"],
+        hocon::Hocon::BadValue(_)
+    ));
+
+    let no_system = loader
+        .no_system()
+        .load_str(doc)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert!(matches!(no_system["both"], hocon::Hocon::BadValue(_)));
+    assert!(matches!(no_system["property"], hocon::Hocon::BadValue(_)));
+}
+
+#[test]
+fn substitution_inline_defaults() {
+    let env = [("HOCON_TEST_DEFAULT_SET", "from-env")];
+    let doc = hocon::HoconLoader::new().with_env_source(env)
+        .substitution_defaults()
+        .load_str(
+            r#"{
+                a: 1
+                b: ${?a:-2}
+                c: ${HOCON_TEST_DEFAULT_SET:-unused}
+                d: "http://"${?HOCON_TEST_DEFAULT_UNSET:- localhost }":"${?HOCON_TEST_DEFAULT_UNSET:-80}
+                e: 5
+                e: ${?HOCON_TEST_DEFAULT_UNSET:-true}
+                f: ${?HOCON_TEST_DEFAULT_UNSET:-null}
+                g: ${?HOCON_TEST_DEFAULT_UNSET:-}
+                h: "prefix-"${?HOCON_TEST_DEFAULT_UNSET:- }"-suffix"
+            }"#,
+        )
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["b"], hocon::Hocon::Integer(1));
+    assert_eq!(doc["c"].as_string(), Some(String::from("from-env")));
+    assert_eq!(
+        doc["d"].as_string(),
+        Some(String::from("http://localhost:80"))
+    );
+    assert_eq!(doc["e"], hocon::Hocon::Boolean(true));
+    assert_eq!(doc["f"], hocon::Hocon::Null);
+    // an empty default is an empty string
+    assert_eq!(doc["g"].as_string(), Some(String::new()));
+    assert_eq!(doc["h"].as_string(), Some(String::from("prefix--suffix")));
+
+    let doc = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .no_system()
+        .substitution_defaults()
+        .load_str(
+            r#"{ a: 1, a: ${?HOCON_TEST_DEFAULT_SET:-2}, b: 3, b: ${?HOCON_TEST_DEFAULT_SET} }"#,
+        )
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    // without the environment, the inline default or the previous value is used
+    assert_eq!(doc["a"], hocon::Hocon::Integer(2));
+    assert_eq!(doc["b"], hocon::Hocon::Integer(3));
+}
+
+#[test]
+fn file_content_substitutions() {
+    let doc = hocon::HoconLoader::new()
+        .file_substitutions()
+        .trace()
+        .load_str(
+            r#"{
+                db.password: ${file:tests/data/secrets/db_password}
+                db.url: "postgres://"${file: tests/data/secrets/db_user }"@localhost"
+            }"#,
+        )
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    assert_eq!(
+        doc.hocon()["db"]["password"].as_string(),
+        Some(String::from("s3cr3t"))
+    );
+    assert_eq!(
+        doc.hocon()["db"]["url"].as_string(),
+        Some(String::from("postgres://admin@localhost"))
+    );
+    assert!(doc.trace_for("db.password").iter().any(|step| step.action
+        == hocon::document::MergeAction::Resolve {
+            substitution: String::from("file:tests/data/secrets/db_password"),
+            resolution: hocon::document::Resolution::File,
+        }));
+
+    let missing = r#"{ a: ${file:tests/data/secrets/missing} }"#;
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .file_substitutions()
+            .strict()
+            .load_str(missing)
+            .expect("during test")
+            .hocon()
+            .err(),
+        Some(hocon::Error::File {
+            path: String::from("tests/data/secrets/missing")
+        })
+    );
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .file_substitutions()
+            .load_str(missing)
+            .expect("during test")
+            .hocon()
+            .expect("during test")["a"],
+        hocon::Hocon::BadValue(hocon::Error::File {
+            path: String::from("tests/data/secrets/missing")
+        })
+    );
+
+    // an optional substitution of a missing file leaves the value undefined
+    let optional = r#"{
+        user: ${?file:tests/data/secrets/db_user}
+        missing: ${?file:tests/data/secrets/missing}
+        list: [1, ${?file:tests/data/secrets/missing}]
+        port: 5432
+        port: ${?file:tests/data/secrets/missing}
+    }"#;
+    for loader in [
+        hocon::HoconLoader::new().file_substitutions(),
+        hocon::HoconLoader::new().file_substitutions().strict(),
+    ] {
+        let doc = loader
+            .load_str(optional)
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+        assert_eq!(doc["user"].as_string(), Some(String::from("admin")));
+        assert_eq!(
+            doc["missing"],
+            hocon::Hocon::BadValue(hocon::Error::MissingKey)
+        );
+        assert_eq!(
+            doc["list"],
+            hocon::Hocon::Array(vec![hocon::Hocon::Integer(1)])
+        );
+        assert_eq!(doc["port"].as_i64(), Some(5432));
+    }
+}
+
+#[test]
+fn strict_unresolved_substitutions() {
+    let err = hocon::HoconLoader::new()
+        .no_system()
+        .strict()
+        .load_file("tests/data/unresolved/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect_err("during test");
+    match &err {
+        hocon::Error::UnresolvedSubstitution {
+            substitution,
+            key,
+            origin,
+            line,
+        } => {
+            assert_eq!(substitution, "DATABASE_URL");
+            assert_eq!(key.as_deref(), Some("services.database.url"));
+            assert!(
+                matches!(origin, Some(hocon::document::Source::File(path)) if path.ends_with("database.conf"))
+            );
+            assert_eq!(*line, Some(4));
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    assert!(err.to_string().starts_with(
+        "Substitution 'DATABASE_URL' not found for 'services.database.url' in file '"
+    ));
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .no_system()
+            .strict()
+            .load_str("a: 1\nb: [${a}] ${c}")
+            .expect("during test")
+            .hocon(),
+        Err(hocon::Error::UnresolvedSubstitution {
+            substitution: String::from("c"),
+            key: Some(String::from("b")),
+            origin: Some(hocon::document::Source::Str),
+            line: Some(2),
+        })
+    );
+
+    // not an error when not in strict mode, or when optional
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_file("tests/data/unresolved/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["services"]["database"]["url"].as_string(),
+        Some(String::from("jdbc:"))
+    );
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .strict()
+        .load_str(r#"{ a: "x"${?b} }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_string(), Some(String::from("x")));
+}
+
+#[test]
+fn substitution_cycles() {
+    let cycle = hocon::HoconLoader::new()
+        .no_system()
+        .strict()
+        .load_str(r#"{ a: ${b}, c: ${a} }"#)
+        .expect("during test")
+        .load_file("tests/data/cycle/b.conf")
+        .expect("during test")
+        .hocon()
+        .expect_err("during test");
+    match &cycle {
+        hocon::Error::SubstitutionCycle { chain, origins } => {
+            assert_eq!(chain, &vec!["a", "b", "c", "a"]);
+            assert_eq!(origins[0], Some(hocon::document::Source::Str));
+            assert!(
+                matches!(&origins[1], Some(hocon::document::Source::File(path)) if path.ends_with("b.conf"))
+            );
+            assert_eq!(origins[2], Some(hocon::document::Source::Str));
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    assert!(
+        cycle
+            .to_string()
+            .starts_with("Substitution cycle 'a' (string) -> 'b' (file '")
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(r#"{ a: ${b}, b: ${a}, c: ${?d}, d: ${?c}, e: ${f}, f: ${g}, g: 1, g: 2 }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["a"],
+        hocon::Hocon::BadValue(hocon::Error::SubstitutionCycle {
+            chain: vec![String::from("a"), String::from("b"), String::from("a")],
+            origins: vec![Some(hocon::document::Source::Str); 3],
+        })
+    );
+    assert_eq!(doc["c"], hocon::Hocon::Null);
+    assert_eq!(doc["e"], hocon::Hocon::Integer(2));
+
+    // the bad value left without strict mode has the same origins as the error
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(r#"{ a: ${b}, c: ${a} }"#)
+        .expect("during test")
+        .load_file("tests/data/cycle/b.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"], hocon::Hocon::BadValue(cycle));
+}
+
+#[test]
+fn partial_resolution() {
+    let options = hocon::ResolveOptions {
+        allow_unresolved: true,
+        use_env: false,
+    };
+    let loader = hocon::HoconLoader::new()
+        .no_system()
+        .load_str(r#"{ a: 1, b: ${a}, c: ${d}, e: ${?f} "x", obj: ${g} }"#)
+        .expect("during test")
+        .load_args(vec!["a=2"])
+        .expect("during test")
+        .resolve_partially(options)
+        .expect("during test");
+
+    let doc = loader
+        .clone()
+        .load_str(r#"{ a: 3, d: 4, f: y, g { h: ${a} } }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"], hocon::Hocon::Integer(2));
+    assert_eq!(doc["b"], hocon::Hocon::Integer(2));
+    assert_eq!(doc["c"], hocon::Hocon::Integer(4));
+    assert_eq!(doc["e"].as_string(), Some(String::from("y x")));
+    assert_eq!(doc["obj"]["h"], hocon::Hocon::Integer(2));
+
+    assert_eq!(
+        loader.hocon().expect("during test")["c"],
+        hocon::Hocon::BadValue(hocon::Error::KeyNotFound {
+            key: String::from("d")
+        })
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .strict()
+            .load_str(r#"{ a: ${b} }"#)
+            .expect("during test")
+            .resolve_partially(hocon::ResolveOptions::default())
+            .err(),
+        Some(hocon::Error::UnresolvedSubstitution {
+            substitution: String::from("b"),
+            key: Some(String::from("a")),
+            origin: Some(hocon::document::Source::Str),
+            line: Some(1),
+        })
+    );
+}
+
+#[test]
+fn origin_of_env_resolved_values() {
+    let env = [("HOCON_TEST_ORIGIN_PORT", "9090")];
+    let document = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .trace()
+        .system_properties(vec![("app.name", "demo")])
+        .load_str(r#"{ server.port: 8080, server.port: ${?HOCON_TEST_ORIGIN_PORT} }"#)
+        .expect("during test")
+        .load_str(r#"{ name: ${?app.name}, unset: ${?HOCON_TEST_ORIGIN_UNSET} }"#)
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    assert_eq!(
+        document.origin("server.port"),
+        Some(hocon::document::Origin::EnvVar(String::from(
+            "HOCON_TEST_ORIGIN_PORT"
+        )))
+    );
+    assert_eq!(
+        document.origin("name"),
+        Some(hocon::document::Origin::SystemProperty(String::from(
+            "app.name"
+        )))
+    );
+    assert_eq!(document.origin("unset"), None);
+    assert_eq!(document.origin("missing"), None);
+
+    let untraced = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .load_str(r#"{ server.port: ${?HOCON_TEST_ORIGIN_PORT} }"#)
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert_eq!(untraced.origin("server.port"), None);
+}
+
+#[test]
+fn unresolved_optional_substitutions() {
+    let env = [("HOCON_TEST_OPTIONALS_SET", "set")];
+    let document = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .load_str(
+            r#"{
+                server { port: 8080, port: ${?HOCON_TEST_OPTIONALS_PORT} }
+                name: ${?HOCON_TEST_OPTIONALS_SET}
+                greeting: hello ${?HOCON_TEST_OPTIONALS_NAME}
+                tags: [a] ${?HOCON_TEST_OPTIONALS_TAGS}
+                copy: ${server.port}
+            }"#,
+        )
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    assert_eq!(
+        document
+            .unresolved_optionals()
+            .iter()
+            .map(|unresolved| (unresolved.path.as_str(), unresolved.substitution.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("server.port", "HOCON_TEST_OPTIONALS_PORT"),
+            ("greeting", "HOCON_TEST_OPTIONALS_NAME"),
+            ("tags", "HOCON_TEST_OPTIONALS_TAGS"),
+        ]
+    );
+    assert_eq!(document.hocon()["copy"], hocon::Hocon::Integer(8080));
+
+    let resolved = hocon::HoconLoader::new()
+        .with_env_source(env)
+        .load_str(r#"{ name: ${?HOCON_TEST_OPTIONALS_SET} }"#)
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert!(resolved.unresolved_optionals().is_empty());
+}
+
+#[test]
+fn injected_env_source() {
+    let loader = hocon::HoconLoader::new().with_env_source(vec![
+        ("HOCON_TEST_SOURCE_PORT", "9090"),
+        ("HOCON_TEST_SOURCE_Host", "example.com"),
+        ("APP_SERVER__PORT", "7070"),
+        ("APP_PROFILE", "prod"),
+    ]);
+
+    let doc = loader
+        .clone()
+        .load_str(
+            r#"{
+                port: ${HOCON_TEST_SOURCE_PORT}
+                host: ${?HOCON_TEST_SOURCE_HOST}
+                path: ${?PATH}
+            }"#,
+        )
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["port"].as_string(), Some(String::from("9090")));
+    assert_eq!(doc["host"].as_string(), None);
+    assert_eq!(doc["path"].as_string(), None);
+
+    let doc = loader
+        .env_case_insensitive()
+        .load_str(r#"{ host: ${?HOCON_TEST_SOURCE_HOST} }"#)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["host"].as_string(), Some(String::from("example.com")));
+
+    let doc = loader
+        .profile_from_env("APP_PROFILE")
+        .env_overrides("APP")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["server"]["port"], hocon::Hocon::Integer(7070));
+}
+
+#[test]
+fn glob_includes() {
+    let doc = hocon::HoconLoader::new()
+        .include_globs()
+        .load_file("tests/data/include_glob/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc,
+        hocon::hocon!({
+            service = "database",
+            database { host = "localhost", port = 6432 }
+        })
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .include_globs()
+        .strict()
+        .load_file("tests/data/include_glob/nested.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::hocon!({ cache.enabled = true }));
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_glob/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["service"].as_string(), Some(String::from("main")));
+    assert_eq!(doc["database"]["port"].as_i64(), None);
+}
+
+#[test]
+fn directory_includes() {
+    let doc = hocon::HoconLoader::new()
+        .include_directories()
+        .load_file("tests/data/include_directory/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc,
+        hocon::hocon!({
+            service = "directory",
+            database { host = "localhost", port = 6432 }
+        })
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .include_directories_with(hocon::DirectoryIncludes::new().recursive())
+        .load_file("tests/data/include_directory/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    // lexical order puts services/10-cache.conf before services/9-cache.conf
+    assert_eq!(doc["cache"]["size"], hocon::Hocon::Integer(100));
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_directory/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["service"].as_string(), Some(String::from("main")));
+    assert_eq!(doc["database"]["port"].as_i64(), None);
+}
+
+#[test]
+fn required_includes() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_required/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::hocon!({ present = true, other = true }));
+
+    for file in ["missing.conf", "missing_without_extension.conf"] {
+        assert!(matches!(
+            hocon::HoconLoader::new().load_file(format!("tests/data/include_required/{}", file)),
+            Err(hocon::Error::Include { .. })
+        ));
+    }
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_required/optional.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::hocon!({ a = 1 }));
+}
+
+#[test]
+fn missing_includes_are_skipped() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_required/missing_files.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::hocon!({ a = 1 }));
+
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .strict()
+            .load_file("tests/data/include_required/missing_files.conf"),
+        Err(hocon::Error::Include { .. })
+    ));
+}
+
+#[test]
+fn classpath_includes() {
+    let doc = hocon::HoconLoader::new()
+        .classpath_dir("tests/data/classpath/missing-dir")
+        .classpath_dir("tests/data/classpath/resources")
+        .classpath_resource("embedded.conf", "embedded = true, app.port = 9090")
+        .load_file("tests/data/classpath/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc,
+        hocon::hocon!({
+            app { name = "demo", port = 9090 },
+            db.host = "localhost",
+            embedded = true
+        })
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/classpath/missing.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::hocon!({ present = true }));
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .load_file("tests/data/classpath/required.conf")
+            .err(),
+        Some(hocon::Error::Include {
+            path: String::from("absent.conf")
+        })
+    );
+}
+
+#[test]
+fn custom_include_resolver() {
+    let resolver = |target: hocon::IncludeTarget<'_>| match target {
+        hocon::IncludeTarget::Url("vault://db") => Ok(Some(String::from("db.password = secret"))),
+        hocon::IncludeTarget::File(path) if path == std::path::Path::new("bundle/app.json") => {
+            Ok(Some(String::from(r#"{ "app": { "name": "bundled" } }"#)))
+        }
+        hocon::IncludeTarget::Classpath(name) => Err(hocon::Error::Include {
+            path: String::from(name),
+        }),
+        _ => Ok(None),
+    };
+
+    let doc = hocon::HoconLoader::new()
+        .include_resolver(resolver)
+        .load_file("tests/data/include_resolver/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["db"]["password"].as_string(),
+        Some(String::from("secret"))
+    );
+    assert_eq!(
+        doc["app"]["name"].as_string(),
+        Some(String::from("bundled"))
+    );
+    assert_eq!(doc["local"].as_bool(), Some(true));
+    assert_eq!(
+        doc["broken.conf"],
+        hocon::Hocon::BadValue(hocon::Error::Include {
+            path: String::from("broken.conf")
+        })
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .include_resolver(resolver)
+            .strict()
+            .load_file("tests/data/include_resolver/main.conf")
+            .err(),
+        Some(hocon::Error::Include {
+            path: String::from("broken.conf")
+        })
+    );
+}
+
+#[test]
+fn env_variable_includes() {
+    let loader = hocon::HoconLoader::new()
+        .env_includes()
+        .with_env_source(vec![("APP_CONFIG", "database.port = 5432")]);
+    let doc = loader
+        .load_file("tests/data/include_env/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc,
+        hocon::hocon!({ database { host = "localhost", port = 5432 } })
+    );
+
+    assert_eq!(
+        loader
+            .load_file("tests/data/include_env/required.conf")
+            .err(),
+        Some(hocon::Error::Include {
+            path: String::from("APP_UNSET_CONFIG")
+        })
+    );
+    assert_eq!(
+        loader
+            .deny_env(&["APP_CONFIG"])
+            .strict()
+            .load_file("tests/data/include_env/main.conf")
+            .err(),
+        Some(hocon::Error::Include {
+            path: String::from("APP_CONFIG")
+        })
+    );
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .load_file("tests/data/include_env/main.conf")
+            .err(),
+        Some(hocon::Error::Parse)
+    );
+}
+
+#[test]
+fn extensionless_includes_merge_variants() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/extensionless/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["source"].as_string(), Some(String::from("conf")));
+    assert_eq!(
+        doc["only_json_and_properties"].as_string(),
+        Some(String::from("json"))
+    );
+    assert_eq!(doc["from_conf"].as_bool(), Some(true));
+    assert_eq!(doc["from_json"].as_bool(), Some(true));
+    assert_eq!(doc["from_properties"].as_bool(), Some(true));
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("demo")));
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/extensionless/settings")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["source"].as_string(), Some(String::from("conf")));
+}
+
+#[test]
+fn includes_restricted_to_root() {
+    let loader = hocon::HoconLoader::new()
+        .file_substitutions()
+        .include_root("tests/data/include_root/root");
+
+    let doc = loader
+        .load_file("tests/data/include_root/root/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["local"].as_bool(), Some(true));
+    assert_eq!(doc["inner"].as_bool(), Some(true));
+    assert_eq!(
+        doc["secret"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+
+    assert_eq!(
+        loader
+            .strict()
+            .load_file("tests/data/include_root/root/symlink.conf")
+            .err(),
+        Some(hocon::Error::IncludeOutsideRoot {
+            path: String::from("sub/link.conf")
+        })
+    );
+
+    let doc = loader
+        .load_file("tests/data/include_root/root/substitution.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["inner"].as_bool(), Some(true));
+    assert_eq!(
+        doc["secret"],
+        hocon::Hocon::BadValue(hocon::Error::IncludeOutsideRoot {
+            path: String::from("tests/data/include_root/secret.conf")
+        })
+    );
+    assert_eq!(
+        doc["allowed"].as_string(),
+        Some(String::from("inner = true"))
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_root/root/symlink.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["secret"].as_bool(), Some(true));
+}
+
+#[test]
+fn include_cycles_are_reported() {
+    let path = |file: &str| {
+        std::fs::canonicalize(format!("tests/data/include_cycle/{file}"))
+            .expect("during test")
+            .display()
+            .to_string()
+    };
+    let cycle = hocon::Error::IncludeCycle {
+        chain: vec![path("a.conf"), path("nested/b.conf"), path("a.conf")],
+    };
+    assert_eq!(
+        cycle.to_string(),
+        format!(
+            "Include cycle {} -> {} -> {}",
+            path("a.conf"),
+            path("nested/b.conf"),
+            path("a.conf")
+        )
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_cycle/a.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    assert_eq!(doc["b"].as_i64(), Some(2));
+    assert_eq!(doc["../a.conf"], hocon::Hocon::BadValue(cycle.clone()));
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .strict()
+            .load_file("tests/data/include_cycle/a.conf")
+            .err(),
+        Some(cycle)
+    );
+}
+
+#[test]
+fn repeated_includes() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/include_cache/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"]["timeout"].as_i64(), Some(30));
+    assert_eq!(doc["b"]["timeout"].as_i64(), Some(30));
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_includes_are_fetched_concurrently() {
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+    static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    std::thread::spawn(move || {
+        for mut stream in server.incoming().flatten() {
+            std::thread::spawn(move || {
+                let in_flight = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+                MAX_IN_FLIGHT.fetch_max(in_flight, Ordering::SeqCst);
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                std::thread::sleep(std::time::Duration::from_millis(300));
+                let body = if String::from_utf8_lossy(&request).starts_with("GET /a.conf") {
+                    "a: 1, c: 1"
+                } else {
+                    "c: 3"
+                };
+                IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            });
+        }
+    });
+
+    let doc = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url)])
+        .load_file("tests/data/include_prefetch/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    // merged in order, whatever the order they were fetched in
+    assert_eq!(doc["c"].as_i64(), Some(3));
+    assert_eq!(MAX_IN_FLIGHT.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn registered_fetcher_fetches_concurrently() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let in_flight = Arc::new((AtomicUsize::new(0), AtomicUsize::new(0)));
+    let fetched = Arc::new(Mutex::new(vec![]));
+    let loader = hocon::HoconLoader::new().http_fetcher({
+        let (in_flight, fetched) = (in_flight.clone(), fetched.clone());
+        move |url: &str| {
+            let now = in_flight.0.fetch_add(1, Ordering::SeqCst) + 1;
+            in_flight.1.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            in_flight.0.fetch_sub(1, Ordering::SeqCst);
+            fetched.lock().expect("during test").push(String::from(url));
+            let name = url.trim_start_matches("https://config.example.com/");
+            Ok(match name {
+                "0.conf" => {
+                    String::from("include url(\"https://config.example.com/nested.conf\")\nlast: 0")
+                }
+                _ => format!(
+                    "last: {name}\nfetched.{}: true",
+                    name.trim_end_matches(".conf")
+                ),
+            })
+        }
+    });
+
+    let doc = loader
+        .load_file("tests/data/include_prefetch/many.conf")
+        .expect("during test");
+    let hocon = doc.hocon().expect("during test");
+    // merged in order, whatever the order they were fetched in
+    assert_eq!(hocon["last"].as_string(), Some(String::from("9.conf")));
+    assert_eq!(hocon["fetched"]["local"].as_bool(), Some(true));
+    assert_eq!(hocon["fetched"]["nested"].as_bool(), Some(true));
+    assert_eq!(hocon["fetched"]["9"].as_bool(), Some(true));
+    // the URLs of the document and of the file it includes are fetched together, then the
+    // URL included by a fetched document
+    assert_eq!(in_flight.1.load(Ordering::SeqCst), 8);
+    let fetched = fetched.lock().expect("during test").clone();
+    assert_eq!(fetched.len(), 12);
+    assert_eq!(
+        fetched.last(),
+        Some(&String::from("https://config.example.com/nested.conf"))
+    );
+
+    let document = loader
+        .load_file("tests/data/include_prefetch/many.conf")
+        .expect("during test")
+        .document()
+        .expect("during test");
+    let sources = document.sources()[0]
+        .flatten()
+        .into_iter()
+        .map(|source| match source {
+            hocon::document::Source::Url(url) => url.clone(),
+            source => format!("{source:?}"),
+        })
+        .filter(|source| source.starts_with("https"))
+        .collect::<Vec<_>>();
+    // each where it was included, with the URL included by a fetched document after it
+    assert_eq!(sources.len(), 12);
+    assert_eq!(sources[9], "https://config.example.com/0.conf");
+    assert_eq!(sources[10], "https://config.example.com/nested.conf");
+    assert_eq!(sources[11], "https://config.example.com/local.conf");
+}
+
+#[test]
+fn include_size_limit() {
+    let loader = hocon::HoconLoader::new();
+    assert_eq!(
+        loader
+            .max_include_size(64)
+            .load_file("tests/data/include_limits/main.conf")
+            .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: format!(
+                "'{}' larger than 64 bytes",
+                std::env::current_dir()
+                    .expect("during test")
+                    .join("tests/data/include_limits/large.conf")
+                    .display()
+            )
+        })
+    );
+
+    let doc = loader
+        .max_include_size(1024)
+        .load_file("tests/data/include_limits/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["small"].as_bool(), Some(true));
+    assert_eq!(doc["large"].as_string().map(|large| large.len()), Some(200));
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_include_limits() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    std::thread::spawn(move || {
+        for mut stream in server.incoming().flatten() {
+            std::thread::spawn(move || {
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                if String::from_utf8_lossy(&request).starts_with("GET /slow.conf") {
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                }
+                let body = format!("a: \"{}\"", "x".repeat(100));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            });
+        }
+    });
+    let load = |loader: hocon::HoconLoader, file: &str| {
+        loader
+            .include_substitutions()
+            .with_env_source([("BASE_URL", base_url.clone()), ("FILE", String::from(file))])
+            .load_file("tests/data/include_limits/url.conf")
+    };
+
+    assert!(
+        load(
+            hocon::HoconLoader::new().max_include_size(1024),
+            "slow.conf"
+        )
+        .is_ok()
+    );
+    assert_eq!(
+        load(hocon::HoconLoader::new().max_include_size(64), "fast.conf").err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: format!("'{base_url}/fast.conf' larger than 64 bytes")
+        })
+    );
+    assert_eq!(
+        load(
+            hocon::HoconLoader::new().url_timeout(std::time::Duration::from_millis(100)),
+            "slow.conf"
+        )
+        .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: format!("fetching '{base_url}/slow.conf' took more than 100ms")
+        })
+    );
+}
+
+#[test]
+fn parsed_values_limit_across_includes() {
+    // `common.conf` is parsed once, but its value counts for each include, with the root object
+    let loader = hocon::HoconLoader::new().max_parsed_values(3);
+    assert!(
+        loader
+            .load_file("tests/data/include_cache/main.conf")
+            .is_ok()
+    );
+    assert_eq!(
+        loader
+            .max_parsed_values(2)
+            .load_file("tests/data/include_cache/main.conf")
+            .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: String::from("more than 2 values parsed")
+        })
+    );
+}
+
+#[test]
+fn loaded_sources() {
+    use hocon::document::{LoadedSource, Source};
+
+    let path = |file: &str| {
+        std::env::current_dir()
+            .expect("during test")
+            .join("tests/data")
+            .join(file)
+    };
+    let loader = hocon::HoconLoader::new()
+        .load_file("tests/data/include_cycle/nested/b.conf")
+        .expect("during test")
+        .load_str("{ c: 3 }")
+        .expect("during test")
+        .load_file("tests/data/extensionless/main.conf")
+        .expect("during test");
+    let sources = loader
+        .clone()
+        .document()
+        .expect("during test")
+        .sources()
+        .to_vec();
+
+    let included = |files: &[&str]| {
+        files
+            .iter()
+            .map(|file| LoadedSource {
+                source: Source::File(path(file)),
+                includes: vec![],
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sources.len(), 3);
+    assert_eq!(
+        sources[0].source,
+        Source::File(path("include_cycle/nested/b.conf"))
+    );
+    assert_eq!(
+        sources[0].includes[0].source,
+        Source::File(path("include_cycle/nested/../a.conf"))
+    );
+    assert_eq!(
+        sources[2].source,
+        Source::File(path("extensionless/main.conf"))
+    );
+    let mut extensionless = sources[2].includes.clone();
+    extensionless.sort_by_key(|source| format!("{:?}", source.source));
+    assert_eq!(
+        extensionless,
+        included(&[
+            "extensionless/./my.app.conf",
+            "extensionless/./settings.conf",
+            "extensionless/./settings.json",
+            "extensionless/./settings.properties",
+        ])
+    );
+
+    // documents merged early keep their sources
+    let resolved = loader
+        .resolve_partially(hocon::ResolveOptions::default())
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert_eq!(resolved.sources(), sources.as_slice());
+}
+
+#[test]
+fn archive_includes() {
+    let loader = hocon::HoconLoader::new().archive_includes();
+    let doc = loader
+        .load_file("tests/data/archive/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["local"].as_bool(), Some(true));
+    assert_eq!(
+        doc["app"]["name"].as_string(),
+        Some(String::from("bundled"))
+    );
+    assert_eq!(
+        doc["logging"]["level"].as_string(),
+        Some(String::from("info"))
+    );
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5432));
+    assert_eq!(
+        doc["db"]["url"].as_string(),
+        Some(String::from("jdbc:5432"))
+    );
+
+    assert_eq!(
+        loader
+            .strict()
+            .load_file("tests/data/archive/main.conf")
+            .err(),
+        Some(hocon::Error::Include {
+            path: String::from("bundle.tar!missing.conf")
+        })
+    );
+    assert!(matches!(
+        loader
+            .load_file("tests/data/archive/bundle.tar!missing.conf")
+            .err(),
+        Some(hocon::Error::File { .. })
+    ));
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .load_file("tests/data/archive/bundle.tar!defaults.conf")
+            .err(),
+        Some(hocon::Error::File { .. })
+    ));
+}
+
+#[test]
+fn search_paths() {
+    let doc = hocon::HoconLoader::new()
+        .with_search_paths([
+            "tests/data/search_paths/etc",
+            "tests/data/search_paths/defaults",
+        ])
+        .load_file("tests/data/search_paths/app/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    // the first search path with the file wins, files are not merged
+    assert_eq!(
+        doc["logging"]["level"].as_string(),
+        Some(String::from("warn"))
+    );
+    assert_eq!(
+        doc["logging"]["format"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+    // a file relative to the including file is found first
+    assert_eq!(doc["local"]["from"].as_string(), Some(String::from("app")));
+    // includes of a file found in a search path are relative to it
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5432));
+    assert_eq!(doc["db"]["pool"].as_i64(), Some(10));
+    assert_eq!(
+        doc["missing.conf"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/search_paths/app/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["logging"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+}
+
+#[test]
+fn load_files_in_order() {
+    let files = [
+        "tests/data/search_paths/etc/logging.conf",
+        "tests/data/search_paths/defaults/logging.conf",
+    ];
+    let doc = hocon::HoconLoader::new()
+        .load_files(files)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["logging"]["level"].as_string(),
+        Some(String::from("debug"))
+    );
+
+    let doc = hocon::HoconLoader::new()
+        .first_wins()
+        .load_files(files)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["logging"]["level"].as_string(),
+        Some(String::from("warn"))
+    );
+
+    assert!(matches!(
+        hocon::HoconLoader::new()
+            .load_files(["tests/data/search_paths/etc/logging.conf", "missing.conf"])
+            .err(),
+        Some(hocon::Error::File { .. })
+    ));
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .load_files(Vec::<&str>::new())
+            .expect("during test")
+            .hocon()
+            .expect("during test"),
+        hocon::Hocon::Hash(Default::default())
+    );
+}
+
+#[test]
+fn load_directory() {
+    let doc = hocon::HoconLoader::new()
+        .no_system()
+        .profile("prod")
+        .load_file("tests/data/layers")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"], hocon::Hocon::Integer(443));
+    assert_eq!(
+        doc["http"]["host"].as_string(),
+        Some(String::from("example.com"))
+    );
+
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .load_file("tests/data/include_root")
+            .err(),
+        Some(hocon::Error::File {
+            path: String::from("tests/data/include_root")
+        })
+    );
+}
+
+/// Serve `a.conf`, including `b.conf`, and `b.conf` on a local server, at the returned URL
+#[cfg(any(
+    all(feature = "async", feature = "url-support"),
+    feature = "url-support-ureq"
+))]
+fn serve_includes() -> String {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    let served_url = base_url.clone();
+    std::thread::spawn(move || {
+        for mut stream in server.incoming().flatten() {
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request);
+            // redirections to the same document, from another host name for the server
+            let location = if request.starts_with("GET /to-localhost") {
+                Some(served_url.replace("127.0.0.1", "localhost") + "/b.conf")
+            } else if request.starts_with("GET /to-b") {
+                Some(String::from("b.conf"))
+            } else {
+                None
+            };
+            if let Some(location) = location {
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                continue;
+            }
+            let body = if request.starts_with("GET /a.conf") {
+                format!("a: 1\ninclude url(\"{served_url}/b.conf\")")
+            } else {
+                String::from("b: 2")
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    base_url
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_loading() {
+    let base_url = serve_includes();
+
+    // fetching with the blocking client would panic within the runtime
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("during test");
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url.clone())]);
+
+    let doc = runtime
+        .block_on(loader.load_file_async("tests/data/async/main.conf"))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["name"].as_string(), Some(String::from("app")));
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    assert_eq!(doc["b"].as_i64(), Some(2));
+
+    let doc = runtime
+        .block_on(loader.load_url_async(&format!("{base_url}/b.conf")))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["b"].as_i64(), Some(2));
+}
+
+#[cfg(feature = "smol")]
+#[test]
+fn async_loading_with_smol() {
+    let base_url = serve_includes();
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url.clone())]);
+
+    let doc = futures_lite::future::block_on(
+        loader.load_file_async_with(&hocon::SmolRuntime, "tests/data/async/main.conf"),
+    )
+    .expect("during test")
+    .hocon()
+    .expect("during test");
+    assert_eq!(doc["name"].as_string(), Some(String::from("app")));
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    assert_eq!(doc["b"].as_i64(), Some(2));
+}
+
+#[cfg(all(
+    feature = "async",
+    any(feature = "url-support", feature = "url-support-ureq")
+))]
+#[test]
+fn async_loading_with_runtime() {
+    struct Threads;
+
+    impl hocon::AsyncRuntime for Threads {
+        fn spawn_blocking<F, T>(&self, f: F) -> impl std::future::Future<Output = T>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static,
+        {
+            let thread = std::thread::spawn(f);
+            async move { thread.join().expect("during test") }
+        }
+    }
+
+    let base_url = serve_includes();
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url.clone())]);
+
+    // any executor can run the loading
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("during test");
+    let doc = runtime
+        .block_on(loader.load_file_async_with(&Threads, "tests/data/async/main.conf"))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    assert_eq!(doc["b"].as_i64(), Some(2));
+
+    let doc = runtime
+        .block_on(loader.load_url_async_with(&Threads, &format!("{base_url}/a.conf")))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    // includes are not supported when loading a URL
+    assert_eq!(doc["a"].as_i64(), Some(1));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_loading_with_http_fetcher() {
+    struct Threads;
+
+    impl hocon::AsyncRuntime for Threads {
+        fn spawn_blocking<F, T>(&self, f: F) -> impl std::future::Future<Output = T>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static,
+        {
+            let thread = std::thread::spawn(f);
+            async move { thread.join().expect("during test") }
+        }
+    }
+
+    let fetched = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let loader = hocon::HoconLoader::new().http_fetcher({
+        let fetched = fetched.clone();
+        move |url: &str| {
+            fetched
+                .lock()
+                .expect("during test")
+                .push((String::from(url), std::thread::current().id()));
+            Ok(String::from("db.port = 5432"))
+        }
+    });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("during test");
+    let doc = runtime
+        .block_on(loader.load_file_async_with(&Threads, "tests/data/http_fetcher/main.conf"))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5432));
+    // the registered fetcher is run by the runtime, without blocking it
+    let fetched = fetched.lock().expect("during test").clone();
+    assert_eq!(fetched.len(), 1);
+    assert_eq!(fetched[0].0, "https://config.example.com/db.conf");
+    assert_ne!(fetched[0].1, std::thread::current().id());
+
+    // the limits of the loader also apply to the documents it fetches
+    assert_eq!(
+        runtime
+            .block_on(
+                loader
+                    .max_include_size(8)
+                    .load_file_async_with(&Threads, "tests/data/http_fetcher/main.conf")
+            )
+            .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: String::from("'https://config.example.com/db.conf' larger than 8 bytes")
+        })
+    );
+}
+
+#[cfg(feature = "url-support-ureq")]
+#[test]
+fn ureq_fetcher() {
+    let base_url = serve_includes();
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url.clone())])
+        .http_fetcher(hocon::UreqFetcher::new());
+
+    let doc = loader
+        .load_file("tests/data/async/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_i64(), Some(1));
+    assert_eq!(doc["b"].as_i64(), Some(2));
+
+    assert_eq!(
+        loader
+            .http_fetcher(hocon::UreqFetcher::new().max_size(4))
+            .load_file("tests/data/async/main.conf")
+            .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: format!("'{base_url}/a.conf' larger than 4 bytes")
+        })
+    );
+
+    use hocon::HttpFetcher;
+    let fetcher =
+        hocon::UreqFetcher::new().url_filter(hocon::UrlFilter::new().allow_hosts(["127.0.0.1"]));
+    assert_eq!(
+        fetcher.fetch(&format!("{base_url}/to-b")).ok(),
+        Some(String::from("b: 2"))
+    );
+    assert_eq!(
+        fetcher.fetch(&format!("{base_url}/to-localhost")).err(),
+        Some(hocon::Error::Include {
+            path: format!("{base_url}/to-localhost")
+        })
+    );
+    assert!(
+        fetcher
+            .max_redirects(0)
+            .fetch(&format!("{base_url}/to-b"))
+            .is_err()
+    );
+    assert!(
+        hocon::UreqFetcher::new()
+            .fetch(&format!("{base_url}/to-localhost"))
+            .is_ok()
+    );
+
+    // without reqwest, ureq is the default fetcher
+    #[cfg(not(feature = "url-support"))]
+    {
+        let doc = hocon::HoconLoader::new()
+            .include_substitutions()
+            .with_env_source([("BASE_URL", base_url.clone())])
+            .load_file("tests/data/async/main.conf")
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+        assert_eq!(doc["b"].as_i64(), Some(2));
+
+        // the filter of the loader also applies to the redirections
+        let load = |path: &str| {
+            hocon::HoconLoader::new()
+                .include_substitutions()
+                .with_env_source([("URL", format!("{base_url}/{path}"))])
+                .url_filter(hocon::UrlFilter::new().allow_hosts(["127.0.0.1"]))
+                .load_file("tests/data/async/url.conf")
+                .and_then(|loader| loader.hocon())
+        };
+        assert_eq!(load("to-b").expect("during test")["b"].as_i64(), Some(2));
+        assert!(load("to-localhost").is_err());
+    }
+}
+
+#[test]
+fn http_fetcher() {
+    let fetched = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let loader = hocon::HoconLoader::new().http_fetcher({
+        let fetched = fetched.clone();
+        move |url: &str| {
+            fetched.lock().expect("during test").push(String::from(url));
+            Ok(String::from("db.port = 5432"))
+        }
+    });
+
+    let doc = loader
+        .load_file("tests/data/http_fetcher/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5432));
+    assert_eq!(
+        doc["db"]["host"].as_string(),
+        Some(String::from("localhost"))
+    );
+    assert_eq!(
+        *fetched.lock().expect("during test"),
+        vec![String::from("https://config.example.com/db.conf")]
+    );
+
+    // the limits of the loader also apply to the documents it fetches
+    assert_eq!(
+        loader
+            .max_include_size(8)
+            .load_file("tests/data/http_fetcher/main.conf")
+            .err(),
+        Some(hocon::Error::ResourceLimit {
+            limit: String::from("'https://config.example.com/db.conf' larger than 8 bytes")
+        })
+    );
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_requests() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    let requests = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for mut stream in server.incoming().flatten().take(2) {
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            // the server is unavailable for the first request
+            let (status, body) = match requests.len() {
+                0 => ("503 Service Unavailable", ""),
+                _ => ("200 OK", "app.name = remote"),
+            };
+            requests.push(String::from_utf8_lossy(&request).to_lowercase());
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+        requests
+    });
+
+    let doc = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url)])
+        .url_requests_with(
+            hocon::UrlRequests::new()
+                .retries(2, std::time::Duration::from_millis(10))
+                .header("X-Environment", "staging")
+                .bearer_auth("secret"),
+        )
+        .load_file("tests/data/url_requests/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("remote")));
+
+    let requests = requests.join().expect("during test");
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        assert!(request.contains("x-environment: staging\r\n"));
+        assert!(request.contains("authorization: bearer secret\r\n"));
+    }
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_cache() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    let requests = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for mut stream in server.incoming().flatten().take(2) {
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request).to_lowercase();
+            if request.contains("if-none-match: \"v1\"\r\n") {
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                );
+            } else {
+                let body = "app.name = remote";
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+            requests.push(request);
+        }
+        requests
+    });
+
+    let cache = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("url_cache");
+    let _ = std::fs::remove_dir_all(&cache);
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url)])
+        .url_requests_with(hocon::UrlRequests::new().cache_dir(&cache));
+    for _ in 0..2 {
+        let doc = loader
+            .load_file("tests/data/url_requests/main.conf")
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+        assert_eq!(doc["app"]["name"].as_string(), Some(String::from("remote")));
+    }
+
+    // the second load reused the cached document
+    let requests = requests.join().expect("during test");
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains("if-none-match: \"v1\"\r\n"));
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_proxy() {
+    use std::io::{Read, Write};
+
+    let proxy = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let proxy_url = format!("http://{}", proxy.local_addr().expect("during test"));
+    let requests = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for mut stream in proxy.incoming().flatten().take(2) {
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            let body = "app.name = proxied";
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            requests.push(String::from_utf8_lossy(&request).into_owned());
+        }
+        requests
+    });
+
+    // the proxy is read from the environment of the loader, or set on it
+    for loader in [
+        hocon::HoconLoader::new().with_env_source([
+            ("BASE_URL", "http://config.invalid"),
+            ("HTTP_PROXY", proxy_url.as_str()),
+        ]),
+        hocon::HoconLoader::new()
+            .with_env_source([("BASE_URL", "http://config.invalid")])
+            .url_requests_with(hocon::UrlRequests::new().proxy(proxy_url.as_str())),
+    ] {
+        let doc = loader
+            .include_substitutions()
+            .load_file("tests/data/url_requests/main.conf")
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+        assert_eq!(
+            doc["app"]["name"].as_string(),
+            Some(String::from("proxied"))
+        );
+    }
+
+    for request in requests.join().expect("during test") {
+        assert!(request.starts_with("GET http://config.invalid/app.conf HTTP/1.1\r\n"));
+    }
+}
+
+#[test]
+fn url_filter() {
+    let fetched = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let loader = hocon::HoconLoader::new()
+        .http_fetcher({
+            let fetched = fetched.clone();
+            move |_: &str| {
+                fetched.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Ok(String::from("user-data = leaked"))
+            }
+        })
+        .url_filter(hocon::UrlFilter::new().deny_hosts(["169.254.169.254"]));
+
+    assert_eq!(
+        loader
+            .strict()
+            .load_file("tests/data/url_filter/main.conf")
+            .err(),
+        Some(hocon::Error::UrlNotAllowed {
+            url: String::from("http://169.254.169.254/latest/user-data")
+        })
+    );
+    let doc = loader
+        .load_file("tests/data/url_filter/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("local")));
+    assert_eq!(doc["user-data"].as_string(), None);
+    assert_eq!(fetched.load(std::sync::atomic::Ordering::Relaxed), 0);
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn offline_url_cache() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    let served = std::thread::spawn(move || {
+        // the server answers once, without validators
+        if let Some(mut stream) = server.incoming().flatten().next() {
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            let body = "app.name = remote";
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+
+    let cache = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("offline_url_cache");
+    let _ = std::fs::remove_dir_all(&cache);
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url.as_str())]);
+    let online = loader.url_requests_with(hocon::UrlRequests::new().cache_dir(&cache));
+    let offline = loader.url_requests_with(hocon::UrlRequests::new().cache_dir(&cache).offline());
+
+    // nothing is cached yet
+    assert_eq!(
+        offline.load_file("tests/data/url_requests/main.conf").err(),
+        Some(hocon::Error::Include {
+            path: format!("{base_url}/app.conf")
+        })
+    );
+    for loader in [online, offline] {
+        let doc = loader
+            .load_file("tests/data/url_requests/main.conf")
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+        assert_eq!(doc["app"]["name"].as_string(), Some(String::from("remote")));
+    }
+    served.join().expect("during test");
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn url_redirects() {
+    use std::io::{Read, Write};
+
+    // answer each request with `respond`, given the other server
+    let serve = |server: std::net::TcpListener, respond: fn(&str) -> String, other: String| {
+        std::thread::spawn(move || {
+            for mut stream in server.incoming().flatten() {
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                let _ = stream.write_all(respond(&other).as_bytes());
+            }
+        });
+    };
+    let origin = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let mirror = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let origin_url = format!("http://{}", origin.local_addr().expect("during test"));
+    let mirror_url = format!("http://{}", mirror.local_addr().expect("during test"));
+    serve(
+        origin,
+        |mirror| {
+            format!(
+                "HTTP/1.1 302 Found\r\nLocation: {mirror}/app.conf\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+        },
+        mirror_url,
+    );
+    serve(
+        mirror,
+        |_| {
+            String::from(
+                "HTTP/1.1 200 OK\r\nContent-Length: 17\r\nConnection: close\r\n\r\napp.name = mirror",
+            )
+        },
+        String::new(),
+    );
+
+    let loader = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", origin_url.as_str())]);
+    let doc = loader
+        .load_file("tests/data/url_requests/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("mirror")));
+
+    for requests in [
+        hocon::UrlRequests::new().same_origin_redirects(),
+        hocon::UrlRequests::new().max_redirects(0),
+    ] {
+        assert_eq!(
+            loader
+                .url_requests_with(requests)
+                .load_file("tests/data/url_requests/main.conf")
+                .err(),
+            Some(hocon::Error::Include {
+                path: format!("{origin_url}/app.conf")
+            })
+        );
+    }
+}
+
+#[cfg(feature = "url-support")]
+#[test]
+fn reqwest_client() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let base_url = format!("http://{}", server.local_addr().expect("during test"));
+    let request = std::thread::spawn(move || {
+        let mut stream = server.incoming().flatten().next().expect("during test");
+        let mut request = vec![];
+        let mut buffer = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => request.extend_from_slice(&buffer[..read]),
+            }
+        }
+        let body = "app.name = remote";
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        String::from_utf8_lossy(&request).to_lowercase()
+    });
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("hocon-test")
+        .build()
+        .expect("during test");
+    let doc = hocon::HoconLoader::new()
+        .include_substitutions()
+        .with_env_source([("BASE_URL", base_url)])
+        .reqwest_client(client)
+        .url_requests_with(hocon::UrlRequests::new().header("X-Environment", "staging"))
+        .load_file("tests/data/url_requests/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("remote")));
+
+    // the requests are made with the client, and still configured by the loader
+    let request = request.join().expect("during test");
+    assert!(request.contains("user-agent: hocon-test\r\n"));
+    assert!(request.contains("x-environment: staging\r\n"));
+}
+
+#[test]
+fn load_reader() {
+    // a reader giving its content in several parts
+    let reader = std::io::Read::chain("a.b = 1\n".as_bytes(), "a.c = ${a.b}".as_bytes());
+    let document = hocon::HoconLoader::new()
+        .trace()
+        .load_reader(reader, hocon::Format::Hocon)
+        .expect("during test")
+        .document()
+        .expect("during test");
+    assert_eq!(document.hocon()["a"]["c"].as_i64(), Some(1));
+    assert_eq!(
+        document.override_chain("a.b"),
+        vec![hocon::document::Source::Reader]
+    );
+
+    let invalid: &[u8] = &[b'a', b'=', 0xff];
+    assert!(matches!(
+        hocon::HoconLoader::new().load_reader(invalid, hocon::Format::Json),
+        Err(hocon::Error::Encoding { .. })
+    ));
+}
+
+#[test]
+fn decode_files() {
+    // main.conf is UTF-8 with a byte order mark, and includes a UTF-16 file
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/encoding/main.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["app"]["name"].as_string(), Some(String::from("café")));
+    assert_eq!(doc["app"]["port"].as_i64(), Some(8080));
+    assert_eq!(doc["app"]["owner"].as_string(), Some(String::from("Zoë")));
+
+    let bytes: Vec<u8> = "\u{feff}{ \"a\": 1 }"
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect();
+    let doc = hocon::HoconLoader::new()
+        .load_bytes_as(&bytes, hocon::Format::Json)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_i64(), Some(1));
+
+    assert!(matches!(
+        hocon::HoconLoader::new().load_file("tests/data/encoding/invalid.conf"),
+        Err(hocon::Error::Encoding { document, message })
+            if document.ends_with("invalid.conf") && message == "invalid UTF-8 at byte 12"
+    ));
+}
+
+#[test]
+fn properties_encoding() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/encoding/latin1.properties")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["city"].as_string(), Some(String::from("Zürich")));
+
+    // read as ISO-8859-1, each byte of UTF-8 characters is a character
+    let loader = hocon::HoconLoader::new();
+    let doc = loader
+        .load_file("tests/data/encoding/utf8.properties")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["city"].as_string(), Some(String::from("ZÃ¼rich")));
+    let doc = loader
+        .encoding(hocon::Format::Properties, hocon::Encoding::Utf8)
+        .load_file("tests/data/encoding/utf8.properties")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["city"].as_string(), Some(String::from("Zürich")));
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn load_user_config() {
+    let doc = hocon::HoconLoader::new()
+        .with_env_source([
+            (
+                "XDG_CONFIG_DIRS",
+                "tests/data/user_config/extra:tests/data/user_config/system",
+            ),
+            ("XDG_CONFIG_HOME", "tests/data/user_config/home"),
+        ])
+        .load_user_config("mytool")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["color"].as_bool(), Some(false));
+    // the first of the system-wide directories wins over the others
+    assert_eq!(doc["pager"].as_string(), Some(String::from("more")));
+
+    // without configuration, nothing is loaded
+    let doc = hocon::HoconLoader::new()
+        .with_env_source([("HOME", "tests/data/user_config/missing")])
+        .load_user_config("mytool")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc, hocon::Hocon::Hash(Default::default()));
+}
+
+#[test]
+fn load_key_per_file() {
+    let document = hocon::HoconLoader::new()
+        .trace()
+        .load_key_per_file("tests/data/key_per_file")
+        .expect("during test")
+        .document()
+        .expect("during test");
+    let doc = document.hocon();
+    assert_eq!(doc["log.level"].as_string(), Some(String::from("debug")));
+    assert_eq!(doc["log"], hocon::Hocon::BadValue(hocon::Error::MissingKey));
+    assert_eq!(doc["db_password"].as_string(), Some(String::from("s3cr3t")));
+    assert_eq!(doc["http"]["port"].as_i64(), Some(8080));
+    assert_eq!(
+        doc["subdir"],
+        hocon::Hocon::BadValue(hocon::Error::MissingKey)
+    );
+    assert!(matches!(
+        document.override_chain("db_password").as_slice(),
+        [hocon::document::Source::File(path)] if path.ends_with("key_per_file/db_password")
+    ));
+}
+
+#[test]
+fn load_remote() {
+    let store = |prefix: &str| match prefix {
+        "config/app" => Ok(std::collections::HashMap::from([
+            (String::from("db.host"), String::from("db.internal")),
+            (String::from("db.pool.size"), String::from("20")),
+        ])),
+        _ => Err(hocon::Error::Io {
+            message: String::from("unavailable"),
+        }),
+    };
+    let document = hocon::HoconLoader::new()
+        .trace()
+        .load_str(r#"{ db { host: localhost, port: 5432, pool.size: 5 } }"#)
+        .expect("during test")
+        .load_remote(&store, "config/app")
+        .expect("during test")
+        .document()
+        .expect("during test");
+    let doc = document.hocon();
+    assert_eq!(
+        doc["db"]["host"].as_string(),
+        Some(String::from("db.internal"))
+    );
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5432));
+    assert_eq!(doc["db"]["pool"]["size"].as_i64(), Some(20));
+    assert_eq!(
+        document.override_chain("db.host"),
+        vec![
+            hocon::document::Source::Str,
+            hocon::document::Source::Remote(String::from("config/app"))
+        ]
+    );
+
+    assert!(matches!(
+        hocon::HoconLoader::new().load_remote(&store, "config/other"),
+        Err(hocon::Error::Io { .. })
+    ));
+}
+
+#[cfg(feature = "consul")]
+#[test]
+fn load_consul() {
+    use std::io::{Read, Write};
+
+    let server = std::net::TcpListener::bind("127.0.0.1:0").expect("during test");
+    let address = format!("http://{}", server.local_addr().expect("during test"));
+    let request = std::thread::spawn(move || {
+        let (mut stream, _) = server.accept().expect("during test");
+        let mut request = vec![];
+        let mut buffer = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let read = stream.read(&mut buffer).expect("during test");
+            request.extend_from_slice(&buffer[..read]);
+        }
+        // `db` is a folder, `db/port` is `5433` and `name` is `consul-app`
+        let body = r#"[
+            { "Key": "config/app/db/", "Value": null },
+            { "Key": "config/app/db/port", "Value": "NTQzMw==" },
+            { "Key": "config/app/name", "Value": "Y29uc3VsLWFwcA==" }
+        ]"#;
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        String::from_utf8_lossy(&request).to_lowercase()
+    });
+
+    let doc = hocon::HoconLoader::new()
+        .load_remote(
+            &hocon::ConsulSource::new(&address).token("secret"),
+            "config/app",
+        )
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["db"]["port"].as_i64(), Some(5433));
+    assert_eq!(doc["name"].as_string(), Some(String::from("consul-app")));
+
+    let request = request.join().expect("during test");
+    assert!(request.starts_with("get /v1/kv/config/app?recurse "));
+    assert!(request.contains("x-consul-token: secret\r\n"));
+}
+
+#[test]
+fn load_dotenv() {
+    let loader = hocon::HoconLoader::new()
+        .with_env_source([("HOME", "/home/user")])
+        .load_dotenv("tests/data/dotenv/.env")
+        .expect("during test");
+    let doc = loader
+        .clone()
+        .load_str(r#"{ server { port: 80 }, home: ${HOME}, db: ${DB_HOST} }"#)
+        .expect("during test")
+        .env_overrides("MYAPP")
+        .hocon()
+        .expect("during test");
+    // the variables of the `.env` file are read as environment variables
+    assert_eq!(doc["server"]["port"].as_i64(), Some(9000));
+    assert_eq!(doc["db"].as_string(), Some(String::from("db.local")));
+    // and they are a document
+    assert_eq!(doc["DB_HOST"].as_string(), Some(String::from("db.local")));
+    // unless they are set in the environment
+    assert_eq!(doc["home"].as_string(), Some(String::from("/home/user")));
+    assert_eq!(doc["HOME"].as_string(), Some(String::from("/home/user")));
+
+    assert!(matches!(
+        hocon::HoconLoader::new().load_dotenv("tests/data/dotenv/missing.env"),
+        Err(hocon::Error::File { .. })
+    ));
+}
+
+#[test]
+fn load_strict_json() {
+    let doc = hocon::HoconLoader::new()
+        .strict_json()
+        .load_file("tests/data/strict_json/valid.json")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["http"]["port"].as_i64(), Some(8080));
+
+    let invalid = hocon::Error::InvalidJson {
+        line: 3,
+        column: 5,
+        message: String::from("comments are not JSON"),
+    };
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .strict_json()
+            .load_file("tests/data/strict_json/comments.json")
+            .err(),
+        Some(invalid.clone())
+    );
+    // also when the JSON file is included
+    assert_eq!(
+        hocon::HoconLoader::new()
+            .strict_json()
+            .load_file("tests/data/strict_json/include.conf")
+            .err(),
+        Some(invalid)
+    );
+    // HOCON files are still HOCON
+    assert!(
+        hocon::HoconLoader::new()
+            .load_file("tests/data/strict_json/comments.json")
+            .is_ok()
+    );
+}
+
+#[test]
+fn load_json5() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/json5/service.json5")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["service"]["name"].as_string(),
+        Some(String::from("billing"))
+    );
+    assert_eq!(doc["service"]["port"].as_i64(), Some(8080));
+    assert_eq!(doc["service"]["ratio"].as_f64(), Some(0.75));
+    assert_eq!(
+        doc["service"]["hosts"][1].as_string(),
+        Some(String::from("b.local"))
+    );
+
+    // included from HOCON
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/json5/application.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["service"]["name"].as_string(),
+        Some(String::from("billing"))
+    );
+    assert_eq!(doc["service"]["port"].as_i64(), Some(9000));
+
+    let doc = hocon::HoconLoader::new()
+        .load_bytes_as(b"{ a: 'b', }", hocon::Format::Json5)
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["a"].as_string(), Some(String::from("b")));
+}
+
+#[cfg(feature = "yaml-support")]
+#[test]
+fn load_yaml() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/yaml/service.yaml")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["service"]["name"].as_string(),
+        Some(String::from("billing"))
+    );
+    assert_eq!(doc["service"]["port"].as_i64(), Some(8080));
+
+    // included and overridden from HOCON
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/yaml/application.conf")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(doc["service"]["port"].as_i64(), Some(9000));
+    assert_eq!(
+        doc["service"]["url"].as_string(),
+        Some(String::from("http://a.local:9000"))
+    );
+}
+
+#[cfg(feature = "toml-support")]
+#[test]
+fn load_toml() {
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/toml/service.toml")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["service"]["name"].as_string(),
+        Some(String::from("billing"))
+    );
+    assert_eq!(
+        doc["service"]["started"].as_string(),
+        Some(String::from("2024-01-15"))
+    );
+    assert_eq!(
+        doc["service"]["backends"][1]["host"].as_string(),
+        Some(String::from("b.local"))
+    );
+
+    // included and overridden from HOCON
+    let doc = hocon::HoconLoader::new()
+        .load_file("tests/data/toml/application.conf")
+        .expect("during test")
+        .load_file("tests/data/toml/empty.toml")
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+    assert_eq!(
+        doc["service"]["name"].as_string(),
+        Some(String::from("billing"))
+    );
+    assert_eq!(doc["service"]["port"].as_i64(), Some(9000));
+}
+
+#[test]
+fn render_with_origin_comments() {
+    let document = hocon::HoconLoader::new()
+        .no_system()
+        .trace()
+        .load_file("tests/data/layers/application.conf")
+        .expect("during test")
+        .load_args(vec!["http.host=example.org"])
+        .expect("during test")
+        .with_reference("tests/data/layers/reference.conf")
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    let rendered = document
+        .to_hocon_string(hocon::RenderOptions {
+            origin_comments: true,
+            ..hocon::RenderOptions::default()
+        })
+        .expect("during test");
+    let data = std::fs::canonicalize("tests/data/layers").expect("during test");
+    assert_eq!(
+        rendered.replace(&format!("{}/", data.display()), ""),
+        r#"http {
+  # from arguments, overrides file 'reference.conf'
+  host = example.org
+  # from file 'application.conf', overrides file 'reference.conf'
+  port = 8080
+  # from file 'reference.conf'
+  url = "http://example.org:8080"
+}
+# from file 'application.conf', overrides file 'reference.conf'
+workers = "4 x"
+"#
+    );
+    // the same document without tracing has no comments
+    assert!(
+        !hocon::HoconLoader::new()
+            .no_system()
+            .load_file("tests/data/layers/application.conf")
+            .and_then(hocon::HoconLoader::document)
+            .and_then(|document| document.to_hocon_string(hocon::RenderOptions {
+                origin_comments: true,
+                ..hocon::RenderOptions::default()
+            }))
+            .expect("during test")
+            .contains('#')
+    );
+}
+
+#[test]
+fn keep_comments_of_loaded_documents() {
+    let document = hocon::HoconLoader::new()
+        .no_system()
+        .keep_comments()
+        .load_file("tests/data/comments/application.conf")
+        .expect("during test")
+        .load_str("# from the command line\nhttp.port = 9000")
+        .expect("during test")
+        .document()
+        .expect("during test");
+
+    assert_eq!(
+        document.comments("http.host"),
+        Some(&hocon::document::Comments {
+            leading: vec![],
+            trailing: Some(String::from(" bound to every interface in production")),
+        })
+    );
+    assert_eq!(document.comments("missing"), None);
+    assert_eq!(
+        document
+            .to_hocon_string(hocon::RenderOptions::default())
+            .expect("during test"),
+        r#"# settings of the HTTP server
+http {
+  host = localhost # bound to every interface in production
+  # from the command line
+  port = 9000
+}
+# the tags, in order
+tags = [a, b]
+"#
+    );
+    assert!(
+        !document
+            .to_hocon_string(hocon::RenderOptions {
+                comments: false,
+                ..hocon::RenderOptions::default()
+            })
+            .expect("during test")
+            .contains('#')
+    );
+}
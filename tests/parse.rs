@@ -86,6 +86,19 @@ fn parse_string_strict_with_windows_newline() {
     );
 }
 
+#[test]
+fn parse_string_with_windows_and_old_mac_newlines() {
+    let s = "a = 1\r\nb = \"\"\"first\r\nsecond\"\"\"\rc = x // no newline at the end";
+    let doc: Hocon = dbg!(HoconLoader::new().strict().load_str(dbg!(s)))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["a"].as_i64().expect("during test"), 1);
+    assert_eq!(doc["b"].as_string().expect("during test"), "first\nsecond");
+    assert_eq!(doc["c"].as_string().expect("during test"), "x");
+}
+
 #[test]
 fn parse_int() {
     let s = r#"{"a":5}"#;
@@ -425,6 +438,28 @@ fn parse_substitute_array() {
     assert_eq!(doc["b"][0][1].as_i64().expect("during test"), 2);
 }
 
+#[test]
+fn parse_substitute_array_element() {
+    let s = r#"{
+        servers = [{ host: a }, { host: b }]
+        servers += { host: c }
+        first = ${servers.0.host}
+        second = ${servers[1].host}
+        last = ${servers[2]}
+        matrix = [[1, 2], [3]]
+        cell = ${matrix[0][1]}
+    }"#;
+    let doc: Hocon = dbg!(HoconLoader::new().load_str(dbg!(s)))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["first"].as_string(), Some(String::from("a")));
+    assert_eq!(doc["second"].as_string(), Some(String::from("b")));
+    assert_eq!(doc["last"]["host"].as_string(), Some(String::from("c")));
+    assert_eq!(doc["cell"].as_i64(), Some(2));
+}
+
 #[test]
 fn parse_empty_objects() {
     let s = r#"a={b{}},b=5"#;
@@ -460,7 +495,92 @@ fn parse_missing_optional_substitution() {
         .hocon()
         .expect("during test");
 
-    assert_eq!(doc["a"]["c"], Hocon::Null);
+    assert_eq!(doc["a"], Hocon::Hash(LinkedHashMap::new()));
+}
+
+#[test]
+fn parse_missing_optional_substitution_in_array_and_object() {
+    let s = r#"{
+        a = [${?b}, 1, 2]
+        c = [1, ${?b}, 2]
+        d = [1, 2, ${?b}]
+        e = [${?b}]
+        f = { g: 1, h: ${?b} }
+        i.j = ${?b}
+        k = ${?b}
+        l = 1
+        l = ${?b}
+    }"#;
+    for loader in [HoconLoader::new(), HoconLoader::new().no_system()] {
+        let doc: Hocon = dbg!(loader.load_str(dbg!(s)))
+            .expect("during test")
+            .hocon()
+            .expect("during test");
+
+        let expected = Hocon::Array(vec![Hocon::Integer(1), Hocon::Integer(2)]);
+        assert_eq!(doc["a"], expected);
+        assert_eq!(doc["c"], expected);
+        assert_eq!(doc["d"], expected);
+        assert_eq!(doc["e"], Hocon::Array(vec![]));
+        let mut expected = LinkedHashMap::new();
+        expected.insert(String::from("g"), Hocon::Integer(1));
+        assert_eq!(doc["f"], Hocon::Hash(expected));
+        assert_eq!(doc["i"], Hocon::Hash(LinkedHashMap::new()));
+        assert_eq!(doc["k"], Hocon::BadValue(Error::MissingKey));
+        assert_eq!(doc["l"].as_i64().expect("during test"), 1);
+    }
+}
+
+#[test]
+fn parse_missing_optional_substitution_in_concatenation() {
+    let s = r#"{
+        a = [1, 2] ${?b}
+        c = [1] ${?b} [2]
+        d = ${?b} [1, 2]
+        e = { f: 1 } ${?b} { g: 2 }
+        h = {} ${?b}
+    }"#;
+    let doc: Hocon = dbg!(HoconLoader::new().no_system().load_str(dbg!(s)))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(doc["a"], doc["c"]);
+    assert_eq!(doc["a"], doc["d"]);
+    assert_eq!(doc["a"][1].as_i64().expect("during test"), 2);
+    assert_eq!(doc["e"]["f"].as_i64().expect("during test"), 1);
+    assert_eq!(doc["e"]["g"].as_i64().expect("during test"), 2);
+    assert_eq!(doc["h"], Hocon::Hash(LinkedHashMap::new()));
+}
+
+#[test]
+fn parse_substitution_in_concatenation() {
+    let s = r#"{
+        a = [2, 3]
+        b = [1] ${a} [4] ${c}
+        c = [5]
+        d = { e: 1, f { g: 1 } }
+        h = { e: 0, i: 0 } ${d} { i: 1 }
+        j = [1] ${d}
+    }"#;
+    let doc: Hocon = dbg!(HoconLoader::new().load_str(dbg!(s)))
+        .expect("during test")
+        .hocon()
+        .expect("during test");
+
+    assert_eq!(
+        doc["b"],
+        Hocon::Array((1..=5).map(Hocon::Integer).collect())
+    );
+    assert_eq!(doc["h"]["e"].as_i64().expect("during test"), 1);
+    assert_eq!(doc["h"]["f"]["g"].as_i64().expect("during test"), 1);
+    assert_eq!(doc["h"]["i"].as_i64().expect("during test"), 1);
+    assert_eq!(
+        doc["j"][1],
+        Hocon::BadValue(Error::InvalidConcatenation {
+            path: String::from("d")
+        })
+    );
 }
 
 #[test]